@@ -7,10 +7,15 @@ use solana_program::{
 };
 use solana_readonly_account::ReadonlyAccountData;
 
+pub const NONCE_VERSIONS_LEGACY_DISCM: [u8; 4] = 0u32.to_le_bytes();
+pub const NONCE_VERSIONS_CURRENT_DISCM: [u8; 4] = 1u32.to_le_bytes();
+
 pub const NONCE_STATE_UNINITIALIZED_DISCM: [u8; 4] = 0u32.to_le_bytes();
 pub const NONCE_STATE_INITIALIZED_DISCM: [u8; 4] = 1u32.to_le_bytes();
 
-pub const NONCE_DISCM_OFFSET: usize = 0;
+/// Nonce accounts store a [`nonce::state::Versions`], which wraps the [`nonce::State`]
+pub const NONCE_VERSIONS_DISCM_OFFSET: usize = 0;
+pub const NONCE_DISCM_OFFSET: usize = NONCE_VERSIONS_DISCM_OFFSET + 4;
 pub const NONCE_DATA_OFFSET: usize = NONCE_DISCM_OFFSET + 4;
 pub const NONCE_DATA_AUTHORITY_OFFSET: usize = NONCE_DATA_OFFSET;
 pub const NONCE_DATA_DURABLE_NONCE_OFFSET: usize = NONCE_DATA_AUTHORITY_OFFSET + PUBKEY_BYTES;
@@ -57,6 +62,15 @@ impl<T: ReadonlyAccountData> ReadonlyNonceAccount<T> {
         if d.len() != nonce::State::size() {
             return false;
         }
+        let v: &[u8; 4] = d[NONCE_VERSIONS_DISCM_OFFSET..NONCE_VERSIONS_DISCM_OFFSET + 4]
+            .try_into()
+            .unwrap();
+        if !matches!(
+            *v,
+            NONCE_VERSIONS_LEGACY_DISCM | NONCE_VERSIONS_CURRENT_DISCM
+        ) {
+            return false;
+        }
         let b: &[u8; 4] = d[NONCE_DISCM_OFFSET..NONCE_DISCM_OFFSET + 4]
             .try_into()
             .unwrap();
@@ -222,8 +236,10 @@ impl TryFrom<[u8; 4]> for NonceStateMarker {
 mod tests {
     use proptest::prelude::*;
     use sanctum_solana_test_utils::proptest_utils::nonce;
-    use solana_program::nonce;
+    use solana_program::{nonce, rent::Rent, system_instruction};
+    use solana_program_test::ProgramTest;
     use solana_readonly_account::ReadonlyAccountData;
+    use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
 
     use super::*;
 
@@ -243,7 +259,7 @@ mod tests {
         #[test]
         fn nonce_readonly_matches_full_deser_invalid(data: [u8; nonce::State::size()]) {
             let account = ReadonlyNonceAccount(AccountData(&data));
-            let unpack_res = bincode::deserialize::<nonce::State>(&data);
+            let unpack_res = bincode::deserialize::<nonce::state::Versions>(&data);
             if !account.nonce_data_is_valid() {
                 prop_assert!(unpack_res.is_err());
             }
@@ -254,7 +270,11 @@ mod tests {
         #[test]
         fn nonce_readonly_matches_full_deser_valid(nonce in nonce()) {
             let mut data = vec![0u8; nonce::State::size()];
-            bincode::serialize_into(data.as_mut_slice(), &nonce).unwrap();
+            bincode::serialize_into(
+                data.as_mut_slice(),
+                &nonce::state::Versions::new(nonce.clone()),
+            )
+            .unwrap();
             let account = ReadonlyNonceAccount(AccountData(&data));
             prop_assert!(account.nonce_data_is_valid());
             let account = account.try_into_valid().unwrap();
@@ -273,4 +293,47 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn nonce_readonly_reads_system_program_nonce_account() {
+        let nonce_account = Keypair::new();
+        let authority = Pubkey::new_unique();
+        let (mut bc, payer, rbh) = ProgramTest::default().start().await;
+        let tx = Transaction::new_signed_with_payer(
+            &system_instruction::create_nonce_account(
+                &payer.pubkey(),
+                &nonce_account.pubkey(),
+                &authority,
+                Rent::default().minimum_balance(nonce::State::size()),
+            ),
+            Some(&payer.pubkey()),
+            &[&payer, &nonce_account],
+            rbh,
+        );
+        bc.process_transaction(tx).await.unwrap();
+        let data = bc
+            .get_account(nonce_account.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+
+        let nonce::state::Versions::Current(state) = bincode::deserialize(&data).unwrap() else {
+            panic!("expected current nonce version");
+        };
+        let nonce::State::Initialized(expected) = *state else {
+            panic!("expected initialized nonce");
+        };
+        let account = ReadonlyNonceAccount(AccountData(&data))
+            .try_into_valid()
+            .unwrap()
+            .try_into_initialized()
+            .unwrap();
+        assert_eq!(account.nonce_data_authority(), authority);
+        assert_eq!(
+            account.nonce_data_durable_nonce(),
+            *expected.durable_nonce.as_hash()
+        );
+        assert_eq!(account.nonce_data_fee_calculator(), expected.fee_calculator);
+    }
 }
@@ -1,9 +1,11 @@
 mod pubkey_src;
 mod signer;
+mod signer_signature;
 mod token_amt;
 mod token_amt_or_all;
 
 pub use pubkey_src::*;
 pub use signer::*;
+pub use signer_signature::*;
 pub use token_amt::*;
 pub use token_amt_or_all::*;
@@ -0,0 +1,62 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature, signer::presigner::Presigner};
+
+/// A `pubkey=signature` pair, as output by [`crate::TxSendMode::SignOnly`]
+/// and the `solana` CLI's `--sign-only`, and accepted by its `--signer` args
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct SignerSignature {
+    pub pubkey: Pubkey,
+    pub signature: Signature,
+}
+
+impl SignerSignature {
+    pub fn presigner(&self) -> Presigner {
+        Presigner::new(&self.pubkey, &self.signature)
+    }
+}
+
+impl Display for SignerSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.pubkey, self.signature)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SignerSignatureParseError;
+
+impl Display for SignerSignatureParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Expecting a base58 pubkey and signature pair in the form of PUBKEY=SIGNATURE")
+    }
+}
+
+impl Error for SignerSignatureParseError {}
+
+impl FromStr for SignerSignature {
+    type Err = SignerSignatureParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (pubkey, signature) = s.split_once('=').ok_or(SignerSignatureParseError)?;
+        Ok(Self {
+            pubkey: pubkey.parse().map_err(|_| SignerSignatureParseError)?,
+            signature: signature.parse().map_err(|_| SignerSignatureParseError)?,
+        })
+    }
+}
+
+impl From<SignerSignature> for String {
+    fn from(value: SignerSignature) -> Self {
+        value.to_string()
+    }
+}
+
+impl TryFrom<String> for SignerSignature {
+    type Error = SignerSignatureParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
@@ -0,0 +1,139 @@
+use sanctum_solana_client_utils::{ComputeBudgetFeeLimit, ComputeBudgetIxs, SortedSigners};
+use solana_rpc_client_api::client_error::{Error as ClientError, ErrorKind as ClientErrorKind};
+use solana_sdk::{
+    address_lookup_table::AddressLookupTableAccount,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signer::Signer,
+    transaction::VersionedTransaction,
+};
+
+use crate::{sign_only_versioned_tx, NonceArgs};
+
+pub const DEFAULT_PRIORITY_FEE_LIMIT_LAMPORTS: u64 = 1_000_000;
+
+pub const DEFAULT_CU_BUFFER_RATIO: f64 = 1.1;
+
+/// Max compute unit limit of a tx, used for the placeholder
+/// compute budget instructions simulated for compute unit estimation
+const EST_CU_SIM_CU_LIMIT: u32 = 1_400_000;
+
+/// Args for sizing the compute budget of txs built by
+/// [`crate::TxSendingRpcClient::build_and_handle_tx`].
+///
+/// The priority fee used is the lower of the slot weighted median of recent prioritization fees
+/// and the fee limit, which defaults to [`DEFAULT_PRIORITY_FEE_LIMIT_LAMPORTS`] total lamports
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "clap", derive(clap::Args))]
+pub struct ComputeBudgetArgs {
+    /// Max total lamports to pay in priority fees for each transaction
+    #[cfg_attr(
+        feature = "clap",
+        arg(long, conflicts_with = "fee_limit_micro_lamports_per_cu")
+    )]
+    pub fee_limit_lamports: Option<u64>,
+
+    /// Max priority fee in micro-lamports per compute unit for each transaction
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub fee_limit_micro_lamports_per_cu: Option<u64>,
+
+    /// Multiple to multiply simulated compute units by to give some room for error. Should be >= 1.0
+    #[cfg_attr(feature = "clap", arg(long, default_value_t = DEFAULT_CU_BUFFER_RATIO))]
+    pub cu_buffer_ratio: f64,
+}
+
+impl Default for ComputeBudgetArgs {
+    fn default() -> Self {
+        Self {
+            fee_limit_lamports: None,
+            fee_limit_micro_lamports_per_cu: None,
+            cu_buffer_ratio: DEFAULT_CU_BUFFER_RATIO,
+        }
+    }
+}
+
+impl ComputeBudgetArgs {
+    /// `fee_limit_lamports` takes precedence if both are set
+    pub fn fee_limit(&self) -> ComputeBudgetFeeLimit {
+        match (
+            self.fee_limit_lamports,
+            self.fee_limit_micro_lamports_per_cu,
+        ) {
+            (Some(lamports), _) => ComputeBudgetFeeLimit::TotalLamports(lamports),
+            (None, Some(micro_lamports)) => {
+                ComputeBudgetFeeLimit::MicroLamportsPerCu(micro_lamports)
+            }
+            (None, None) => {
+                ComputeBudgetFeeLimit::TotalLamports(DEFAULT_PRIORITY_FEE_LIMIT_LAMPORTS)
+            }
+        }
+    }
+}
+
+/// Contents of a tx to build with [`crate::TxSendingRpcClient::build_and_handle_tx`]
+#[derive(Debug)]
+pub struct BuildTxArgs<'a, S: ?Sized> {
+    pub payer: Pubkey,
+
+    /// Excluding compute budget and advance nonce instructions, which are prepended
+    pub ixs: &'a [Instruction],
+
+    pub luts: &'a [AddressLookupTableAccount],
+
+    /// May be unsorted and contain duplicates.
+    ///
+    /// Use [`crate::PubkeySrc::Pubkey`] for signers that will sign elsewhere
+    /// with [`crate::TxSendMode::SignOnly`] or [`crate::TxSendMode::DumpMsg`]
+    pub signers: &'a [&'a S],
+
+    /// Use a durable nonce instead of a recent blockhash if set
+    pub nonce: Option<NonceArgs>,
+}
+
+impl<S: ?Sized> Clone for BuildTxArgs<'_, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S: ?Sized> Copy for BuildTxArgs<'_, S> {}
+
+impl<S: ?Sized> BuildTxArgs<'_, S> {
+    /// Instructions to simulate for compute unit estimation.
+    ///
+    /// Includes placeholder compute budget instructions since they consume compute units too
+    pub(crate) fn est_cu_ixs(&self) -> Vec<Instruction> {
+        self.final_ixs(ComputeBudgetIxs::new(EST_CU_SIM_CU_LIMIT, 0))
+    }
+
+    /// `AdvanceNonceAccount` must remain the first instruction
+    pub(crate) fn final_ixs(&self, compute_budget_ixs: ComputeBudgetIxs) -> Vec<Instruction> {
+        let ixs = compute_budget_ixs
+            .into_iter()
+            .chain(self.ixs.iter().cloned());
+        match self.nonce {
+            Some(nonce) => nonce.prepend_advance_nonce_ix(ixs),
+            None => ixs.collect(),
+        }
+    }
+}
+
+impl<S: Signer + ?Sized> BuildTxArgs<'_, S> {
+    /// Compiles the v0 message and signs it with deduplicated `signers`
+    pub(crate) fn sign(
+        &self,
+        ixs: &[Instruction],
+        blockhash: Hash,
+    ) -> Result<VersionedTransaction, ClientError> {
+        let message = VersionedMessage::V0(
+            v0::Message::try_compile(&self.payer, ixs, self.luts, blockhash)
+                .map_err(|e| ClientError::from(ClientErrorKind::Custom(e.to_string())))?,
+        );
+        let mut signers = self.signers.to_vec();
+        signers.sort_by_key(|s| s.pubkey());
+        signers.dedup_by_key(|s| s.pubkey());
+        Ok(sign_only_versioned_tx(message, &SortedSigners(&signers))?)
+    }
+}
@@ -0,0 +1,262 @@
+//! Durable nonce transactions, for transactions that need to outlive recent blockhashes
+//! e.g. long-running multisig approvals
+
+use std::time::{Duration, Instant};
+
+use sanctum_system_program_lib::{AdvanceNonceAccountFreeKeys, ReadonlyNonceAccount};
+use solana_client::{
+    rpc_client::SerializableTransaction, rpc_config::RpcSendTransactionConfig,
+    rpc_response::Response,
+};
+use solana_readonly_account::ReadonlyAccountData;
+use solana_rpc_client_api::client_error::{Error as ClientError, ErrorKind as ClientErrorKind};
+use solana_sdk::{
+    account::Account, commitment_config::CommitmentConfig, hash::Hash, instruction::Instruction,
+    program_error::ProgramError, pubkey::Pubkey, signature::Signature,
+};
+use solana_transaction_status::TransactionStatus;
+use system_program_interface::advance_nonce_account_ix;
+
+use crate::to_versioned_tx;
+
+/// Max time to wait for a durable nonce tx to be confirmed.
+///
+/// Durable nonce txs do not expire, so the usual last valid block height cutoff does not apply
+pub const DURABLE_NONCE_TX_CONFIRM_TIMEOUT: Duration = Duration::from_secs(120);
+
+pub const DURABLE_NONCE_TX_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Resend the tx every this many signature status polls
+const DURABLE_NONCE_TX_RESEND_INTERVAL_POLLS: u32 = 4;
+
+/// Nonce account to use in place of a recent blockhash
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NonceArgs {
+    pub nonce_account: Pubkey,
+    pub nonce_authority: Pubkey,
+}
+
+impl NonceArgs {
+    pub fn advance_nonce_ix(&self) -> Instruction {
+        advance_nonce_account_ix(
+            AdvanceNonceAccountFreeKeys {
+                nonce: self.nonce_account,
+                authority: self.nonce_authority,
+            }
+            .resolve(),
+        )
+    }
+
+    /// Durable nonce txs must have `AdvanceNonceAccount` as their first instruction
+    pub fn prepend_advance_nonce_ix(
+        &self,
+        ixs: impl IntoIterator<Item = Instruction>,
+    ) -> Vec<Instruction> {
+        std::iter::once(self.advance_nonce_ix())
+            .chain(ixs)
+            .collect()
+    }
+}
+
+/// Contents of an initialized nonce account
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DurableNonce {
+    pub authority: Pubkey,
+
+    /// The `recent_blockhash` to use for txs using this nonce account
+    pub durable_nonce: Hash,
+
+    pub lamports_per_signature: u64,
+}
+
+impl DurableNonce {
+    pub fn try_from_account<T: ReadonlyAccountData>(account: T) -> Result<Self, ProgramError> {
+        let n = ReadonlyNonceAccount(account)
+            .try_into_valid()?
+            .try_into_initialized()?;
+        Ok(Self {
+            authority: n.nonce_data_authority(),
+            durable_nonce: n.nonce_data_durable_nonce(),
+            lamports_per_signature: n.nonce_data_fee_calculator_lamports_per_signature(),
+        })
+    }
+}
+
+/// Returns the nonce account `tx` advances if it is a durable nonce tx
+pub fn durable_nonce_account_of<T: SerializableTransaction>(tx: &T) -> Option<Pubkey> {
+    if !tx.uses_durable_nonce() {
+        return None;
+    }
+    let tx = to_versioned_tx(tx);
+    let keys = tx.message.static_account_keys();
+    // AdvanceNonceAccount's first account is the nonce account
+    let i = *tx.message.instructions().first()?.accounts.first()?;
+    keys.get(usize::from(i)).copied()
+}
+
+fn custom_err(msg: String) -> ClientError {
+    ClientErrorKind::Custom(msg).into()
+}
+
+pub(crate) fn durable_nonce_from_fetched(
+    NonceArgs {
+        nonce_account,
+        nonce_authority,
+    }: &NonceArgs,
+    Response { value, .. }: Response<Option<Account>>,
+) -> Result<DurableNonce, ClientError> {
+    let account =
+        value.ok_or_else(|| custom_err(format!("Nonce account {nonce_account} not found")))?;
+    let nonce = DurableNonce::try_from_account(&account)
+        .map_err(|e| custom_err(format!("Invalid nonce account {nonce_account}: {e}")))?;
+    if nonce.authority != *nonce_authority {
+        return Err(custom_err(format!(
+            "Nonce account {nonce_account} authority is {}, not {nonce_authority}",
+            nonce.authority
+        )));
+    }
+    Ok(nonce)
+}
+
+enum DurableNonceTxPoll {
+    Confirmed,
+    /// landed but not yet at the required commitment
+    Pending,
+    NotFound,
+}
+
+fn durable_nonce_tx_poll(
+    status: Option<TransactionStatus>,
+    commitment: CommitmentConfig,
+) -> Result<DurableNonceTxPoll, ClientError> {
+    let Some(status) = status else {
+        return Ok(DurableNonceTxPoll::NotFound);
+    };
+    if let Some(err) = status.err.clone() {
+        return Err(err.into());
+    }
+    Ok(if status.satisfies_commitment(commitment) {
+        DurableNonceTxPoll::Confirmed
+    } else {
+        DurableNonceTxPoll::Pending
+    })
+}
+
+/// The tx can no longer land once its durable nonce has been advanced
+fn is_durable_nonce_advanced(account: Option<Account>, tx_nonce: &Hash) -> bool {
+    let nonce = account.and_then(|a| DurableNonce::try_from_account(&a).ok());
+    !matches!(nonce, Some(n) if n.durable_nonce == *tx_nonce)
+}
+
+fn durable_nonce_advanced_err(signature: &Signature) -> ClientError {
+    custom_err(format!(
+        "Durable nonce advanced without tx {signature} landing"
+    ))
+}
+
+fn durable_nonce_timeout_err(signature: &Signature) -> ClientError {
+    custom_err(format!(
+        "Durable nonce tx {signature} not confirmed after {DURABLE_NONCE_TX_CONFIRM_TIMEOUT:?}"
+    ))
+}
+
+/// Sends and confirms a durable nonce tx without checking for blockhash expiry.
+///
+/// Resends the tx until it is confirmed, fails, its nonce is advanced by another tx,
+/// or [`DURABLE_NONCE_TX_CONFIRM_TIMEOUT`] elapses.
+pub(crate) fn send_and_confirm_durable_nonce_tx<T: SerializableTransaction>(
+    client: &solana_client::rpc_client::RpcClient,
+    tx: &T,
+    nonce_account: &Pubkey,
+    commitment: CommitmentConfig,
+    config: RpcSendTransactionConfig,
+) -> Result<Signature, ClientError> {
+    let signature = client.send_transaction_with_config(tx, config)?;
+    let start = Instant::now();
+    let mut nonce_advanced = false;
+    let mut polls_until_resend = DURABLE_NONCE_TX_RESEND_INTERVAL_POLLS;
+    loop {
+        let status = client.get_signature_statuses(&[signature])?.value[0].take();
+        match durable_nonce_tx_poll(status, commitment)? {
+            DurableNonceTxPoll::Confirmed => return Ok(signature),
+            DurableNonceTxPoll::Pending => (),
+            DurableNonceTxPoll::NotFound => {
+                // check status once more after detecting advancement in case
+                // the tx itself advanced the nonce in between the 2 requests
+                if nonce_advanced {
+                    return Err(durable_nonce_advanced_err(&signature));
+                }
+                let account = client
+                    .get_account_with_commitment(nonce_account, CommitmentConfig::processed())?
+                    .value;
+                nonce_advanced = is_durable_nonce_advanced(account, tx.get_recent_blockhash());
+            }
+        }
+        if start.elapsed() > DURABLE_NONCE_TX_CONFIRM_TIMEOUT {
+            return Err(durable_nonce_timeout_err(&signature));
+        }
+        std::thread::sleep(DURABLE_NONCE_TX_POLL_INTERVAL);
+        polls_until_resend -= 1;
+        if polls_until_resend == 0 {
+            polls_until_resend = DURABLE_NONCE_TX_RESEND_INTERVAL_POLLS;
+            // errs if tx was already processed
+            let _ = client.send_transaction_with_config(
+                tx,
+                RpcSendTransactionConfig {
+                    skip_preflight: true,
+                    ..config
+                },
+            );
+        }
+    }
+}
+
+/// Nonblocking version of [`send_and_confirm_durable_nonce_tx`]
+pub(crate) async fn send_and_confirm_durable_nonce_tx_nonblocking<
+    T: SerializableTransaction + Sync,
+>(
+    client: &solana_client::nonblocking::rpc_client::RpcClient,
+    tx: &T,
+    nonce_account: &Pubkey,
+    commitment: CommitmentConfig,
+    config: RpcSendTransactionConfig,
+) -> Result<Signature, ClientError> {
+    let signature = client.send_transaction_with_config(tx, config).await?;
+    let start = Instant::now();
+    let mut nonce_advanced = false;
+    let mut polls_until_resend = DURABLE_NONCE_TX_RESEND_INTERVAL_POLLS;
+    loop {
+        let status = client.get_signature_statuses(&[signature]).await?.value[0].take();
+        match durable_nonce_tx_poll(status, commitment)? {
+            DurableNonceTxPoll::Confirmed => return Ok(signature),
+            DurableNonceTxPoll::Pending => (),
+            DurableNonceTxPoll::NotFound => {
+                if nonce_advanced {
+                    return Err(durable_nonce_advanced_err(&signature));
+                }
+                let account = client
+                    .get_account_with_commitment(nonce_account, CommitmentConfig::processed())
+                    .await?
+                    .value;
+                nonce_advanced = is_durable_nonce_advanced(account, tx.get_recent_blockhash());
+            }
+        }
+        if start.elapsed() > DURABLE_NONCE_TX_CONFIRM_TIMEOUT {
+            return Err(durable_nonce_timeout_err(&signature));
+        }
+        tokio::time::sleep(DURABLE_NONCE_TX_POLL_INTERVAL).await;
+        polls_until_resend -= 1;
+        if polls_until_resend == 0 {
+            polls_until_resend = DURABLE_NONCE_TX_RESEND_INTERVAL_POLLS;
+            let _ = client
+                .send_transaction_with_config(
+                    tx,
+                    RpcSendTransactionConfig {
+                        skip_preflight: true,
+                        ..config
+                    },
+                )
+                .await;
+        }
+    }
+}
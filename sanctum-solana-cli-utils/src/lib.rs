@@ -1,9 +1,17 @@
 mod argparse;
+mod build_tx;
 mod clap_helpers;
+mod durable_nonce;
+mod rebroadcast;
+mod sign_only;
 mod tx_send_mode;
 mod utils;
 
 pub use argparse::*;
+pub use build_tx::*;
 pub use clap_helpers::*;
+pub use durable_nonce::*;
+pub use rebroadcast::*;
+pub use sign_only::*;
 pub use tx_send_mode::*;
 pub use utils::*;
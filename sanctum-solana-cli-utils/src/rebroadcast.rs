@@ -0,0 +1,268 @@
+//! Client-side rebroadcasting of txs until they land or their blockhash expires,
+//! for when the RPC node's own retries drop txs e.g. during congestion
+
+use std::time::Duration;
+
+use solana_client::{rpc_client::SerializableTransaction, rpc_config::RpcSendTransactionConfig};
+use solana_rpc_client_api::client_error::{Error as ClientError, ErrorKind as ClientErrorKind};
+use solana_sdk::{
+    clock::Slot,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    signature::Signature,
+    transaction::TransactionError,
+};
+use solana_transaction_status::{TransactionStatus, UiTransactionEncoding};
+
+use crate::RecentBlockhash;
+
+pub const DEFAULT_REBROADCAST_RESEND_INTERVAL: Duration = Duration::from_secs(2);
+
+/// [`RebroadcastArgs::resend_interval`]s shorter than this are clamped to it
+/// so that the RPC is not spammed in a tight loop
+pub const MIN_REBROADCAST_RESEND_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RebroadcastArgs {
+    /// Time between resends of the same signed tx, also the signature status polling interval.
+    /// At least [`MIN_REBROADCAST_RESEND_INTERVAL`]
+    pub resend_interval: Duration,
+
+    /// Max number of times to sign the tx with a fresh blockhash and priority fees.
+    /// At least 1 attempt is always made.
+    ///
+    /// Only used by [`crate::TxSendingRpcClient::build_and_rebroadcast_tx`]
+    pub max_attempts: u32,
+
+    pub tx_cfm_commitment: CommitmentLevel,
+
+    /// Only applies to the first send of each attempt, resends always skip preflight
+    pub skip_preflight: bool,
+}
+
+impl Default for RebroadcastArgs {
+    fn default() -> Self {
+        Self {
+            resend_interval: DEFAULT_REBROADCAST_RESEND_INTERVAL,
+            max_attempts: 1,
+            tx_cfm_commitment: CommitmentLevel::Confirmed,
+            skip_preflight: false,
+        }
+    }
+}
+
+impl RebroadcastArgs {
+    pub const fn with_max_attempts(self, max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..self
+        }
+    }
+
+    fn resend_interval(&self) -> Duration {
+        self.resend_interval.max(MIN_REBROADCAST_RESEND_INTERVAL)
+    }
+
+    fn send_config(&self) -> RpcSendTransactionConfig {
+        RpcSendTransactionConfig {
+            skip_preflight: self.skip_preflight,
+            preflight_commitment: Some(CommitmentLevel::Confirmed),
+            encoding: Some(UiTransactionEncoding::Base64),
+            // rebroadcasting is done by us instead of the RPC node
+            max_retries: Some(0),
+            min_context_slot: None,
+        }
+    }
+
+    fn resend_config(&self) -> RpcSendTransactionConfig {
+        RpcSendTransactionConfig {
+            skip_preflight: true,
+            ..self.send_config()
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RebroadcastOutcome {
+    /// Landed and reached [`RebroadcastArgs::tx_cfm_commitment`]
+    Confirmed { slot: Slot },
+
+    /// Landed but failed
+    Failed { slot: Slot, err: TransactionError },
+
+    /// Block height exceeded the blockhash's last valid block height without the tx landing
+    Expired,
+}
+
+/// A single signed tx and how rebroadcasting it went
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebroadcastAttempt {
+    pub signature: Signature,
+
+    pub blockhash: RecentBlockhash,
+
+    /// Number of times the tx was sent, including the first send
+    pub sends: u32,
+
+    pub outcome: RebroadcastOutcome,
+}
+
+/// Result of [`crate::TxSendingRpcClient::build_and_rebroadcast_tx`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RebroadcastOutput {
+    /// In order. Only the last attempt may not be [`RebroadcastOutcome::Expired`]
+    pub attempts: Vec<RebroadcastAttempt>,
+}
+
+impl RebroadcastOutput {
+    /// Signature of the attempt that was confirmed, if any
+    pub fn confirmed_signature(&self) -> Option<Signature> {
+        self.attempts
+            .iter()
+            .find(|a| matches!(a.outcome, RebroadcastOutcome::Confirmed { .. }))
+            .map(|a| a.signature)
+    }
+}
+
+/// Result of [`crate::TxSendingRpcClient::build_and_rebroadcast_tx`].
+/// On error, also contains the attempts made before the error occurred
+pub type RebroadcastResult = Result<RebroadcastOutput, (RebroadcastOutput, ClientError)>;
+
+pub(crate) fn durable_nonce_rebroadcast_err() -> ClientError {
+    ClientErrorKind::Custom(
+        "Durable nonce txs do not expire and should not be rebroadcast, use build_and_handle_tx instead"
+            .to_owned(),
+    )
+    .into()
+}
+
+enum RebroadcastPoll {
+    Done(RebroadcastOutcome),
+    /// landed but not yet at the required commitment
+    Pending,
+    NotFound,
+}
+
+/// `block_height` must be fetched before `status`
+/// so that a tx that lands right before expiry is not reported as expired
+fn rebroadcast_poll(
+    status: Option<TransactionStatus>,
+    block_height: u64,
+    last_valid_blockheight: u64,
+    commitment: CommitmentLevel,
+) -> RebroadcastPoll {
+    match status {
+        Some(TransactionStatus {
+            slot,
+            err: Some(err),
+            ..
+        }) => RebroadcastPoll::Done(RebroadcastOutcome::Failed { slot, err }),
+        Some(status) if status.satisfies_commitment(CommitmentConfig { commitment }) => {
+            RebroadcastPoll::Done(RebroadcastOutcome::Confirmed { slot: status.slot })
+        }
+        Some(_) => RebroadcastPoll::Pending,
+        None if block_height > last_valid_blockheight => {
+            RebroadcastPoll::Done(RebroadcastOutcome::Expired)
+        }
+        None => RebroadcastPoll::NotFound,
+    }
+}
+
+/// Sends `tx` then resends it every [`RebroadcastArgs::resend_interval`]
+/// until it lands or `last_valid_blockheight` is exceeded.
+///
+/// Errs if the first send fails. Failed resends and failed
+/// block height or signature status polls are ignored.
+pub(crate) fn rebroadcast_until_expiry<T: SerializableTransaction>(
+    client: &solana_client::rpc_client::RpcClient,
+    tx: &T,
+    last_valid_blockheight: u64,
+    args: &RebroadcastArgs,
+) -> Result<RebroadcastAttempt, ClientError> {
+    let signature = client.send_transaction_with_config(tx, args.send_config())?;
+    let mut sends = 1;
+    let outcome = loop {
+        std::thread::sleep(args.resend_interval());
+        let Ok(epoch_info) = client.get_epoch_info_with_commitment(CommitmentConfig::confirmed())
+        else {
+            continue;
+        };
+        let Ok(mut statuses) = client.get_signature_statuses(&[signature]) else {
+            continue;
+        };
+        let block_height = epoch_info.block_height;
+        let status = statuses.value[0].take();
+        match rebroadcast_poll(
+            status,
+            block_height,
+            last_valid_blockheight,
+            args.tx_cfm_commitment,
+        ) {
+            RebroadcastPoll::Done(outcome) => break outcome,
+            RebroadcastPoll::Pending => (),
+            RebroadcastPoll::NotFound => {
+                let _ = client.send_transaction_with_config(tx, args.resend_config());
+                sends += 1;
+            }
+        }
+    };
+    Ok(RebroadcastAttempt {
+        signature,
+        blockhash: RecentBlockhash {
+            hash: *tx.get_recent_blockhash(),
+            last_valid_blockheight,
+        },
+        sends,
+        outcome,
+    })
+}
+
+/// Nonblocking version of [`rebroadcast_until_expiry`]
+pub(crate) async fn rebroadcast_until_expiry_nonblocking<T: SerializableTransaction + Sync>(
+    client: &solana_client::nonblocking::rpc_client::RpcClient,
+    tx: &T,
+    last_valid_blockheight: u64,
+    args: &RebroadcastArgs,
+) -> Result<RebroadcastAttempt, ClientError> {
+    let signature = client
+        .send_transaction_with_config(tx, args.send_config())
+        .await?;
+    let mut sends = 1;
+    let outcome = loop {
+        tokio::time::sleep(args.resend_interval()).await;
+        let Ok(epoch_info) = client
+            .get_epoch_info_with_commitment(CommitmentConfig::confirmed())
+            .await
+        else {
+            continue;
+        };
+        let Ok(mut statuses) = client.get_signature_statuses(&[signature]).await else {
+            continue;
+        };
+        let block_height = epoch_info.block_height;
+        let status = statuses.value[0].take();
+        match rebroadcast_poll(
+            status,
+            block_height,
+            last_valid_blockheight,
+            args.tx_cfm_commitment,
+        ) {
+            RebroadcastPoll::Done(outcome) => break outcome,
+            RebroadcastPoll::Pending => (),
+            RebroadcastPoll::NotFound => {
+                let _ = client
+                    .send_transaction_with_config(tx, args.resend_config())
+                    .await;
+                sends += 1;
+            }
+        }
+    };
+    Ok(RebroadcastAttempt {
+        signature,
+        blockhash: RecentBlockhash {
+            hash: *tx.get_recent_blockhash(),
+            last_valid_blockheight,
+        },
+        sends,
+        outcome,
+    })
+}
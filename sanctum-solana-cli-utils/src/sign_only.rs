@@ -0,0 +1,160 @@
+//! Offline signing: sign with the signers available, output `pubkey=signature` pairs,
+//! then merge signatures collected from all signers into a fully signed transaction.
+
+use std::{error::Error, fmt::Display};
+
+use sanctum_solana_client_utils::SortedSigners;
+use solana_rpc_client_api::client_error::{Error as ClientError, ErrorKind as ClientErrorKind};
+use solana_sdk::{
+    message::VersionedMessage,
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{Signer, SignerError},
+    transaction::VersionedTransaction,
+};
+
+use crate::SignerSignature;
+
+#[derive(Debug, PartialEq)]
+pub enum SignOnlyError {
+    /// Pubkey is not a required signer of the transaction
+    NotASigner(Pubkey),
+
+    /// Signature does not verify against the transaction message for pubkey
+    InvalidSignature(Pubkey),
+
+    /// Transaction is still missing signatures for these pubkeys
+    MissingSignatures(Vec<Pubkey>),
+
+    Signer(SignerError),
+}
+
+impl Display for SignOnlyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotASigner(pk) => write!(f, "{pk} is not a signer of the transaction"),
+            Self::InvalidSignature(pk) => write!(f, "Invalid signature for {pk}"),
+            Self::MissingSignatures(pks) => {
+                f.write_str("Missing signatures for")?;
+                pks.iter().try_for_each(|pk| write!(f, " {pk}"))
+            }
+            Self::Signer(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for SignOnlyError {}
+
+impl From<SignerError> for SignOnlyError {
+    fn from(value: SignerError) -> Self {
+        Self::Signer(value)
+    }
+}
+
+impl From<SignOnlyError> for ClientError {
+    fn from(value: SignOnlyError) -> Self {
+        match value {
+            SignOnlyError::Signer(e) => ClientErrorKind::SigningError(e).into(),
+            e => ClientErrorKind::Custom(e.to_string()).into(),
+        }
+    }
+}
+
+fn signer_index(message: &VersionedMessage, pubkey: &Pubkey) -> Option<usize> {
+    message.static_account_keys()[..usize::from(message.header().num_required_signatures)]
+        .iter()
+        .position(|k| k == pubkey)
+}
+
+/// Creates a transaction signed by only the signers available.
+///
+/// `signers` may contain [`crate::PubkeySrc::Pubkey`]s for signers that will sign elsewhere,
+/// their signatures are left as [`Signature::default()`].
+/// The message's recent blockhash should be an externally provided blockhash or durable nonce
+/// that will still be valid by the time all signatures are collected.
+pub fn sign_only_versioned_tx<S: Signer + ?Sized>(
+    message: VersionedMessage,
+    signers: &SortedSigners<'_, '_, S>,
+) -> Result<VersionedTransaction, SignOnlyError> {
+    let mut tx = VersionedTransaction {
+        signatures: vec![Signature::default(); message.header().num_required_signatures.into()],
+        message,
+    };
+    let message_data = tx.message.serialize();
+    for signer in signers.iter() {
+        let pubkey = signer.try_pubkey()?;
+        let i = signer_index(&tx.message, &pubkey).ok_or(SignOnlyError::NotASigner(pubkey))?;
+        tx.signatures[i] = signer.try_sign_message(&message_data)?;
+    }
+    Ok(tx)
+}
+
+/// Signatures of a partially signed transaction
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SignOnlySignatures {
+    pub present: Vec<SignerSignature>,
+    pub absent: Vec<Pubkey>,
+}
+
+impl SignOnlySignatures {
+    /// Missing signature entries are treated as absent
+    pub fn from_tx(tx: &VersionedTransaction) -> Self {
+        let mut res = Self::default();
+        let n_signers = usize::from(tx.message.header().num_required_signatures);
+        for (i, pubkey) in tx.message.static_account_keys()[..n_signers]
+            .iter()
+            .enumerate()
+        {
+            match tx.signatures.get(i) {
+                Some(signature) if *signature != Signature::default() => {
+                    res.present.push(SignerSignature {
+                        pubkey: *pubkey,
+                        signature: *signature,
+                    })
+                }
+                _ => res.absent.push(*pubkey),
+            }
+        }
+        res
+    }
+}
+
+/// Merges `signer_signatures` collected from other signers into `tx`,
+/// verifying each against the transaction message
+pub fn merge_signer_signatures<'a>(
+    tx: &mut VersionedTransaction,
+    signer_signatures: impl IntoIterator<Item = &'a SignerSignature>,
+) -> Result<(), SignOnlyError> {
+    tx.signatures.resize(
+        tx.message.header().num_required_signatures.into(),
+        Signature::default(),
+    );
+    let message_data = tx.message.serialize();
+    for SignerSignature { pubkey, signature } in signer_signatures {
+        let i = signer_index(&tx.message, pubkey).ok_or(SignOnlyError::NotASigner(*pubkey))?;
+        if !signature.verify(pubkey.as_ref(), &message_data) {
+            return Err(SignOnlyError::InvalidSignature(*pubkey));
+        }
+        tx.signatures[i] = *signature;
+    }
+    Ok(())
+}
+
+/// Errs with [`SignOnlyError::MissingSignatures`] if `tx` is not ready to be sent
+pub fn ensure_fully_signed(tx: &VersionedTransaction) -> Result<(), SignOnlyError> {
+    let SignOnlySignatures { absent, .. } = SignOnlySignatures::from_tx(tx);
+    if !absent.is_empty() {
+        return Err(SignOnlyError::MissingSignatures(absent));
+    }
+    Ok(())
+}
+
+/// [`merge_signer_signatures`] followed by [`ensure_fully_signed`]
+pub fn assemble_signed_tx<'a>(
+    mut tx: VersionedTransaction,
+    signer_signatures: impl IntoIterator<Item = &'a SignerSignature>,
+) -> Result<VersionedTransaction, SignOnlyError> {
+    merge_signer_signatures(&mut tx, signer_signatures)?;
+    ensure_fully_signed(&tx)?;
+    Ok(tx)
+}
@@ -1,28 +1,55 @@
-use std::fmt::Display;
+use std::{fmt::Display, future::Future, pin::Pin};
 
 use async_trait::async_trait;
 use data_encoding::BASE64;
+use sanctum_solana_client_utils::{
+    get_compute_budget_ixs_auto, get_compute_budget_ixs_auto_nonblocking,
+};
+use serde::{Deserialize, Serialize};
 use solana_client::{
     rpc_client::SerializableTransaction,
-    rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig},
+    rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig, RpcTransactionConfig},
+    rpc_response::{Response, RpcSimulateTransactionResult},
+};
+use solana_rpc_client_api::{
+    client_error::{Error as ClientError, ErrorKind as ClientErrorKind},
+    request::{RpcError, RpcResponseErrorData},
 };
-use solana_rpc_client_api::client_error::Error as ClientError;
 use solana_sdk::{
+    clock::Slot,
     commitment_config::{CommitmentConfig, CommitmentLevel},
     hash::Hash,
+    signature::Signature,
+    signer::Signer,
+    transaction::TransactionError,
+};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    TransactionConfirmationStatus, TransactionStatus, UiTransactionEncoding,
+};
+
+use crate::{
+    durable_nonce_account_of, durable_nonce_from_fetched, durable_nonce_rebroadcast_err,
+    rebroadcast_until_expiry, rebroadcast_until_expiry_nonblocking,
+    send_and_confirm_durable_nonce_tx, send_and_confirm_durable_nonce_tx_nonblocking,
+    to_versioned_tx, BuildTxArgs, ComputeBudgetArgs, DurableNonce, NonceArgs, RebroadcastArgs,
+    RebroadcastAttempt, RebroadcastOutcome, RebroadcastOutput, RebroadcastResult,
+    SignOnlySignatures, SignerSignature,
 };
-use solana_transaction_status::UiTransactionEncoding;
 
 /// Enum for specifying how to handle transactions output.
 /// - `SendActual` sends the actual transaction to the cluster
 /// - `SimOnly` simulates the transaction against the cluster
 /// - `DumpMsg` outputs base64 encoded serialized transaction to stdout for use with multisigs, explorer inspectors, or piping into other applications
+/// - `SignOnly` outputs the transaction's blockhash and `pubkey=signature` pairs to stdout for offline signing.
+///   See [`crate::sign_only_versioned_tx`] and [`crate::assemble_signed_tx`]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 pub enum TxSendMode {
     SendActual,
     SimOnly,
     DumpMsg,
+    SignOnly,
 }
 
 impl Default for TxSendMode {
@@ -71,6 +98,23 @@ impl TxSendMode {
     }
 }
 
+/// Format of the output [`TxSendingRpcClient::handle_tx`] writes for each transaction.
+/// - `Text` outputs human-readable text
+/// - `Json` outputs a single-line json [`HandleTxOutput`] to stdout, for consumption by scripts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum TxOutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl Display for TxOutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 /// This struct combines the common fields of
 /// `RpcSendTransactionConfig` and `RpcSimulateTransactionConfig`,
 /// while omitting some fields that are deemed to be not important for user config
@@ -84,6 +128,7 @@ pub struct HandleTxArgs {
     pub min_context_slot: Option<u64>,
     pub max_retries: Option<usize>,
     pub inner_instructions: bool,
+    pub output_format: TxOutputFormat,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -104,6 +149,7 @@ impl HandleTxArgs {
             max_retries: None,
             min_context_slot: None,
             inner_instructions: false,
+            output_format: TxOutputFormat::Text,
         }
     }
 
@@ -119,6 +165,7 @@ impl HandleTxArgs {
             min_context_slot: None,
             max_retries: None,
             inner_instructions: false,
+            output_format: TxOutputFormat::Text,
         }
     }
 
@@ -133,10 +180,187 @@ impl HandleTxArgs {
             replace_recent_blockhash: false,
             min_context_slot: None,
             inner_instructions: false,
+            output_format: TxOutputFormat::Text,
+        }
+    }
+
+    pub const fn with_output_format(self, output_format: TxOutputFormat) -> Self {
+        Self {
+            output_format,
+            ..self
         }
     }
 }
 
+/// Result of [`TxSendingRpcClient::handle_tx`] for a single transaction.
+///
+/// Fields that do not apply to the [`TxSendMode`] used are `None`:
+/// - `SendActual`: `slot`, `confirmation_status`, `logs` and `units_consumed`
+///   are only fetched if [`HandleTxArgs::output_format`] is [`TxOutputFormat::Json`],
+///   and are left `None` if fetching them fails after the tx landed.
+///   If sending fails, the output is still printed with `err` and, if available, `logs`
+///   before the error is returned
+/// - `SimOnly`: `slot` is the slot the simulation was ran at. `confirmation_status` is always `None`
+/// - `DumpMsg`: only `signature` and `tx_base64` are set
+/// - `SignOnly`: only `signature`, `tx_base64`, `blockhash`, `signers` and `absent_signers` are set
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandleTxOutput {
+    /// base58-encoded first signature of the tx, `None` if unsigned
+    pub signature: Option<String>,
+    pub slot: Option<Slot>,
+    pub confirmation_status: Option<TransactionConfirmationStatus>,
+    pub err: Option<TransactionError>,
+    pub logs: Option<Vec<String>>,
+    pub units_consumed: Option<u64>,
+
+    /// bincode-serialized tx, base64-encoded
+    pub tx_base64: String,
+
+    /// base58-encoded recent blockhash or durable nonce the tx was signed with
+    pub blockhash: Option<String>,
+    pub signers: Option<Vec<SignerSignature>>,
+    pub absent_signers: Option<Vec<String>>,
+}
+
+impl HandleTxOutput {
+    fn from_tx<T: SerializableTransaction>(tx: &T) -> Self {
+        let signature = tx.get_signature();
+        Self {
+            signature: (*signature != Signature::default()).then(|| signature.to_string()),
+            // somehow `BASE64.encode(&tx.message_data())` as suggested by all the explorers
+            // results in a different output that cannot be handled by their inspectors lmao
+            tx_base64: BASE64.encode(&bincode::serialize(&tx).unwrap()),
+            ..Default::default()
+        }
+    }
+
+    fn set_sim_result(
+        &mut self,
+        Response { context, value }: &Response<RpcSimulateTransactionResult>,
+    ) {
+        self.slot = Some(context.slot);
+        self.err.clone_from(&value.err);
+        self.logs.clone_from(&value.logs);
+        self.units_consumed = value.units_consumed;
+    }
+
+    fn set_sign_only<T: SerializableTransaction>(&mut self, tx: &T) {
+        let SignOnlySignatures { present, absent } =
+            SignOnlySignatures::from_tx(&to_versioned_tx(tx));
+        self.blockhash = Some(tx.get_recent_blockhash().to_string());
+        self.signers = Some(present);
+        self.absent_signers = Some(absent.iter().map(|pk| pk.to_string()).collect());
+    }
+
+    /// Same format as the `solana` CLI's `--sign-only` output
+    fn println_sign_only_text(&self) {
+        println!(
+            "Blockhash: {}",
+            self.blockhash.as_deref().unwrap_or_default()
+        );
+        println!("Signers (Pubkey=Signature):");
+        for signer in self.signers.iter().flatten() {
+            println!("  {signer}");
+        }
+        if let Some(absent_signers) = self.absent_signers.as_ref().filter(|a| !a.is_empty()) {
+            println!("Absent Signers (Pubkey):");
+            for pk in absent_signers {
+                println!("  {pk}");
+            }
+        }
+    }
+
+    fn set_status(&mut self, status: TransactionStatus) {
+        self.slot = Some(status.slot);
+        self.err = status.err;
+        self.confirmation_status = status.confirmation_status;
+    }
+
+    fn set_confirmed_tx(&mut self, confirmed_tx: EncodedConfirmedTransactionWithStatusMeta) {
+        let Some(meta) = confirmed_tx.transaction.meta else {
+            return;
+        };
+        if let OptionSerializer::Some(logs) = meta.log_messages {
+            self.logs = Some(logs);
+        }
+        if let OptionSerializer::Some(units_consumed) = meta.compute_units_consumed {
+            self.units_consumed = Some(units_consumed);
+        }
+    }
+
+    /// Fetches the status, logs and compute units consumed of the landed tx `signature`
+    fn fetch_landed(
+        &mut self,
+        client: &solana_client::rpc_client::RpcClient,
+        signature: &Signature,
+        tx_cfm_commitment: CommitmentLevel,
+    ) -> Result<(), ClientError> {
+        if let Some(status) = client.get_signature_statuses(&[*signature])?.value[0].take() {
+            self.set_status(status);
+        }
+        // best-effort, tx history may not be available
+        if let Ok(confirmed_tx) =
+            client.get_transaction_with_config(signature, get_transaction_config(tx_cfm_commitment))
+        {
+            self.set_confirmed_tx(confirmed_tx);
+        }
+        Ok(())
+    }
+
+    /// Nonblocking version of [`Self::fetch_landed`]
+    async fn fetch_landed_nonblocking(
+        &mut self,
+        client: &solana_client::nonblocking::rpc_client::RpcClient,
+        signature: &Signature,
+        tx_cfm_commitment: CommitmentLevel,
+    ) -> Result<(), ClientError> {
+        if let Some(status) = client.get_signature_statuses(&[*signature]).await?.value[0].take() {
+            self.set_status(status);
+        }
+        // best-effort, tx history may not be available
+        if let Ok(confirmed_tx) = client
+            .get_transaction_with_config(signature, get_transaction_config(tx_cfm_commitment))
+            .await
+        {
+            self.set_confirmed_tx(confirmed_tx);
+        }
+        Ok(())
+    }
+
+    /// Sets `err`, and `logs` and `units_consumed` too if the preflight simulation failed
+    fn set_send_err(&mut self, err: &ClientError) {
+        if let ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(sim),
+            ..
+        }) = err.kind()
+        {
+            self.logs.clone_from(&sim.logs);
+            self.units_consumed = sim.units_consumed;
+        }
+        if let Some(tx_err) = err.get_transaction_error() {
+            self.err = Some(tx_err);
+        }
+    }
+
+    fn println_json(&self) {
+        println!("{}", serde_json::to_string(self).unwrap());
+    }
+}
+
+/// getTransaction does not support processed commitment
+fn get_transaction_config(tx_cfm_commitment: CommitmentLevel) -> RpcTransactionConfig {
+    let commitment = match tx_cfm_commitment {
+        CommitmentLevel::Processed => CommitmentLevel::Confirmed,
+        c => c,
+    };
+    RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig { commitment }),
+        max_supported_transaction_version: Some(0),
+    }
+}
+
 impl From<HandleTxArgs> for RpcSimulateTransactionConfig {
     fn from(
         HandleTxArgs {
@@ -185,16 +409,72 @@ pub trait TxSendingRpcClient {
     /// Get blockhash with confirmed commitment. Optimal for transaction sending.
     fn get_confirmed_blockhash(&self) -> Result<RecentBlockhash, ClientError>;
 
-    /// Handles the given transaction, outputting the following to stdout:
-    /// - simulation results if `send_mode == TxSendMode::SimOnly`
-    /// - transaction signature if `send_mode == TxSendMode::SendActual`
-    /// - base64 encoded serialized tx if `send_mode == TxSendMode::DumpMsg`
+    /// Get the durable nonce to use as the `recent_blockhash` of a durable nonce tx
+    /// with confirmed commitment, checking that `nonce_args.nonce_authority` is the nonce account's authority.
+    ///
+    /// The tx's first instruction must be [`NonceArgs::advance_nonce_ix`].
+    fn get_durable_nonce(&self, nonce_args: &NonceArgs) -> Result<DurableNonce, ClientError>;
+
+    /// Handles the given transaction, outputting the following if `args.output_format == TxOutputFormat::Text`:
+    /// - simulation results to stderr if `send_mode == TxSendMode::SimOnly`
+    /// - transaction signature to stderr if `send_mode == TxSendMode::SendActual`.
+    ///   Durable nonce txs are confirmed without checking for blockhash expiry,
+    ///   see [`crate::DURABLE_NONCE_TX_CONFIRM_TIMEOUT`]
+    /// - base64 encoded serialized tx to stdout if `send_mode == TxSendMode::DumpMsg`
+    /// - blockhash and `pubkey=signature` pairs to stdout if `send_mode == TxSendMode::SignOnly`.
+    ///   No RPC calls are made in this mode
+    ///
+    /// or the returned [`HandleTxOutput`] as a single line of json to stdout
+    /// if `args.output_format == TxOutputFormat::Json`
     fn handle_tx<T: SerializableTransaction>(
         &self,
         tx: &T,
         send_mode: TxSendMode,
         args: HandleTxArgs,
-    ) -> Result<(), ClientError>;
+    ) -> Result<HandleTxOutput, ClientError>;
+
+    /// Builds a v0 transaction then [`TxSendingRpcClient::handle_tx`]s it:
+    /// 1. simulates `build_args.ixs` to set the compute unit limit, multiplied by `compute_budget.cu_buffer_ratio`
+    /// 2. sets the priority fee to the lower of the slot weighted median of recent prioritization fees
+    ///    and [`ComputeBudgetArgs::fee_limit`]
+    /// 3. fetches a confirmed blockhash, or the durable nonce if `build_args.nonce` is set
+    /// 4. signs with deduplicated `build_args.signers`
+    fn build_and_handle_tx<S: Signer + ?Sized>(
+        &self,
+        build_args: BuildTxArgs<'_, S>,
+        compute_budget: ComputeBudgetArgs,
+        send_mode: TxSendMode,
+        args: HandleTxArgs,
+    ) -> Result<HandleTxOutput, ClientError>;
+
+    /// Sends `tx` then resends it every `args.resend_interval` until it lands or
+    /// the block height exceeds `last_valid_blockheight` (see [`RecentBlockhash`]),
+    /// instead of relying on the RPC node's retries like [`HandleTxArgs::optimal_tx_send`] does.
+    ///
+    /// Errs if the first send fails e.g. on preflight failure. Failed resends are ignored.
+    fn rebroadcast_tx<T: SerializableTransaction>(
+        &self,
+        tx: &T,
+        last_valid_blockheight: u64,
+        args: RebroadcastArgs,
+    ) -> Result<RebroadcastAttempt, ClientError>;
+
+    /// Builds and signs a v0 transaction like [`TxSendingRpcClient::build_and_handle_tx`]
+    /// then [`TxSendingRpcClient::rebroadcast_tx`]s it.
+    ///
+    /// Each time an attempt's blockhash expires, the tx is re-signed with a fresh blockhash
+    /// and recomputed compute budget instructions, up to `args.max_attempts` attempts.
+    ///
+    /// Errs if `build_args.nonce` is set since durable nonce txs do not expire.
+    ///
+    /// The error is returned alongside the attempts made before it occurred,
+    /// so that callers can check if any of their signatures may still land.
+    fn build_and_rebroadcast_tx<S: Signer + ?Sized>(
+        &self,
+        build_args: BuildTxArgs<'_, S>,
+        compute_budget: ComputeBudgetArgs,
+        args: RebroadcastArgs,
+    ) -> RebroadcastResult;
 }
 
 impl TxSendingRpcClient for solana_client::rpc_client::RpcClient {
@@ -209,12 +489,20 @@ impl TxSendingRpcClient for solana_client::rpc_client::RpcClient {
         })
     }
 
+    fn get_durable_nonce(&self, nonce_args: &NonceArgs) -> Result<DurableNonce, ClientError> {
+        let fetched = self.get_account_with_commitment(
+            &nonce_args.nonce_account,
+            CommitmentConfig::confirmed(),
+        )?;
+        durable_nonce_from_fetched(nonce_args, fetched)
+    }
+
     fn handle_tx<T: SerializableTransaction>(
         &self,
         tx: &T,
         send_mode: TxSendMode,
         mut args: HandleTxArgs,
-    ) -> Result<(), ClientError> {
+    ) -> Result<HandleTxOutput, ClientError> {
         let [tx_cfm_commitment, _sim_against_commitment] = [
             &mut args.tx_cfm_commitment,
             &mut args.sim_against_commitment,
@@ -227,28 +515,148 @@ impl TxSendingRpcClient for solana_client::rpc_client::RpcClient {
                 commitment
             }
         });
+        let is_json = args.output_format == TxOutputFormat::Json;
+        let mut output = HandleTxOutput::from_tx(tx);
         match send_mode {
             TxSendMode::SendActual => {
-                let signature = self.send_and_confirm_transaction_with_spinner_and_config(
-                    tx,
-                    CommitmentConfig {
-                        commitment: tx_cfm_commitment,
-                    },
-                    args.into(),
-                )?;
-                eprintln!("Signature: {}", signature);
+                let commitment = CommitmentConfig {
+                    commitment: tx_cfm_commitment,
+                };
+                let send_res = match durable_nonce_account_of(tx) {
+                    Some(nonce_account) => send_and_confirm_durable_nonce_tx(
+                        self,
+                        tx,
+                        &nonce_account,
+                        commitment,
+                        args.into(),
+                    ),
+                    None => self.send_and_confirm_transaction_with_spinner_and_config(
+                        tx,
+                        commitment,
+                        args.into(),
+                    ),
+                };
+                let signature = match send_res {
+                    Ok(signature) => signature,
+                    Err(e) => {
+                        if is_json {
+                            // landed but failed, e.g. with preflight skipped
+                            if matches!(e.kind(), ClientErrorKind::TransactionError(_)) {
+                                let _ = output.fetch_landed(
+                                    self,
+                                    tx.get_signature(),
+                                    tx_cfm_commitment,
+                                );
+                            }
+                            output.set_send_err(&e);
+                            output.println_json();
+                        }
+                        return Err(e);
+                    }
+                };
+                output.signature = Some(signature.to_string());
+                if is_json {
+                    // best-effort: the tx has already landed, erring here may cause callers to resend
+                    let _ = output.fetch_landed(self, &signature, tx_cfm_commitment);
+                } else {
+                    eprintln!("Signature: {}", signature);
+                }
             }
             TxSendMode::SimOnly => {
                 let result = self.simulate_transaction_with_config(tx, args.into())?;
-                eprintln!("Simulate result: {:#?}", result);
+                output.set_sim_result(&result);
+                if !is_json {
+                    eprintln!("Simulate result: {:#?}", result);
+                }
             }
             TxSendMode::DumpMsg => {
-                // somehow `BASE64.encode(&tx.message_data())` as suggested by all the explorers
-                // results in a different output that cannot be handled by their inspectors lmao
-                println!("{}", BASE64.encode(&bincode::serialize(&tx).unwrap()))
+                if !is_json {
+                    println!("{}", output.tx_base64);
+                }
+            }
+            TxSendMode::SignOnly => {
+                output.set_sign_only(tx);
+                if !is_json {
+                    output.println_sign_only_text();
+                }
             }
         };
-        Ok(())
+        if is_json {
+            output.println_json();
+        }
+        Ok(output)
+    }
+
+    fn build_and_handle_tx<S: Signer + ?Sized>(
+        &self,
+        build_args: BuildTxArgs<'_, S>,
+        compute_budget: ComputeBudgetArgs,
+        send_mode: TxSendMode,
+        args: HandleTxArgs,
+    ) -> Result<HandleTxOutput, ClientError> {
+        let compute_budget_ixs = get_compute_budget_ixs_auto(
+            self,
+            &build_args.payer,
+            &build_args.est_cu_ixs(),
+            build_args.luts,
+            &compute_budget.fee_limit(),
+            compute_budget.cu_buffer_ratio,
+        )?;
+        let blockhash = match build_args.nonce {
+            Some(nonce) => self.get_durable_nonce(&nonce)?.durable_nonce,
+            None => self.get_confirmed_blockhash()?.hash,
+        };
+        let tx = build_args.sign(&build_args.final_ixs(compute_budget_ixs), blockhash)?;
+        self.handle_tx(&tx, send_mode, args)
+    }
+
+    fn rebroadcast_tx<T: SerializableTransaction>(
+        &self,
+        tx: &T,
+        last_valid_blockheight: u64,
+        args: RebroadcastArgs,
+    ) -> Result<RebroadcastAttempt, ClientError> {
+        rebroadcast_until_expiry(self, tx, last_valid_blockheight, &args)
+    }
+
+    fn build_and_rebroadcast_tx<S: Signer + ?Sized>(
+        &self,
+        build_args: BuildTxArgs<'_, S>,
+        compute_budget: ComputeBudgetArgs,
+        args: RebroadcastArgs,
+    ) -> RebroadcastResult {
+        let mut output = RebroadcastOutput::default();
+        if build_args.nonce.is_some() {
+            return Err((output, durable_nonce_rebroadcast_err()));
+        }
+        for _ in 0..args.max_attempts.max(1) {
+            let attempt_res = (|| {
+                let compute_budget_ixs = get_compute_budget_ixs_auto(
+                    self,
+                    &build_args.payer,
+                    &build_args.est_cu_ixs(),
+                    build_args.luts,
+                    &compute_budget.fee_limit(),
+                    compute_budget.cu_buffer_ratio,
+                )?;
+                let RecentBlockhash {
+                    hash,
+                    last_valid_blockheight,
+                } = self.get_confirmed_blockhash()?;
+                let tx = build_args.sign(&build_args.final_ixs(compute_budget_ixs), hash)?;
+                self.rebroadcast_tx(&tx, last_valid_blockheight, args)
+            })();
+            let attempt = match attempt_res {
+                Ok(a) => a,
+                Err(e) => return Err((output, e)),
+            };
+            let is_expired = attempt.outcome == RebroadcastOutcome::Expired;
+            output.attempts.push(attempt);
+            if !is_expired {
+                break;
+            }
+        }
+        Ok(output)
     }
 }
 
@@ -257,16 +665,47 @@ pub trait TxSendingNonblockingRpcClient {
     /// Get blockhash with confirmed commitment. Optimal for transaction sending.
     async fn get_confirmed_blockhash(&self) -> Result<RecentBlockhash, ClientError>;
 
-    /// Handles the given transaction, outputting the following to stdout:
-    /// - simulation results if `send_mode == TxSendMode::SimOnly`
-    /// - transaction signature if `send_mode == TxSendMode::SendActual`
-    /// - base64 encoded serialized tx if `send_mode == TxSendMode::DumpMsg`
+    /// See [`TxSendingRpcClient::get_durable_nonce`]
+    async fn get_durable_nonce(&self, nonce_args: &NonceArgs) -> Result<DurableNonce, ClientError>;
+
+    /// See [`TxSendingRpcClient::handle_tx`]
     async fn handle_tx<T: SerializableTransaction + Sync>(
         &self,
         tx: &T,
         send_mode: TxSendMode,
         args: HandleTxArgs,
-    ) -> Result<(), ClientError>;
+    ) -> Result<HandleTxOutput, ClientError>;
+
+    /// See [`TxSendingRpcClient::build_and_handle_tx`].
+    ///
+    /// The returned future is not `Send` so that non-`Sync` signers
+    /// such as those returned by [`crate::parse_signer`] can be used.
+    fn build_and_handle_tx<'a, S: Signer + ?Sized>(
+        &'a self,
+        build_args: BuildTxArgs<'a, S>,
+        compute_budget: ComputeBudgetArgs,
+        send_mode: TxSendMode,
+        args: HandleTxArgs,
+    ) -> Pin<Box<dyn Future<Output = Result<HandleTxOutput, ClientError>> + 'a>>;
+
+    /// See [`TxSendingRpcClient::rebroadcast_tx`]
+    async fn rebroadcast_tx<T: SerializableTransaction + Sync>(
+        &self,
+        tx: &T,
+        last_valid_blockheight: u64,
+        args: RebroadcastArgs,
+    ) -> Result<RebroadcastAttempt, ClientError>;
+
+    /// See [`TxSendingRpcClient::build_and_rebroadcast_tx`].
+    ///
+    /// The returned future is not `Send` for the same reason as
+    /// [`TxSendingNonblockingRpcClient::build_and_handle_tx`]'s
+    fn build_and_rebroadcast_tx<'a, S: Signer + ?Sized>(
+        &'a self,
+        build_args: BuildTxArgs<'a, S>,
+        compute_budget: ComputeBudgetArgs,
+        args: RebroadcastArgs,
+    ) -> Pin<Box<dyn Future<Output = RebroadcastResult> + 'a>>;
 }
 
 #[async_trait]
@@ -283,12 +722,19 @@ impl TxSendingNonblockingRpcClient for solana_client::nonblocking::rpc_client::R
         })
     }
 
+    async fn get_durable_nonce(&self, nonce_args: &NonceArgs) -> Result<DurableNonce, ClientError> {
+        let fetched = self
+            .get_account_with_commitment(&nonce_args.nonce_account, CommitmentConfig::confirmed())
+            .await?;
+        durable_nonce_from_fetched(nonce_args, fetched)
+    }
+
     async fn handle_tx<T: SerializableTransaction + Sync>(
         &self,
         tx: &T,
         send_mode: TxSendMode,
         mut args: HandleTxArgs,
-    ) -> Result<(), ClientError> {
+    ) -> Result<HandleTxOutput, ClientError> {
         let [tx_cfm_commitment, _sim_against_commitment] = [
             &mut args.tx_cfm_commitment,
             &mut args.sim_against_commitment,
@@ -301,31 +747,166 @@ impl TxSendingNonblockingRpcClient for solana_client::nonblocking::rpc_client::R
                 commitment
             }
         });
+        let is_json = args.output_format == TxOutputFormat::Json;
+        let mut output = HandleTxOutput::from_tx(tx);
         match send_mode {
             TxSendMode::SendActual => {
-                let signature = self
-                    .send_and_confirm_transaction_with_spinner_and_config(
-                        tx,
-                        CommitmentConfig {
-                            commitment: tx_cfm_commitment,
-                        },
-                        args.into(),
-                    )
-                    .await?;
-                eprintln!("Signature: {}", signature);
+                let commitment = CommitmentConfig {
+                    commitment: tx_cfm_commitment,
+                };
+                let send_res = match durable_nonce_account_of(tx) {
+                    Some(nonce_account) => {
+                        send_and_confirm_durable_nonce_tx_nonblocking(
+                            self,
+                            tx,
+                            &nonce_account,
+                            commitment,
+                            args.into(),
+                        )
+                        .await
+                    }
+                    None => {
+                        self.send_and_confirm_transaction_with_spinner_and_config(
+                            tx,
+                            commitment,
+                            args.into(),
+                        )
+                        .await
+                    }
+                };
+                let signature = match send_res {
+                    Ok(signature) => signature,
+                    Err(e) => {
+                        if is_json {
+                            // landed but failed, e.g. with preflight skipped
+                            if matches!(e.kind(), ClientErrorKind::TransactionError(_)) {
+                                let _ = output
+                                    .fetch_landed_nonblocking(
+                                        self,
+                                        tx.get_signature(),
+                                        tx_cfm_commitment,
+                                    )
+                                    .await;
+                            }
+                            output.set_send_err(&e);
+                            output.println_json();
+                        }
+                        return Err(e);
+                    }
+                };
+                output.signature = Some(signature.to_string());
+                if is_json {
+                    // best-effort: the tx has already landed, erring here may cause callers to resend
+                    let _ = output
+                        .fetch_landed_nonblocking(self, &signature, tx_cfm_commitment)
+                        .await;
+                } else {
+                    eprintln!("Signature: {}", signature);
+                }
             }
             TxSendMode::SimOnly => {
                 let result = self
                     .simulate_transaction_with_config(tx, args.into())
                     .await?;
-                eprintln!("Simulate result: {:#?}", result);
+                output.set_sim_result(&result);
+                if !is_json {
+                    eprintln!("Simulate result: {:#?}", result);
+                }
             }
             TxSendMode::DumpMsg => {
-                // somehow `BASE64.encode(&tx.message_data())` as suggested by all the explorers
-                // results in a different output that cannot be handled by their inspectors lmao
-                println!("{}", BASE64.encode(&bincode::serialize(&tx).unwrap()))
+                if !is_json {
+                    println!("{}", output.tx_base64);
+                }
+            }
+            TxSendMode::SignOnly => {
+                output.set_sign_only(tx);
+                if !is_json {
+                    output.println_sign_only_text();
+                }
             }
         };
-        Ok(())
+        if is_json {
+            output.println_json();
+        }
+        Ok(output)
+    }
+
+    fn build_and_handle_tx<'a, S: Signer + ?Sized>(
+        &'a self,
+        build_args: BuildTxArgs<'a, S>,
+        compute_budget: ComputeBudgetArgs,
+        send_mode: TxSendMode,
+        args: HandleTxArgs,
+    ) -> Pin<Box<dyn Future<Output = Result<HandleTxOutput, ClientError>> + 'a>> {
+        Box::pin(async move {
+            let compute_budget_ixs = get_compute_budget_ixs_auto_nonblocking(
+                self,
+                &build_args.payer,
+                &build_args.est_cu_ixs(),
+                build_args.luts,
+                &compute_budget.fee_limit(),
+                compute_budget.cu_buffer_ratio,
+            )
+            .await?;
+            let blockhash = match build_args.nonce {
+                Some(nonce) => self.get_durable_nonce(&nonce).await?.durable_nonce,
+                None => self.get_confirmed_blockhash().await?.hash,
+            };
+            let tx = build_args.sign(&build_args.final_ixs(compute_budget_ixs), blockhash)?;
+            self.handle_tx(&tx, send_mode, args).await
+        })
+    }
+
+    async fn rebroadcast_tx<T: SerializableTransaction + Sync>(
+        &self,
+        tx: &T,
+        last_valid_blockheight: u64,
+        args: RebroadcastArgs,
+    ) -> Result<RebroadcastAttempt, ClientError> {
+        rebroadcast_until_expiry_nonblocking(self, tx, last_valid_blockheight, &args).await
+    }
+
+    fn build_and_rebroadcast_tx<'a, S: Signer + ?Sized>(
+        &'a self,
+        build_args: BuildTxArgs<'a, S>,
+        compute_budget: ComputeBudgetArgs,
+        args: RebroadcastArgs,
+    ) -> Pin<Box<dyn Future<Output = RebroadcastResult> + 'a>> {
+        Box::pin(async move {
+            let mut output = RebroadcastOutput::default();
+            if build_args.nonce.is_some() {
+                return Err((output, durable_nonce_rebroadcast_err()));
+            }
+            for _ in 0..args.max_attempts.max(1) {
+                let attempt_res = async {
+                    let compute_budget_ixs = get_compute_budget_ixs_auto_nonblocking(
+                        self,
+                        &build_args.payer,
+                        &build_args.est_cu_ixs(),
+                        build_args.luts,
+                        &compute_budget.fee_limit(),
+                        compute_budget.cu_buffer_ratio,
+                    )
+                    .await?;
+                    let RecentBlockhash {
+                        hash,
+                        last_valid_blockheight,
+                    } = self.get_confirmed_blockhash().await?;
+                    let tx = build_args.sign(&build_args.final_ixs(compute_budget_ixs), hash)?;
+                    self.rebroadcast_tx(&tx, last_valid_blockheight, args).await
+                }
+                .await;
+                let attempt = match attempt_res {
+                    Ok(a) => a,
+                    Err(e) => return Err((output, e)),
+                };
+                let is_expired = attempt.outcome == RebroadcastOutcome::Expired;
+                output.attempts.push(attempt);
+                if !is_expired {
+                    break;
+                }
+            }
+            Ok(output)
+        })
     }
 }
@@ -1,7 +1,14 @@
-use solana_sdk::signer::Signer;
+use solana_client::rpc_client::SerializableTransaction;
+use solana_sdk::{signer::Signer, transaction::VersionedTransaction};
 
 /// Deduplicates a `Vec` of `Signer`s
 pub fn dedup_signers(signers: &mut Vec<Box<dyn Signer>>) {
     signers.sort_by_key(|l| l.pubkey());
     signers.dedup_by(|a, b| a.pubkey() == b.pubkey());
 }
+
+/// Converts a legacy or versioned transaction into a [`VersionedTransaction`]
+pub fn to_versioned_tx<T: SerializableTransaction>(tx: &T) -> VersionedTransaction {
+    // bincode serialization of legacy txs is a valid VersionedTransaction
+    bincode::deserialize(&bincode::serialize(tx).unwrap()).unwrap()
+}
@@ -1,2 +1,3 @@
 mod config_wrapper;
+mod sign_only;
 mod tx_send_mode;
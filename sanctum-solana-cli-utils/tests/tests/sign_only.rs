@@ -0,0 +1,132 @@
+use sanctum_solana_cli_utils::{
+    assemble_signed_tx, merge_signer_signatures, sign_only_versioned_tx, HandleTxArgs, PubkeySrc,
+    SignOnlyError, SignOnlySignatures, SignerSignature, TxOutputFormat, TxSendMode,
+    TxSendingRpcClient,
+};
+use sanctum_solana_client_utils::SortedSigners;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    hash::Hash,
+    message::{v0, VersionedMessage},
+    signature::{Keypair, NullSigner},
+    signer::Signer,
+    system_instruction,
+};
+
+fn transfer_msg(payer: &Keypair, from: &Keypair, nonce: Hash) -> VersionedMessage {
+    VersionedMessage::V0(
+        v0::Message::try_compile(
+            &payer.pubkey(),
+            &[system_instruction::transfer(
+                &from.pubkey(),
+                &payer.pubkey(),
+                1,
+            )],
+            &[],
+            nonce,
+        )
+        .unwrap(),
+    )
+}
+
+#[test]
+fn signer_signature_roundtrip() {
+    let kp = Keypair::new();
+    let ss = SignerSignature {
+        pubkey: kp.pubkey(),
+        signature: kp.sign_message(b"hello"),
+    };
+    let s = ss.to_string();
+    assert_eq!(s, format!("{}={}", ss.pubkey, ss.signature));
+    assert_eq!(s.parse::<SignerSignature>().unwrap(), ss);
+    assert_eq!(
+        serde_json::to_string(&ss).unwrap(),
+        serde_json::to_string(&s).unwrap()
+    );
+    assert!(kp.pubkey().to_string().parse::<SignerSignature>().is_err());
+    assert!(format!("{}=abc", kp.pubkey())
+        .parse::<SignerSignature>()
+        .is_err());
+}
+
+#[test]
+fn sign_separately_then_assemble() {
+    let [payer, from] = [(); 2].map(|_| Keypair::new());
+    let nonce = Hash::new_unique();
+    let message = transfer_msg(&payer, &from, nonce);
+
+    // payer's machine only has payer's keypair
+    let payer_src = PubkeySrc::Signer(Box::new(payer.insecure_clone()));
+    let from_src = PubkeySrc::Pubkey(NullSigner::new(&from.pubkey()));
+    let mut signers = [&payer_src, &from_src];
+    signers.sort_by_key(|s| s.pubkey());
+    let tx = sign_only_versioned_tx(message.clone(), &SortedSigners(&signers)).unwrap();
+    let sigs = SignOnlySignatures::from_tx(&tx);
+    assert_eq!(sigs.present.len(), 1);
+    assert_eq!(sigs.present[0].pubkey, payer.pubkey());
+    assert_eq!(sigs.absent, [from.pubkey()]);
+    assert_eq!(
+        assemble_signed_tx(tx.clone(), []).unwrap_err(),
+        SignOnlyError::MissingSignatures(vec![from.pubkey()])
+    );
+
+    // from's machine signs the same message independently
+    let from_tx = sign_only_versioned_tx(message, &SortedSigners(&[&from])).unwrap();
+    let from_sigs = SignOnlySignatures::from_tx(&from_tx).present;
+
+    let stranger = Keypair::new();
+    let mut bad_tx = tx.clone();
+    assert_eq!(
+        merge_signer_signatures(
+            &mut bad_tx,
+            &[SignerSignature {
+                pubkey: stranger.pubkey(),
+                signature: stranger.sign_message(b"x"),
+            }]
+        )
+        .unwrap_err(),
+        SignOnlyError::NotASigner(stranger.pubkey())
+    );
+    assert_eq!(
+        merge_signer_signatures(
+            &mut bad_tx,
+            &[SignerSignature {
+                pubkey: from.pubkey(),
+                signature: from.sign_message(b"x"),
+            }]
+        )
+        .unwrap_err(),
+        SignOnlyError::InvalidSignature(from.pubkey())
+    );
+
+    let tx = assemble_signed_tx(tx, &from_sigs).unwrap();
+    assert!(tx.verify_with_results().into_iter().all(|v| v));
+    assert_eq!(*tx.message.recent_blockhash(), nonce);
+}
+
+#[test]
+fn handle_tx_sign_only_json() {
+    let [payer, from] = [(); 2].map(|_| Keypair::new());
+    let nonce = Hash::new_unique();
+    let tx = sign_only_versioned_tx(
+        transfer_msg(&payer, &from, nonce),
+        &SortedSigners(&[&payer]),
+    )
+    .unwrap();
+
+    // no RPC calls are made in SignOnly mode
+    let output = RpcClient::new_mock("fails".to_owned())
+        .handle_tx(
+            &tx,
+            TxSendMode::SignOnly,
+            HandleTxArgs::cli_default().with_output_format(TxOutputFormat::Json),
+        )
+        .unwrap();
+    assert_eq!(output.blockhash, Some(nonce.to_string()));
+    assert_eq!(output.signature, Some(tx.signatures[0].to_string()));
+    assert_eq!(
+        output.signers.unwrap(),
+        SignOnlySignatures::from_tx(&tx).present
+    );
+    assert_eq!(output.absent_signers, Some(vec![from.pubkey().to_string()]));
+}
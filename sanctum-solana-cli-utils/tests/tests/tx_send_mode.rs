@@ -28,6 +28,7 @@ fn parse_tx_send_mode_enum() {
         ("send-actual", TxSendMode::SendActual),
         ("sim-only", TxSendMode::SimOnly),
         ("dump-msg", TxSendMode::DumpMsg),
+        ("sign-only", TxSendMode::SignOnly),
     ] {
         assert_eq!(
             Args::parse_from(["cmd", "--send-mode", arg]).send_mode,
@@ -36,6 +37,76 @@ fn parse_tx_send_mode_enum() {
     }
 }
 
+#[cfg(feature = "clap")]
+#[test]
+fn parse_tx_output_format_enum() {
+    use clap::Parser;
+    use sanctum_solana_cli_utils::TxOutputFormat;
+
+    #[derive(Parser, Debug)]
+    #[command(author, version, about)]
+    struct Args {
+        #[arg(
+             long,
+             help = "output format",
+             default_value_t = TxOutputFormat::default(),
+             value_enum,
+         )]
+        pub output: TxOutputFormat,
+    }
+
+    assert_eq!(Args::parse_from(["cmd"]).output, TxOutputFormat::Text);
+    assert_eq!(
+        Args::parse_from(["cmd", "--output", "json"]).output,
+        TxOutputFormat::Json
+    );
+}
+
+#[cfg(feature = "clap")]
+#[test]
+fn parse_compute_budget_args() {
+    use clap::Parser;
+    use sanctum_solana_cli_utils::{ComputeBudgetArgs, DEFAULT_PRIORITY_FEE_LIMIT_LAMPORTS};
+    use sanctum_solana_client_utils::ComputeBudgetFeeLimit;
+
+    #[derive(Parser, Debug)]
+    #[command(author, version, about)]
+    struct Args {
+        #[command(flatten)]
+        pub compute_budget: ComputeBudgetArgs,
+    }
+
+    let default = Args::parse_from(["cmd"]).compute_budget;
+    assert_eq!(default, ComputeBudgetArgs::default());
+    assert_eq!(
+        default.fee_limit(),
+        ComputeBudgetFeeLimit::TotalLamports(DEFAULT_PRIORITY_FEE_LIMIT_LAMPORTS)
+    );
+
+    let args = Args::parse_from([
+        "cmd",
+        "--fee-limit-micro-lamports-per-cu",
+        "5000",
+        "--cu-buffer-ratio",
+        "1.5",
+    ])
+    .compute_budget;
+    assert_eq!(
+        args.fee_limit(),
+        ComputeBudgetFeeLimit::MicroLamportsPerCu(5000)
+    );
+    assert_eq!(args.cu_buffer_ratio, 1.5);
+
+    assert!(Args::try_parse_from([
+        "cmd",
+        "--fee-limit-lamports",
+        "1",
+        "--fee-limit-micro-lamports-per-cu",
+        "1",
+    ])
+    .is_err());
+}
+
 #[test]
 fn parse_tx_send_mode_from_dry_run() {
     #[derive(Parser, Debug)]
@@ -0,0 +1,234 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use solana_program::{pubkey::Pubkey, stake::state::StakeStateV2};
+use solana_sdk::account::Account;
+
+/// The state of a set of accounts at a point in time.
+///
+/// Accounts that did not exist at the time of the snapshot are stored as `None`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccountsSnapshot {
+    pub accounts: BTreeMap<Pubkey, Option<Account>>,
+}
+
+impl AccountsSnapshot {
+    pub fn keys(&self) -> impl Iterator<Item = &Pubkey> {
+        self.accounts.keys()
+    }
+
+    pub fn get(&self, addr: &Pubkey) -> Option<&Account> {
+        self.accounts.get(addr).and_then(Option::as_ref)
+    }
+
+    /// Diffs `self` (before) against `after` over the union of both snapshots' keys
+    pub fn diff(&self, after: &Self) -> AccountsDiff {
+        let keys: Vec<Pubkey> = self.keys().chain(after.keys()).copied().collect();
+        AccountsDiff(
+            keys.into_iter()
+                .map(|k| {
+                    (
+                        k,
+                        AccountDiff::new(self.get(&k).cloned(), after.get(&k).cloned()),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+impl FromIterator<(Pubkey, Option<Account>)> for AccountsSnapshot {
+    fn from_iter<I: IntoIterator<Item = (Pubkey, Option<Account>)>>(iter: I) -> Self {
+        Self {
+            accounts: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Change<T> {
+    pub before: T,
+    pub after: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    pub fn is_changed(&self) -> bool {
+        self.before != self.after
+    }
+}
+
+impl Change<u64> {
+    pub fn delta(&self) -> i128 {
+        i128::from(self.after) - i128::from(self.before)
+    }
+}
+
+/// Nonexistent accounts are treated as having 0 lamports, no owner and empty data
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountDiff {
+    pub before: Option<Account>,
+    pub after: Option<Account>,
+    pub lamports: Change<u64>,
+    pub owner: Change<Option<Pubkey>>,
+    pub data_len: Change<usize>,
+    /// Byte ranges of account data that differ.
+    /// Bytes past the end of the shorter data are considered changed.
+    pub changed_byte_ranges: Vec<Range<usize>>,
+    /// `None` if the account is not a token account owned by a
+    /// token program enabled by this crate's features
+    pub token_amount: Change<Option<u64>>,
+    /// `None` if the account is not a stake account
+    pub stake_state: Change<Option<StakeStateV2>>,
+}
+
+impl AccountDiff {
+    pub fn new(before: Option<Account>, after: Option<Account>) -> Self {
+        let lamports = Change {
+            before: before.as_ref().map_or(0, |a| a.lamports),
+            after: after.as_ref().map_or(0, |a| a.lamports),
+        };
+        let owner = Change {
+            before: before.as_ref().map(|a| a.owner),
+            after: after.as_ref().map(|a| a.owner),
+        };
+        let data_len = Change {
+            before: before.as_ref().map_or(0, |a| a.data.len()),
+            after: after.as_ref().map_or(0, |a| a.data.len()),
+        };
+        let changed_byte_ranges = changed_byte_ranges(
+            before.as_ref().map_or(&[], |a| a.data.as_slice()),
+            after.as_ref().map_or(&[], |a| a.data.as_slice()),
+        );
+        let token_amount = Change {
+            before: before.as_ref().and_then(decode_token_amount),
+            after: after.as_ref().and_then(decode_token_amount),
+        };
+        let stake_state = Change {
+            before: before.as_ref().and_then(decode_stake_state),
+            after: after.as_ref().and_then(decode_stake_state),
+        };
+        Self {
+            before,
+            after,
+            lamports,
+            owner,
+            data_len,
+            changed_byte_ranges,
+            token_amount,
+            stake_state,
+        }
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.before != self.after
+    }
+}
+
+/// Per-account diffs between 2 [`AccountsSnapshot`]s, including unchanged accounts
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccountsDiff(pub BTreeMap<Pubkey, AccountDiff>);
+
+impl AccountsDiff {
+    /// Panics if `addr` was not in either snapshot
+    pub fn account(&self, addr: &Pubkey) -> &AccountDiff {
+        self.0
+            .get(addr)
+            .unwrap_or_else(|| panic!("{addr} not in snapshot"))
+    }
+
+    pub fn changed(&self) -> impl Iterator<Item = (&Pubkey, &AccountDiff)> {
+        self.0.iter().filter(|(_, d)| d.is_changed())
+    }
+
+    pub fn assert_lamports_delta(&self, addr: &Pubkey, expected: i128) {
+        let actual = self.account(addr).lamports.delta();
+        assert_eq!(actual, expected, "lamports delta of {addr}");
+    }
+
+    /// An account that is a token account only before or only after,
+    /// e.g. one that was created or closed, is treated as having 0 balance when it is not.
+    ///
+    /// Panics if `addr` is a token account neither before nor after
+    pub fn assert_token_balance_delta(&self, addr: &Pubkey, expected: i128) {
+        let Change { before, after } = self.account(addr).token_amount;
+        if before.is_none() && after.is_none() {
+            panic!("{addr} is not a token account");
+        }
+        let actual = i128::from(after.unwrap_or(0)) - i128::from(before.unwrap_or(0));
+        assert_eq!(actual, expected, "token balance delta of {addr}");
+    }
+
+    pub fn assert_unchanged(&self, addr: &Pubkey) {
+        assert!(!self.account(addr).is_changed(), "{addr} changed");
+    }
+
+    /// Asserts that no account outside of `addrs` changed.
+    /// Does not assert that every account in `addrs` changed.
+    pub fn assert_only_changed(&self, addrs: &[Pubkey]) {
+        let unexpected: Vec<&Pubkey> = self
+            .changed()
+            .map(|(k, _)| k)
+            .filter(|k| !addrs.contains(k))
+            .collect();
+        assert!(
+            unexpected.is_empty(),
+            "unexpected accounts changed: {unexpected:?}"
+        );
+    }
+}
+
+fn changed_byte_ranges(before: &[u8], after: &[u8]) -> Vec<Range<usize>> {
+    let len = before.len().max(after.len());
+    let mut res: Vec<Range<usize>> = Vec::new();
+    for i in (0..len).filter(|i| before.get(*i) != after.get(*i)) {
+        match res.last_mut() {
+            Some(r) if r.end == i => r.end += 1,
+            _ => res.push(i..i + 1),
+        }
+    }
+    res
+}
+
+#[allow(unused_variables)] // account unused if neither token feature enabled
+fn decode_token_amount(account: &Account) -> Option<u64> {
+    #[cfg(feature = "token")]
+    if account.owner == spl_token::ID {
+        use solana_program::program_pack::Pack;
+
+        return spl_token::state::Account::unpack(&account.data)
+            .ok()
+            .map(|a| a.amount);
+    }
+    #[cfg(feature = "token-2022")]
+    if account.owner == spl_token_2022::ID {
+        use spl_token_2022::extension::StateWithExtensions;
+
+        return StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .ok()
+            .map(|s| s.base.amount);
+    }
+    None
+}
+
+fn decode_stake_state(account: &Account) -> Option<StakeStateV2> {
+    if account.owner != solana_program::stake::program::ID {
+        return None;
+    }
+    bincode::deserialize(&account.data).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn changed_byte_ranges_basic() {
+        assert!(changed_byte_ranges(&[1, 2, 3], &[1, 2, 3]).is_empty());
+        assert_eq!(
+            changed_byte_ranges(&[1, 2, 3, 4, 5], &[0, 2, 0, 0, 5]),
+            [0..1, 2..4]
+        );
+        assert_eq!(changed_byte_ranges(&[1, 2], &[1, 2, 3, 4]), [2..4]);
+        assert_eq!(changed_byte_ranges(&[1, 2, 3], &[]), [0..3]);
+    }
+}
@@ -0,0 +1,88 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use solana_banks_interface::{
+    BanksTransactionResultWithMetadata, BanksTransactionResultWithSimulation, TransactionStatus,
+};
+use solana_program::{hash::Hash, message::Message, pubkey::Pubkey};
+use solana_program_test::BanksClient;
+use solana_sdk::{
+    account::Account, commitment_config::CommitmentLevel, signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+use super::RpcBackend;
+
+// inherent methods take precedence over trait methods of the same name,
+// so these all call BanksClient's own methods
+#[async_trait]
+impl RpcBackend for BanksClient {
+    async fn get_account_with_commitment(
+        &mut self,
+        key: Pubkey,
+        commitment: CommitmentLevel,
+    ) -> Result<Option<Account>, Box<dyn Error + Send + Sync>> {
+        Ok(self.get_account_with_commitment(key, commitment).await?)
+    }
+
+    async fn get_slot(
+        &mut self,
+        commitment: CommitmentLevel,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .get_slot_with_context(tarpc::context::current(), commitment)
+            .await?)
+    }
+
+    async fn get_block_height(
+        &mut self,
+        commitment: CommitmentLevel,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .get_block_height_with_context(tarpc::context::current(), commitment)
+            .await?)
+    }
+
+    async fn get_latest_blockhash_with_commitment(
+        &mut self,
+        commitment: CommitmentLevel,
+    ) -> Result<Option<(Hash, u64)>, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .get_latest_blockhash_with_commitment(commitment)
+            .await?)
+    }
+
+    async fn get_fee_for_message_with_commitment(
+        &mut self,
+        msg: Message,
+        commitment: CommitmentLevel,
+    ) -> Result<Option<u64>, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .get_fee_for_message_with_commitment(msg, commitment)
+            .await?)
+    }
+
+    async fn get_transaction_status(
+        &mut self,
+        signature: Signature,
+    ) -> Result<Option<TransactionStatus>, Box<dyn Error + Send + Sync>> {
+        Ok(self.get_transaction_status(signature).await?)
+    }
+
+    async fn process_transaction_with_metadata(
+        &mut self,
+        tx: VersionedTransaction,
+    ) -> Result<BanksTransactionResultWithMetadata, Box<dyn Error + Send + Sync>> {
+        Ok(self.process_transaction_with_metadata(tx).await?)
+    }
+
+    async fn simulate_transaction_with_commitment(
+        &mut self,
+        tx: VersionedTransaction,
+        commitment: CommitmentLevel,
+    ) -> Result<BanksTransactionResultWithSimulation, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .simulate_transaction_with_commitment(tx, commitment)
+            .await?)
+    }
+}
@@ -0,0 +1,133 @@
+//! The account and ledger state that [`super::BanksRpcServer`] serves requests from
+
+use std::error::Error;
+
+use async_trait::async_trait;
+use solana_banks_interface::{
+    BanksTransactionResultWithMetadata, BanksTransactionResultWithSimulation, TransactionStatus,
+};
+use solana_program::{hash::Hash, message::Message, pubkey::Pubkey, sysvar::Sysvar};
+use solana_sdk::{
+    account::{from_account, Account},
+    commitment_config::CommitmentLevel,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+mod banks_client;
+mod stored_accounts;
+
+pub use stored_accounts::*;
+
+/// Everything a [`super::BanksRpcServer`] needs to serve its RPC methods,
+/// so that new methods only need to be written once for all backends.
+///
+/// Method names and semantics mirror [`solana_program_test::BanksClient`]'s.
+///
+/// Errors that a real RPC would respond with should be [`jsonrpc_core::Error`]s
+/// or [`solana_rpc_client_api::custom_error::RpcCustomError`]s,
+/// all other errors are treated as internal server errors.
+#[async_trait]
+pub trait RpcBackend: Clone + Send + Sync + 'static {
+    async fn get_account_with_commitment(
+        &mut self,
+        key: Pubkey,
+        commitment: CommitmentLevel,
+    ) -> Result<Option<Account>, Box<dyn Error + Send + Sync>>;
+
+    async fn get_slot(
+        &mut self,
+        commitment: CommitmentLevel,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>>;
+
+    async fn get_block_height(
+        &mut self,
+        commitment: CommitmentLevel,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>>;
+
+    /// Returns `(blockhash, last_valid_block_height)`
+    async fn get_latest_blockhash_with_commitment(
+        &mut self,
+        commitment: CommitmentLevel,
+    ) -> Result<Option<(Hash, u64)>, Box<dyn Error + Send + Sync>>;
+
+    /// Returns `None` if the message's blockhash is not valid
+    async fn get_fee_for_message_with_commitment(
+        &mut self,
+        msg: Message,
+        commitment: CommitmentLevel,
+    ) -> Result<Option<u64>, Box<dyn Error + Send + Sync>>;
+
+    async fn get_transaction_status(
+        &mut self,
+        signature: Signature,
+    ) -> Result<Option<TransactionStatus>, Box<dyn Error + Send + Sync>>;
+
+    async fn process_transaction_with_metadata(
+        &mut self,
+        tx: VersionedTransaction,
+    ) -> Result<BanksTransactionResultWithMetadata, Box<dyn Error + Send + Sync>>;
+
+    async fn simulate_transaction_with_commitment(
+        &mut self,
+        tx: VersionedTransaction,
+        commitment: CommitmentLevel,
+    ) -> Result<BanksTransactionResultWithSimulation, Box<dyn Error + Send + Sync>>;
+
+    /// All accounts this backend has, for `getProgramAccounts` and the token methods that scan accounts.
+    ///
+    /// Returns `None` if the backend can't enumerate its accounts, in which case only
+    /// accounts registered with [`super::KnownPubkeys`] or
+    /// written to by transactions sent to the server are scanned.
+    fn account_keys(&self) -> Option<Vec<Pubkey>> {
+        None
+    }
+
+    async fn get_account(
+        &mut self,
+        key: Pubkey,
+    ) -> Result<Option<Account>, Box<dyn Error + Send + Sync>> {
+        self.get_account_with_commitment(key, CommitmentLevel::default())
+            .await
+    }
+
+    async fn get_balance_with_commitment(
+        &mut self,
+        key: Pubkey,
+        commitment: CommitmentLevel,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .get_account_with_commitment(key, commitment)
+            .await?
+            .map_or(0, |a| a.lamports))
+    }
+
+    async fn get_balance(&mut self, key: Pubkey) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        self.get_balance_with_commitment(key, CommitmentLevel::default())
+            .await
+    }
+
+    async fn get_fee_for_message(
+        &mut self,
+        msg: Message,
+    ) -> Result<Option<u64>, Box<dyn Error + Send + Sync>> {
+        self.get_fee_for_message_with_commitment(msg, CommitmentLevel::default())
+            .await
+    }
+
+    async fn get_sysvar_with_commitment<T: Sysvar>(
+        &mut self,
+        commitment: CommitmentLevel,
+    ) -> Result<T, Box<dyn Error + Send + Sync>> {
+        let account = self
+            .get_account_with_commitment(T::id(), commitment)
+            .await?
+            .ok_or("Sysvar not present")?;
+        Ok(from_account(&account).ok_or("Failed to deserialize sysvar")?)
+    }
+
+    async fn get_sysvar<T: Sysvar>(&mut self) -> Result<T, Box<dyn Error + Send + Sync>> {
+        self.get_sysvar_with_commitment(CommitmentLevel::default())
+            .await
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    sync::{Arc, RwLock},
+};
+
+use async_trait::async_trait;
+use sanctum_stored_account::StoredAccount;
+use solana_banks_interface::{
+    BanksTransactionResultWithMetadata, BanksTransactionResultWithSimulation, TransactionStatus,
+};
+use solana_program::{
+    clock::{Clock, MAX_PROCESSING_AGE},
+    epoch_schedule::EpochSchedule,
+    hash::Hash,
+    message::Message,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::{self, Sysvar, SysvarId},
+};
+use solana_sdk::{
+    account::{from_account, Account},
+    commitment_config::CommitmentLevel,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+use super::RpcBackend;
+
+/// Same as the default `lamports_per_signature` of `ProgramTest`
+pub const STORED_ACCOUNTS_BACKEND_LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+#[derive(Debug)]
+struct State {
+    accounts: HashMap<Pubkey, StoredAccount>,
+    slot: u64,
+    blockhash: Hash,
+}
+
+/// A [`RpcBackend`] that serves a plain in-memory map of accounts,
+/// for serving static fixtures without starting a `ProgramTest` bank.
+///
+/// - All commitment levels see the same state
+/// - The `Clock`, `Rent` and `EpochSchedule` sysvars are added with default values if not provided
+/// - Block height is the same as slot
+/// - The latest blockhash is the only valid blockhash,
+///   and fees are [`STORED_ACCOUNTS_BACKEND_LAMPORTS_PER_SIGNATURE`] per signature,
+///   ignoring prioritization fees
+/// - Transactions can't be processed or simulated, requests that need to are responded to with an error
+///
+/// Clones share the same state, so this can be used to change accounts after the server has been spawned.
+#[derive(Clone, Debug)]
+pub struct StoredAccountsBackend(Arc<RwLock<State>>);
+
+fn sysvar_account<T: Sysvar>(sysvar: &T) -> StoredAccount {
+    Account {
+        lamports: 1,
+        data: bincode::serialize(sysvar).unwrap(),
+        owner: sysvar::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+    .into()
+}
+
+fn unsupported(what: &str) -> Box<dyn Error + Send + Sync> {
+    Box::new(jsonrpc_core::Error {
+        code: jsonrpc_core::ErrorCode::MethodNotFound,
+        message: format!("{what} is not supported by StoredAccountsBackend"),
+        data: None,
+    })
+}
+
+impl StoredAccountsBackend {
+    pub fn new<A: Into<StoredAccount>>(accounts: impl IntoIterator<Item = (Pubkey, A)>) -> Self {
+        let mut accounts: HashMap<Pubkey, StoredAccount> = accounts
+            .into_iter()
+            .map(|(pubkey, account)| (pubkey, account.into()))
+            .collect();
+        accounts
+            .entry(Clock::id())
+            .or_insert_with(|| sysvar_account(&Clock::default()));
+        accounts
+            .entry(Rent::id())
+            .or_insert_with(|| sysvar_account(&Rent::default()));
+        accounts
+            .entry(EpochSchedule::id())
+            .or_insert_with(|| sysvar_account(&EpochSchedule::default()));
+        let slot = accounts
+            .get(&Clock::id())
+            .and_then(|a| from_account::<Clock, _>(&Account::from(a.clone())))
+            .map_or(0, |c| c.slot);
+        Self(Arc::new(RwLock::new(State {
+            accounts,
+            slot,
+            blockhash: Hash::new_unique(),
+        })))
+    }
+
+    pub fn set_account(&self, key: Pubkey, account: impl Into<StoredAccount>) {
+        self.0.write().unwrap().accounts.insert(key, account.into());
+    }
+
+    pub fn remove_account(&self, key: &Pubkey) -> Option<StoredAccount> {
+        self.0.write().unwrap().accounts.remove(key)
+    }
+
+    /// Also updates the `Clock` sysvar's slot and epoch
+    pub fn set_slot(&self, slot: u64) {
+        let mut state = self.0.write().unwrap();
+        state.slot = slot;
+        let sysvar = |id: &Pubkey| {
+            state
+                .accounts
+                .get(id)
+                .map(|a| Account::from(a.clone()))
+                .unwrap_or_default()
+        };
+        let epoch_schedule: EpochSchedule =
+            from_account(&sysvar(&EpochSchedule::id())).unwrap_or_default();
+        let mut clock: Clock = from_account(&sysvar(&Clock::id())).unwrap_or_default();
+        clock.slot = slot;
+        clock.epoch = epoch_schedule.get_epoch(slot);
+        state.accounts.insert(Clock::id(), sysvar_account(&clock));
+    }
+
+    pub fn set_blockhash(&self, blockhash: Hash) {
+        self.0.write().unwrap().blockhash = blockhash;
+    }
+}
+
+#[async_trait]
+impl RpcBackend for StoredAccountsBackend {
+    async fn get_account_with_commitment(
+        &mut self,
+        key: Pubkey,
+        _commitment: CommitmentLevel,
+    ) -> Result<Option<Account>, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .0
+            .read()
+            .unwrap()
+            .accounts
+            .get(&key)
+            .map(|a| a.clone().into()))
+    }
+
+    async fn get_slot(
+        &mut self,
+        _commitment: CommitmentLevel,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        Ok(self.0.read().unwrap().slot)
+    }
+
+    async fn get_block_height(
+        &mut self,
+        _commitment: CommitmentLevel,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        Ok(self.0.read().unwrap().slot)
+    }
+
+    async fn get_latest_blockhash_with_commitment(
+        &mut self,
+        _commitment: CommitmentLevel,
+    ) -> Result<Option<(Hash, u64)>, Box<dyn Error + Send + Sync>> {
+        let state = self.0.read().unwrap();
+        Ok(Some((
+            state.blockhash,
+            state.slot + MAX_PROCESSING_AGE as u64,
+        )))
+    }
+
+    async fn get_fee_for_message_with_commitment(
+        &mut self,
+        msg: Message,
+        _commitment: CommitmentLevel,
+    ) -> Result<Option<u64>, Box<dyn Error + Send + Sync>> {
+        if msg.recent_blockhash != self.0.read().unwrap().blockhash {
+            return Ok(None);
+        }
+        Ok(Some(
+            u64::from(msg.header.num_required_signatures)
+                * STORED_ACCOUNTS_BACKEND_LAMPORTS_PER_SIGNATURE,
+        ))
+    }
+
+    async fn get_transaction_status(
+        &mut self,
+        _signature: Signature,
+    ) -> Result<Option<TransactionStatus>, Box<dyn Error + Send + Sync>> {
+        Ok(None)
+    }
+
+    async fn process_transaction_with_metadata(
+        &mut self,
+        _tx: VersionedTransaction,
+    ) -> Result<BanksTransactionResultWithMetadata, Box<dyn Error + Send + Sync>> {
+        Err(unsupported("Transaction processing"))
+    }
+
+    async fn simulate_transaction_with_commitment(
+        &mut self,
+        _tx: VersionedTransaction,
+        _commitment: CommitmentLevel,
+    ) -> Result<BanksTransactionResultWithSimulation, Box<dyn Error + Send + Sync>> {
+        Err(unsupported("Transaction simulation"))
+    }
+
+    fn account_keys(&self) -> Option<Vec<Pubkey>> {
+        Some(self.0.read().unwrap().accounts.keys().copied().collect())
+    }
+}
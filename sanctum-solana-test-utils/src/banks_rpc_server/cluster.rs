@@ -0,0 +1,187 @@
+//! Cluster state methods, served from the bank's sysvars
+
+use std::error::Error;
+
+use solana_program::{epoch_schedule::EpochSchedule, pubkey::Pubkey, rent::Rent};
+use solana_rpc_client_api::{
+    config::{
+        RpcAccountInfoConfig, RpcContextConfig, RpcProgramAccountsConfig,
+        RpcSimulateTransactionConfig,
+    },
+    custom_error::RpcCustomError,
+};
+use solana_sdk::{
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    epoch_info::EpochInfo,
+    message::VersionedMessage,
+};
+
+use super::{to_legacy_message, BanksRpcServer, RpcBackend};
+
+/// Configs of requests that are served at the bank of a given commitment
+pub(super) trait ContextCfg {
+    fn commitment(&self) -> Option<CommitmentConfig>;
+
+    fn min_context_slot(&self) -> Option<u64>;
+}
+
+impl ContextCfg for CommitmentConfig {
+    fn commitment(&self) -> Option<CommitmentConfig> {
+        Some(*self)
+    }
+
+    fn min_context_slot(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl ContextCfg for RpcContextConfig {
+    fn commitment(&self) -> Option<CommitmentConfig> {
+        self.commitment
+    }
+
+    fn min_context_slot(&self) -> Option<u64> {
+        self.min_context_slot
+    }
+}
+
+impl ContextCfg for RpcAccountInfoConfig {
+    fn commitment(&self) -> Option<CommitmentConfig> {
+        self.commitment
+    }
+
+    fn min_context_slot(&self) -> Option<u64> {
+        self.min_context_slot
+    }
+}
+
+impl ContextCfg for RpcProgramAccountsConfig {
+    fn commitment(&self) -> Option<CommitmentConfig> {
+        self.account_config.commitment
+    }
+
+    fn min_context_slot(&self) -> Option<u64> {
+        self.account_config.min_context_slot
+    }
+}
+
+impl ContextCfg for RpcSimulateTransactionConfig {
+    fn commitment(&self) -> Option<CommitmentConfig> {
+        self.commitment
+    }
+
+    fn min_context_slot(&self) -> Option<u64> {
+        self.min_context_slot
+    }
+}
+
+impl<T: ContextCfg> ContextCfg for Option<T> {
+    fn commitment(&self) -> Option<CommitmentConfig> {
+        self.as_ref().and_then(T::commitment)
+    }
+
+    fn min_context_slot(&self) -> Option<u64> {
+        self.as_ref().and_then(T::min_context_slot)
+    }
+}
+
+impl<B: RpcBackend> BanksRpcServer<B> {
+    /// Returns the slot of the bank at `cfg`'s commitment,
+    /// or [`RpcCustomError::MinContextSlotNotReached`] if it's below `cfg`'s `minContextSlot`.
+    ///
+    /// Responses with a context should sample this before serving the request,
+    /// so that they report the slot they were served at even if the bank advances during the request.
+    pub(super) async fn context_slot(
+        &mut self,
+        cfg: &impl ContextCfg,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let context_slot = self.backend.get_slot(commitment_level(cfg)).await?;
+        if cfg.min_context_slot().is_some_and(|min| context_slot < min) {
+            return Err(Box::new(RpcCustomError::MinContextSlotNotReached {
+                context_slot,
+            }));
+        }
+        Ok(context_slot)
+    }
+
+    pub async fn get_slot(
+        &mut self,
+        cfg: Option<RpcContextConfig>,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        self.context_slot(&cfg).await
+    }
+
+    pub async fn get_epoch_info(
+        &mut self,
+        cfg: Option<RpcContextConfig>,
+    ) -> Result<EpochInfo, Box<dyn Error + Send + Sync>> {
+        let commitment = commitment_level(&cfg);
+        let absolute_slot = self.context_slot(&cfg).await?;
+        let block_height = self.backend.get_block_height(commitment).await?;
+        let epoch_schedule: EpochSchedule =
+            self.backend.get_sysvar_with_commitment(commitment).await?;
+        let (epoch, slot_index) = epoch_schedule.get_epoch_and_slot_index(absolute_slot);
+        Ok(EpochInfo {
+            epoch,
+            slot_index,
+            slots_in_epoch: epoch_schedule.get_slots_in_epoch(epoch),
+            absolute_slot,
+            block_height,
+            // BanksClient doesn't expose the bank's transaction count
+            transaction_count: None,
+        })
+    }
+
+    pub async fn get_balance(
+        &mut self,
+        key: Pubkey,
+        cfg: Option<RpcContextConfig>,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        self.backend
+            .get_balance_with_commitment(key, commitment_level(&cfg))
+            .await
+    }
+
+    pub async fn get_minimum_balance_for_rent_exemption(
+        &mut self,
+        data_len: usize,
+        cfg: Option<CommitmentConfig>,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let rent: Rent = self
+            .backend
+            .get_sysvar_with_commitment(commitment_level(&cfg))
+            .await?;
+        Ok(rent.minimum_balance(data_len))
+    }
+
+    /// Returns `None` if the message's blockhash is not valid
+    pub async fn get_fee_for_message(
+        &mut self,
+        msg: &VersionedMessage,
+        cfg: Option<RpcContextConfig>,
+    ) -> Result<Option<u64>, Box<dyn Error + Send + Sync>> {
+        let loaded_addresses = self.load_addresses(msg).await?.ok_or_else(|| {
+            jsonrpc_core::Error::invalid_params(
+                "invalid transaction message: could not load address lookup table",
+            )
+        })?;
+        self.backend
+            .get_fee_for_message_with_commitment(
+                to_legacy_message(msg, &loaded_addresses),
+                commitment_level(&cfg),
+            )
+            .await
+    }
+
+    pub async fn get_epoch_schedule(
+        &mut self,
+    ) -> Result<EpochSchedule, Box<dyn Error + Send + Sync>> {
+        self.backend
+            .get_sysvar_with_commitment(CommitmentLevel::default())
+            .await
+    }
+}
+
+fn commitment_level(cfg: &impl ContextCfg) -> CommitmentLevel {
+    cfg.commitment().unwrap_or_default().commitment
+}
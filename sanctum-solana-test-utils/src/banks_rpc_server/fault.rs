@@ -0,0 +1,174 @@
+//! Scriptable fault injection for testing how clients handle misbehaving RPCs
+
+use std::{
+    error::Error,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use hyper::StatusCode;
+use serde_json::Value;
+use solana_rpc_client_api::custom_error::RpcCustomError;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+
+use super::{
+    json_rpc::{
+        decode_wire_tx, deser_send_transaction_params, JsonRpcErrResp, JsonRpcReq, JsonRpcResp,
+        RpcMethod,
+    },
+    to_json_rpc_err_resp, BanksRpcServer, RpcBackend,
+};
+
+/// What to do to a request that a [`FaultRule`] applies to
+#[derive(Clone, Debug)]
+pub enum Fault {
+    /// Wait before responding. Other faults of the same request still apply after the delay.
+    Delay(Duration),
+
+    /// Respond with this HTTP status code and an empty body, e.g. 429 Too Many Requests
+    HttpStatus(u16),
+
+    /// Respond with this JSON-RPC error object
+    JsonRpcError(jsonrpc_core::Error),
+
+    /// Respond with [`RpcCustomError::MinContextSlotNotReached`] at the current slot
+    MinContextSlotNotReached,
+
+    /// Handle the request normally, but overwrite `context.slot` of the response with this slot
+    StaleContextSlot(u64),
+
+    /// Close the connection without responding
+    DropConnection,
+
+    /// Respond to `sendTransaction` with the tx's signature without processing it,
+    /// like a tx that never reaches the leader. Does nothing for other methods
+    DropTransaction,
+}
+
+#[derive(Clone, Debug)]
+pub struct FaultRule {
+    /// JSON-RPC method name this rule applies to, e.g. `getAccountInfo`.
+    /// `None` applies to all methods.
+    pub method: Option<String>,
+
+    pub fault: Fault,
+
+    /// Only apply to the first `times` matching requests, after which this rule is removed.
+    /// `None` applies to all matching requests.
+    pub times: Option<usize>,
+}
+
+/// Handle to a [`BanksRpcServer`]'s fault rules that can be used to change them while it's running.
+///
+/// Rules are applied to each request in order.
+#[derive(Clone, Debug, Default)]
+pub struct FaultInjector(Arc<Mutex<Vec<FaultRule>>>);
+
+impl FaultInjector {
+    pub fn set_rules(&self, rules: Vec<FaultRule>) {
+        *self.0.lock().unwrap() = rules;
+    }
+
+    pub fn push_rule(&self, rule: FaultRule) {
+        self.0.lock().unwrap().push(rule);
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    /// The current rules. Rules whose `times` ran out are no longer included
+    pub fn rules(&self) -> Vec<FaultRule> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Returns the faults of all rules that apply to `method`,
+    /// counting this request against their `times`
+    fn take_matching(&self, method: &str) -> Vec<Fault> {
+        let mut rules = self.0.lock().unwrap();
+        let mut res = Vec::new();
+        rules.retain_mut(|rule| {
+            if rule.method.as_ref().is_some_and(|m| m != method) {
+                return true;
+            }
+            res.push(rule.fault.clone());
+            match &mut rule.times {
+                Some(times) => {
+                    *times = times.saturating_sub(1);
+                    *times > 0
+                }
+                None => true,
+            }
+        });
+        res
+    }
+}
+
+/// Faults that affect the entire HTTP response instead of a single JSON-RPC response
+pub(super) enum HttpFault {
+    Status(StatusCode),
+    DropConnection,
+}
+
+impl<B: RpcBackend> BanksRpcServer<B> {
+    pub fn with_fault_rules(self, rules: Vec<FaultRule>) -> Self {
+        self.faults.set_rules(rules);
+        self
+    }
+
+    /// Returns a handle to change this server's fault rules after it has been spawned
+    pub fn fault_injector(&self) -> FaultInjector {
+        self.faults.clone()
+    }
+
+    pub(super) async fn handle_req_with_faults(
+        &mut self,
+        req: JsonRpcReq,
+    ) -> Result<Result<Value, HttpFault>, Box<dyn Error + Send + Sync>> {
+        let mut stale_slot = None;
+        for fault in self.faults.take_matching(&req.method.name()) {
+            match fault {
+                Fault::Delay(d) => tokio::time::sleep(d).await,
+                Fault::HttpStatus(status) => {
+                    return Ok(Err(HttpFault::Status(StatusCode::from_u16(status)?)))
+                }
+                Fault::JsonRpcError(e) => return Ok(Ok(JsonRpcErrResp::new(req.id, e).into())),
+                Fault::MinContextSlotNotReached => {
+                    let context_slot = self.curr_slot().await;
+                    return Ok(Ok(JsonRpcErrResp::new(
+                        req.id,
+                        RpcCustomError::MinContextSlotNotReached { context_slot }.into(),
+                    )
+                    .into()));
+                }
+                Fault::StaleContextSlot(slot) => stale_slot = Some(slot),
+                Fault::DropConnection => return Ok(Err(HttpFault::DropConnection)),
+                Fault::DropTransaction if req.method == RpcMethod::SendTransaction => {
+                    let id = req.id;
+                    return Ok(Ok(match dropped_tx_signature(req.params) {
+                        Ok(signature) => JsonRpcResp::new(id, signature.to_string()).into(),
+                        Err(e) => to_json_rpc_err_resp(id, e)?,
+                    }));
+                }
+                Fault::DropTransaction => (),
+            }
+        }
+        let mut resp = self.handle_req(req).await?;
+        if let Some(slot) = stale_slot {
+            if let Some(context_slot) = resp.pointer_mut("/result/context/slot") {
+                *context_slot = slot.into();
+            }
+        }
+        Ok(Ok(resp))
+    }
+}
+
+fn dropped_tx_signature(params: Value) -> Result<Signature, Box<dyn Error + Send + Sync>> {
+    let (encoded_tx, cfg) = deser_send_transaction_params(params)?;
+    let encoding = cfg
+        .and_then(|cfg| cfg.encoding)
+        .unwrap_or(UiTransactionEncoding::Base58);
+    let tx = decode_wire_tx(&encoded_tx, encoding)?;
+    Ok(tx.signatures.first().copied().unwrap_or_default())
+}
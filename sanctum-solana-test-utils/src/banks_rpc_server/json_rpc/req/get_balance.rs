@@ -0,0 +1,18 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client_api::config::RpcContextConfig;
+
+#[derive(Deserialize)]
+struct GetBalanceParams(
+    #[serde(with = "As::<DisplayFromStr>")] Pubkey,
+    #[serde(default)] Option<RpcContextConfig>,
+);
+
+pub fn deser_get_balance_params(
+    params: Value,
+) -> Result<(Pubkey, Option<RpcContextConfig>), serde_json::Error> {
+    let GetBalanceParams(key, cfg) = serde_json::from_value(params)?;
+    Ok((key, cfg))
+}
@@ -0,0 +1,25 @@
+use data_encoding::BASE64;
+use serde::Deserialize;
+use serde_json::Value;
+use solana_rpc_client_api::config::RpcContextConfig;
+use solana_sdk::message::VersionedMessage;
+
+#[derive(Deserialize)]
+struct GetFeeForMessageParams(String, #[serde(default)] Option<RpcContextConfig>);
+
+pub fn deser_get_fee_for_message_params(
+    params: Value,
+) -> Result<(String, Option<RpcContextConfig>), serde_json::Error> {
+    let GetFeeForMessageParams(encoded_msg, cfg) = serde_json::from_value(params)?;
+    Ok((encoded_msg, cfg))
+}
+
+/// getFeeForMessage only accepts base64 encoded messages
+pub fn decode_wire_msg(encoded: &str) -> Result<VersionedMessage, jsonrpc_core::Error> {
+    let bytes = BASE64
+        .decode(encoded.as_bytes())
+        .map_err(|e| jsonrpc_core::Error::invalid_params(format!("invalid base64: {e}")))?;
+    bincode::deserialize(&bytes).map_err(|e| {
+        jsonrpc_core::Error::invalid_params(format!("failed to deserialize message: {e}"))
+    })
+}
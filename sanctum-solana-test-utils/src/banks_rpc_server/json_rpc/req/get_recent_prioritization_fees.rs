@@ -0,0 +1,23 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_program::pubkey::Pubkey;
+
+#[derive(Deserialize)]
+struct WritableAccounts(#[serde(with = "As::<Vec<DisplayFromStr>>")] Vec<Pubkey>);
+
+/// The only param is optional, so unlike other methods, this can't be a tuple struct
+/// since single-field tuple structs are deserialized as their inner field
+pub fn deser_get_recent_prioritization_fees_params(
+    params: Value,
+) -> Result<Vec<Pubkey>, serde_json::Error> {
+    let params: Vec<WritableAccounts> = match params {
+        Value::Null => Vec::new(),
+        params => serde_json::from_value(params)?,
+    };
+    Ok(params
+        .into_iter()
+        .next()
+        .map(|WritableAccounts(accounts)| accounts)
+        .unwrap_or_default())
+}
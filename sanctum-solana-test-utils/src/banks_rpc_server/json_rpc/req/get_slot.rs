@@ -0,0 +1,7 @@
+use serde_json::Value;
+use solana_rpc_client_api::config::RpcContextConfig;
+
+pub fn deser_get_slot_params(params: Value) -> Result<Option<RpcContextConfig>, serde_json::Error> {
+    let (res,) = serde_json::from_value(params)?;
+    Ok(res)
+}
@@ -0,0 +1,19 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client_api::config::{RpcAccountInfoConfig, RpcTokenAccountsFilter};
+
+#[derive(Deserialize)]
+struct GetTokenAccountsByOwnerParams(
+    #[serde(with = "As::<DisplayFromStr>")] Pubkey,
+    RpcTokenAccountsFilter,
+    #[serde(default)] Option<RpcAccountInfoConfig>,
+);
+
+pub fn deser_get_token_accounts_by_owner_params(
+    params: Value,
+) -> Result<(Pubkey, RpcTokenAccountsFilter, Option<RpcAccountInfoConfig>), serde_json::Error> {
+    let GetTokenAccountsByOwnerParams(owner, filter, cfg) = serde_json::from_value(params)?;
+    Ok((owner, filter, cfg))
+}
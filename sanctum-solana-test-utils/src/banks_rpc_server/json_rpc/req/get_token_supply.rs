@@ -0,0 +1,18 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+#[derive(Deserialize)]
+struct GetTokenSupplyParams(
+    #[serde(with = "As::<DisplayFromStr>")] Pubkey,
+    #[serde(default)] Option<CommitmentConfig>,
+);
+
+pub fn deser_get_token_supply_params(
+    params: Value,
+) -> Result<(Pubkey, Option<CommitmentConfig>), serde_json::Error> {
+    let GetTokenSupplyParams(mint, cfg) = serde_json::from_value(params)?;
+    Ok((mint, cfg))
+}
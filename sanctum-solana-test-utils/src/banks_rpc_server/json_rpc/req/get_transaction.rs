@@ -0,0 +1,21 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_rpc_client_api::config::{RpcEncodingConfigWrapper, RpcTransactionConfig};
+use solana_sdk::signature::Signature;
+
+#[derive(Deserialize)]
+struct GetTransactionParams(
+    #[serde(with = "As::<DisplayFromStr>")] Signature,
+    #[serde(default)] Option<RpcEncodingConfigWrapper<RpcTransactionConfig>>,
+);
+
+pub fn deser_get_transaction_params(
+    params: Value,
+) -> Result<(Signature, RpcTransactionConfig), serde_json::Error> {
+    let GetTransactionParams(sig, cfg) = serde_json::from_value(params)?;
+    Ok((
+        sig,
+        cfg.map_or_else(Default::default, |c| c.convert_to_current()),
+    ))
+}
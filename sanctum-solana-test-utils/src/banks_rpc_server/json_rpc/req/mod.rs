@@ -1,27 +1,93 @@
+use std::error::Error;
+
+use hyper::body::{Buf, Bytes};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use super::JsonRpc2Ident;
 
 mod get_account_info;
+mod get_balance;
+mod get_epoch_info;
+mod get_fee_for_message;
 mod get_latest_blockhash;
+mod get_minimum_balance_for_rent_exemption;
 mod get_multiple_accounts;
+mod get_program_accounts;
+mod get_recent_prioritization_fees;
+mod get_signature_statuses;
+mod get_slot;
+mod get_token_account_balance;
+mod get_token_accounts_by_delegate;
+mod get_token_accounts_by_owner;
+mod get_token_largest_accounts;
+mod get_token_supply;
+mod get_transaction;
+mod is_blockhash_valid;
+mod pubsub;
+mod send_transaction;
+mod simulate_transaction;
 
 pub use get_account_info::*;
+pub use get_balance::*;
+pub use get_epoch_info::*;
+pub use get_fee_for_message::*;
 pub use get_latest_blockhash::*;
+pub use get_minimum_balance_for_rent_exemption::*;
 pub use get_multiple_accounts::*;
+pub use get_program_accounts::*;
+pub use get_recent_prioritization_fees::*;
+pub use get_signature_statuses::*;
+pub use get_slot::*;
+pub use get_token_account_balance::*;
+pub use get_token_accounts_by_delegate::*;
+pub use get_token_accounts_by_owner::*;
+pub use get_token_largest_accounts::*;
+pub use get_token_supply::*;
+pub use get_transaction::*;
+pub use is_blockhash_valid::*;
+pub use pubsub::*;
+pub use send_transaction::*;
+pub use simulate_transaction::*;
 
 // TODO: other methods
 /// solana_rpc_client_api::request::RpcRequest doesn't implement Serialize or Deserialize, or TryFromStr to use with #[serde(with = "As::<DisplayFromStr>")],
 /// so we're redefining it here
-#[allow(clippy::enum_variant_names)] // common "Get" prefix
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum RpcMethod {
     GetAccountInfo,
+    GetBalance,
+    GetEpochInfo,
+    GetEpochSchedule,
+    GetFeeForMessage,
     GetLatestBlockhash,
+    GetMinimumBalanceForRentExemption,
     GetMultipleAccounts,
+    GetProgramAccounts,
+    GetRecentPrioritizationFees,
+    GetSignatureStatuses,
+    GetSlot,
+    GetTokenAccountBalance,
+    GetTokenAccountsByDelegate,
+    GetTokenAccountsByOwner,
+    GetTokenLargestAccounts,
+    GetTokenSupply,
+    GetTransaction,
     GetVersion, // many RpcClient methods call this method before calling the actual method
+    IsBlockhashValid,
+    SendTransaction,
+    SimulateTransaction,
+}
+
+impl RpcMethod {
+    /// The JSON-RPC method name, e.g. `getAccountInfo`
+    pub fn name(&self) -> String {
+        match serde_json::to_value(self).unwrap() {
+            Value::String(s) => s,
+            _ => unreachable!(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -31,3 +97,16 @@ pub struct JsonRpcReq {
     pub method: RpcMethod,
     pub params: Value,
 }
+
+/// Parses a HTTP request body as either a batch of requests or a single request.
+///
+/// Returns `(requests, is_batch)`
+pub fn parse_reqs(body: Bytes) -> Result<(Vec<JsonRpcReq>, bool), Box<dyn Error + Send + Sync>> {
+    if let Ok(v) = serde_json::from_reader::<_, Vec<JsonRpcReq>>(body.clone().reader()) {
+        Ok((v, true))
+    } else if let Ok(r) = serde_json::from_reader(body.reader()) {
+        Ok((vec![r], false))
+    } else {
+        Err("Invalid request".into())
+    }
+}
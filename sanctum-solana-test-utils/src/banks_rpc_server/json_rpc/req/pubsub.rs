@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_rpc_client_api::config::RpcSignatureSubscribeConfig;
+use solana_sdk::signature::Signature;
+
+use crate::banks_rpc_server::json_rpc::JsonRpc2Ident;
+
+/// Methods supported by the WebSocket pubsub endpoint
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PubsubMethod {
+    AccountSubscribe,
+    AccountUnsubscribe,
+    SignatureSubscribe,
+    SignatureUnsubscribe,
+    SlotSubscribe,
+    SlotUnsubscribe,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PubsubReq {
+    pub jsonrpc: JsonRpc2Ident,
+    pub id: u64,
+    pub method: PubsubMethod,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Deserialize)]
+struct SignatureSubscribeParams(
+    #[serde(with = "As::<DisplayFromStr>")] Signature,
+    #[serde(default)] Option<RpcSignatureSubscribeConfig>,
+);
+
+pub fn deser_signature_subscribe_params(
+    params: Value,
+) -> Result<(Signature, Option<RpcSignatureSubscribeConfig>), serde_json::Error> {
+    let SignatureSubscribeParams(sig, cfg) = serde_json::from_value(params)?;
+    Ok((sig, cfg))
+}
+
+pub fn deser_unsubscribe_params(params: Value) -> Result<u64, serde_json::Error> {
+    let (subscription,) = serde_json::from_value(params)?;
+    Ok(subscription)
+}
@@ -0,0 +1,40 @@
+use data_encoding::BASE64;
+use serde::Deserialize;
+use serde_json::Value;
+use solana_rpc_client_api::config::RpcSendTransactionConfig;
+use solana_sdk::{bs58, transaction::VersionedTransaction};
+use solana_transaction_status::UiTransactionEncoding;
+
+#[derive(Deserialize)]
+struct SendTransactionParams(String, #[serde(default)] Option<RpcSendTransactionConfig>);
+
+pub fn deser_send_transaction_params(
+    params: Value,
+) -> Result<(String, Option<RpcSendTransactionConfig>), serde_json::Error> {
+    let SendTransactionParams(encoded_tx, cfg) = serde_json::from_value(params)?;
+    Ok((encoded_tx, cfg))
+}
+
+/// Decodes a base58 or base64 encoded bincode serialized transaction,
+/// the wire format used by `sendTransaction` and `simulateTransaction`
+pub fn decode_wire_tx(
+    encoded: &str,
+    encoding: UiTransactionEncoding,
+) -> Result<VersionedTransaction, jsonrpc_core::Error> {
+    let bytes = match encoding {
+        UiTransactionEncoding::Base58 => bs58::decode(encoded)
+            .into_vec()
+            .map_err(|e| jsonrpc_core::Error::invalid_params(format!("invalid base58: {e}")))?,
+        UiTransactionEncoding::Base64 => BASE64
+            .decode(encoded.as_bytes())
+            .map_err(|e| jsonrpc_core::Error::invalid_params(format!("invalid base64: {e}")))?,
+        _ => {
+            return Err(jsonrpc_core::Error::invalid_params(format!(
+                "unsupported encoding: {encoding}. Supported encodings: base58, base64"
+            )))
+        }
+    };
+    bincode::deserialize(&bytes).map_err(|e| {
+        jsonrpc_core::Error::invalid_params(format!("failed to deserialize transaction: {e}"))
+    })
+}
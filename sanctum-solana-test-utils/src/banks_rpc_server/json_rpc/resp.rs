@@ -49,3 +49,60 @@ impl<T: Serialize> From<JsonRpcResp<T>> for Value {
         serde_json::to_value(value).unwrap()
     }
 }
+
+/// A JSON-RPC response with an `error` object in place of `result`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JsonRpcErrResp {
+    pub jsonrpc: JsonRpc2Ident,
+    pub id: u64,
+    pub error: jsonrpc_core::Error,
+}
+
+impl JsonRpcErrResp {
+    pub fn new(id: u64, error: jsonrpc_core::Error) -> Self {
+        Self {
+            jsonrpc: Default::default(),
+            id,
+            error,
+        }
+    }
+}
+
+impl From<JsonRpcErrResp> for Value {
+    fn from(value: JsonRpcErrResp) -> Self {
+        serde_json::to_value(value).unwrap()
+    }
+}
+
+/// A pubsub notification pushed to a WebSocket subscriber
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JsonRpcNotification<T> {
+    pub jsonrpc: JsonRpc2Ident,
+    pub method: String,
+    pub params: NotificationParams<T>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NotificationParams<T> {
+    pub result: T,
+    pub subscription: u64,
+}
+
+impl<T> JsonRpcNotification<T> {
+    pub fn new(method: &str, subscription: u64, result: T) -> Self {
+        Self {
+            jsonrpc: Default::default(),
+            method: method.to_owned(),
+            params: NotificationParams {
+                result,
+                subscription,
+            },
+        }
+    }
+}
+
+impl<T: Serialize> From<JsonRpcNotification<T>> for Value {
+    fn from(value: JsonRpcNotification<T>) -> Self {
+        serde_json::to_value(value).unwrap()
+    }
+}
@@ -3,35 +3,107 @@
 
 use http_body_util::{BodyExt, Full};
 use hyper::{
-    body::{Buf, Bytes, Incoming},
+    body::{Bytes, Incoming},
     server::conn::http1,
     service::Service,
     Request, Response,
 };
 use hyper_util::rt::TokioIo;
 use serde_json::Value;
-use solana_account_decoder::{UiAccount, UiAccountEncoding, UiDataSliceConfig};
+use solana_account_decoder::{
+    parse_account_data::{AccountAdditionalDataV2, SplTokenAdditionalData},
+    parse_token::{get_token_account_mint, is_known_spl_token_id},
+    UiAccount, UiAccountEncoding, UiDataSliceConfig,
+};
+use solana_banks_interface::{
+    BanksTransactionResultWithSimulation, TransactionConfirmationStatus as BanksConfirmationStatus,
+    TransactionSimulationDetails,
+};
 use solana_program::{clock::Clock, pubkey::Pubkey};
-use solana_program_test::BanksClient;
+use solana_program_test::{BanksClient, BanksTransactionResultWithMetadata};
 use solana_rpc_client_api::{
-    config::RpcAccountInfoConfig,
-    response::{RpcBlockhash, RpcVersionInfo},
+    config::{
+        RpcAccountInfoConfig, RpcContextConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig,
+        RpcSignatureStatusConfig, RpcSimulateTransactionConfig, RpcTransactionConfig,
+    },
+    custom_error::RpcCustomError,
+    filter::RpcFilterType,
+    response::{RpcBlockhash, RpcKeyedAccount, RpcSimulateTransactionResult, RpcVersionInfo},
+};
+use solana_sdk::{
+    account::Account,
+    address_lookup_table::state::AddressLookupTable,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    hash::Hash,
+    message::{
+        v0::{LoadedAddresses, MessageAddressTableLookup},
+        AccountKeys, Message, VersionedMessage,
+    },
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use solana_transaction_status::{
+    map_inner_instructions, ConfirmedTransactionWithStatusMeta, EncodeError,
+    EncodedConfirmedTransactionWithStatusMeta, TransactionConfirmationStatus, TransactionStatus,
+    TransactionStatusMeta, TransactionWithStatusMeta, UiInnerInstructions, UiTransactionEncoding,
+    VersionedTransactionWithStatusMeta,
 };
-use solana_sdk::{account::Account, commitment_config::CommitmentConfig};
-use std::{cmp, error::Error, future::Future, pin::Pin};
-use tokio::{net::TcpListener, task::JoinHandle};
+use spl_token_2022::{
+    extension::{
+        interest_bearing_mint::InterestBearingConfig, BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::Mint,
+};
+use std::{
+    cmp,
+    collections::{BTreeSet, HashMap},
+    error::Error,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, RwLock},
+};
+use tokio::{net::TcpListener, sync::broadcast, task::JoinHandle};
 
 use crate::banks_rpc_server::json_rpc::{
     deser_get_multiple_accounts_params, JsonRpcReq, JsonRpcResp, RpcMethod,
 };
 
+use self::fault::HttpFault;
+use self::pubsub::{is_ws_upgrade, TxLanded, TX_LANDED_CHANNEL_CAPACITY};
+use self::record::Recorder;
+
 use self::json_rpc::{
-    deser_get_account_info_params, deser_get_latest_blockhash_params, to_http_resp,
+    decode_wire_msg, decode_wire_tx, deser_get_account_info_params, deser_get_balance_params,
+    deser_get_epoch_info_params, deser_get_fee_for_message_params,
+    deser_get_latest_blockhash_params, deser_get_minimum_balance_for_rent_exemption_params,
+    deser_get_program_accounts_params, deser_get_recent_prioritization_fees_params,
+    deser_get_signature_statuses_params, deser_get_slot_params,
+    deser_get_token_account_balance_params, deser_get_token_accounts_by_delegate_params,
+    deser_get_token_accounts_by_owner_params, deser_get_token_largest_accounts_params,
+    deser_get_token_supply_params, deser_get_transaction_params, deser_is_blockhash_valid_params,
+    deser_send_transaction_params, deser_simulate_transaction_params, parse_reqs, to_http_resp,
+    JsonRpcErrResp,
 };
 
+mod backend;
+mod cluster;
+mod fault;
 mod json_rpc;
+mod prioritization_fee;
+mod pubsub;
+mod record;
+mod replay;
+mod rpc_program_test;
+mod token;
 
-/// A simulated solana RPC server backed by a [`BanksClient`]
+pub use backend::*;
+pub use fault::{Fault, FaultInjector, FaultRule};
+pub use prioritization_fee::PrioritizationFeeSchedule;
+pub use replay::ReplayRpcServer;
+pub use rpc_program_test::RpcProgramTest;
+
+/// A simulated solana RPC server backed by a [`RpcBackend`],
+/// a [`BanksClient`] by default
 ///
 /// Be sure to annotate tests using this with
 ///
@@ -42,12 +114,52 @@ mod json_rpc;
 /// With a blocking/sync solana `RpcClient`, you'll get a panic with
 /// `can call blocking only when running on the multi-threaded runtime`
 #[derive(Clone)]
-pub struct BanksRpcServer {
-    // TODO: change this to BanksServer when solana makes it easier
+pub struct BanksRpcServer<B = BanksClient> {
+    // TODO: add a BanksServer backend when solana makes it easier
     // to construct them from ProgramTest
-    bc: BanksClient,
+    backend: B,
+
+    /// Transactions that landed via `sendTransaction`, for `getTransaction`.
+    /// `BanksClient` only keeps signature statuses, not the transactions themselves.
+    txs: Arc<RwLock<HashMap<Signature, ConfirmedTransactionWithStatusMeta>>>,
+
+    /// Accounts to scan for `getProgramAccounts`.
+    /// Accounts written to by transactions that landed via `sendTransaction` are added automatically.
+    known_pubkeys: KnownPubkeys,
+
+    /// Notifies pubsub connections of transactions that landed via `sendTransaction`
+    events: broadcast::Sender<TxLanded>,
+
+    faults: FaultInjector,
+
+    prio_fees: PrioritizationFeeSchedule,
+
+    /// Set by [`Self::with_recording`]
+    recorder: Option<Recorder>,
 }
 
+/// Handle to a [`BanksRpcServer`]'s set of pubkeys to scan for `getProgramAccounts`
+/// and the token methods that scan accounts, since `BanksClient` has no way of enumerating accounts.
+///
+/// `ProgramTest` does not expose the accounts added to it, so they must be registered with
+/// [`BanksRpcServer::with_known_pubkeys`], which [`RpcProgramTest`] does automatically. This handle can be used to register more while the server is running,
+/// e.g. accounts created by transactions sent directly through the `BanksClient`.
+#[derive(Clone, Debug, Default)]
+pub struct KnownPubkeys(Arc<RwLock<BTreeSet<Pubkey>>>);
+
+impl KnownPubkeys {
+    pub fn extend(&self, pubkeys: impl IntoIterator<Item = Pubkey>) {
+        self.0.write().unwrap().extend(pubkeys);
+    }
+
+    fn to_set(&self) -> BTreeSet<Pubkey> {
+        self.0.read().unwrap().clone()
+    }
+}
+
+/// Same as `solana_rpc::rpc::MAX_BASE58_BYTES`
+const MAX_BASE58_BYTES: usize = 128;
+
 fn account_data_sliced(mut account: Account, ds: Option<UiDataSliceConfig>) -> Account {
     let UiDataSliceConfig { offset, length } = match ds {
         Some(ds) => ds,
@@ -62,17 +174,142 @@ fn account_data_sliced(mut account: Account, ds: Option<UiDataSliceConfig>) -> A
     account
 }
 
-impl BanksRpcServer {
+/// Same as `solana_rpc::filter::filter_allows`, without pulling in all of solana-rpc
+fn filter_allows(filter: &RpcFilterType, data: &[u8]) -> bool {
+    const TOKEN_ACCOUNT_LEN: usize = 165;
+    const TOKEN_ACCOUNT_STATE_OFFSET: usize = 108;
+    const TOKEN_2022_ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+    match filter {
+        RpcFilterType::DataSize(size) => data.len() as u64 == *size,
+        RpcFilterType::Memcmp(compare) => compare.bytes_match(data),
+        RpcFilterType::TokenAccountState => {
+            (data.len() == TOKEN_ACCOUNT_LEN && data[TOKEN_ACCOUNT_STATE_OFFSET] != 0)
+                || data.get(TOKEN_ACCOUNT_LEN) == Some(&TOKEN_2022_ACCOUNT_TYPE_ACCOUNT)
+        }
+    }
+}
+
+fn to_rpc_tx_status(
+    solana_banks_interface::TransactionStatus {
+        slot,
+        confirmations,
+        err,
+        confirmation_status,
+    }: solana_banks_interface::TransactionStatus,
+) -> TransactionStatus {
+    TransactionStatus {
+        slot,
+        confirmations,
+        status: err.clone().map_or(Ok(()), Err),
+        err,
+        confirmation_status: confirmation_status.map(|c| match c {
+            BanksConfirmationStatus::Processed => TransactionConfirmationStatus::Processed,
+            BanksConfirmationStatus::Confirmed => TransactionConfirmationStatus::Confirmed,
+            BanksConfirmationStatus::Finalized => TransactionConfirmationStatus::Finalized,
+        }),
+    }
+}
+
+/// Converts RPC-level errors returned by the handlers to a JSON-RPC error response.
+/// All other errors are propagated
+fn to_json_rpc_err_resp(
+    id: u64,
+    err: Box<dyn Error + Send + Sync>,
+) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    let err = match err.downcast::<RpcCustomError>() {
+        Ok(custom) => return Ok(JsonRpcErrResp::new(id, (*custom).into()).into()),
+        Err(e) => e,
+    };
+    let err = err.downcast::<jsonrpc_core::Error>()?;
+    Ok(JsonRpcErrResp::new(id, *err).into())
+}
+
+/// BanksClient only takes legacy messages.
+/// Fees only depend on signatures, precompiles and compute budget ixs,
+/// so converting a v0 message with its loaded addresses appended is good enough.
+fn to_legacy_message(msg: &VersionedMessage, loaded_addresses: &LoadedAddresses) -> Message {
+    match msg {
+        VersionedMessage::Legacy(m) => m.clone(),
+        VersionedMessage::V0(m) => Message {
+            header: m.header,
+            account_keys: m
+                .account_keys
+                .iter()
+                .chain(loaded_addresses.writable.iter())
+                .chain(loaded_addresses.readonly.iter())
+                .copied()
+                .collect(),
+            recent_blockhash: m.recent_blockhash,
+            instructions: m.instructions.clone(),
+        },
+    }
+}
+
+/// Serves `service` on every connection accepted by `tcp_listener` in the background
+fn spawn_http_server<S>(
+    service: S,
+    tcp_listener: TcpListener,
+) -> JoinHandle<Result<(), Box<dyn Error + Send + Sync>>>
+where
+    S: Service<Request<Incoming>, Response = Response<Full<Bytes>>> + Clone + Send + 'static,
+    S::Error: Into<Box<dyn Error + Send + Sync>>,
+    S::Future: Send + 'static,
+{
+    tokio::task::spawn(async move {
+        loop {
+            let (tcp_stream, _socket_addr) = tcp_listener.accept().await?;
+            let io = TokioIo::new(tcp_stream);
+            let service = service.clone();
+            tokio::task::spawn(async move {
+                // RpcClient doesn't support http2
+                if let Err(err) = http1::Builder::new()
+                    .serve_connection(io, service)
+                    .with_upgrades()
+                    .await
+                {
+                    eprintln!("Error serving connection: {:?}", err);
+                }
+            });
+        }
+    })
+}
+
+impl<B: RpcBackend> BanksRpcServer<B> {
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend,
+            txs: Default::default(),
+            known_pubkeys: Default::default(),
+            events: broadcast::channel(TX_LANDED_CHANNEL_CAPACITY).0,
+            faults: Default::default(),
+            prio_fees: Default::default(),
+            recorder: None,
+        }
+    }
+
     /// Spawns the HTTP server on `http://127.0.0.1:{random_unused_port}` (IPV4).
     ///
+    /// The WebSocket pubsub endpoint is served on the same port at `ws://127.0.0.1:{random_unused_port}`.
+    /// Subscriptions are notified as soon as txs sent via `sendTransaction` land.
+    /// Changes made outside of the server, e.g. warps or txs processed directly on the backend,
+    /// are picked up by polling the backend instead, so their notifications may lag slightly.
+    ///
     /// Returns `(bound_port, BanksRpcServer join handle)`
     pub async fn spawn_random_unused(
-        bc: BanksClient,
+        backend: B,
+    ) -> (u16, JoinHandle<Result<(), Box<dyn Error + Send + Sync>>>) {
+        Self::new(backend).spawn_on_random_unused_port().await
+    }
+
+    /// Same as [`Self::spawn_random_unused`], but for an already configured server,
+    /// e.g. one created with [`Self::with_fault_rules`]
+    pub async fn spawn_on_random_unused_port(
+        self,
     ) -> (u16, JoinHandle<Result<(), Box<dyn Error + Send + Sync>>>) {
-        let s = Self { bc };
         let tcp_listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
         let port = tcp_listener.local_addr().unwrap().port();
-        (port, s.spawn(tcp_listener))
+        (port, self.spawn(tcp_listener))
     }
 
     /// Spawn the HTTP sever in the background
@@ -81,81 +318,643 @@ impl BanksRpcServer {
         tcp_listener: TcpListener,
     ) -> JoinHandle<Result<(), Box<dyn Error + Send + Sync>>> {
         // just clone BanksClient on every req lul, it consists of a single Channel
-        tokio::task::spawn(async move {
-            loop {
-                let (tcp_stream, _socket_addr) = tcp_listener.accept().await?;
-                let io = TokioIo::new(tcp_stream);
-                let this = self.clone();
-                tokio::task::spawn(async move {
-                    // RpcClient doesn't support http2
-                    if let Err(err) = http1::Builder::new().serve_connection(io, this).await {
-                        eprintln!("Error serving connection: {:?}", err);
-                    }
-                });
-            }
-        })
+        spawn_http_server(self, tcp_listener)
     }
 
-    // TODO: using this to set context slot for responses is wrong, because the bank mightve advanced?
+    /// The slot of the processed bank, for pubsub notifications and fault injection.
+    ///
+    /// Responses to requests report the slot of the bank at the request's commitment instead
     pub async fn curr_slot(&mut self) -> u64 {
-        let Clock { slot, .. } = self.bc.get_sysvar().await.unwrap();
-        slot
+        self.backend
+            .get_slot(CommitmentLevel::Processed)
+            .await
+            .unwrap()
+    }
+
+    /// The bank's root slot
+    pub async fn finalized_slot(&mut self) -> u64 {
+        self.backend
+            .get_slot(CommitmentLevel::Finalized)
+            .await
+            .unwrap()
+    }
+
+    /// Fetches the mint of `account` if it is a token account,
+    /// for the decimals and interest-bearing config needed to jsonParse it.
+    ///
+    /// Returns `None` if the mint could not be fetched,
+    /// in which case parsing fails and the account is returned base64 encoded like a real RPC would.
+    async fn token_additional_data(
+        &mut self,
+        account: &Account,
+    ) -> Result<Option<AccountAdditionalDataV2>, Box<dyn Error + Send + Sync>> {
+        if !is_known_spl_token_id(&account.owner) {
+            return Ok(None);
+        }
+        let mint = match get_token_account_mint(&account.data) {
+            Some(m) => m,
+            None => return Ok(None),
+        };
+        Ok(self
+            .mint_additional_data(mint)
+            .await?
+            .map(|d| AccountAdditionalDataV2 {
+                spl_token_additional_data: Some(d),
+            }))
+    }
+
+    /// Returns `None` if `mint` is not a valid token mint
+    async fn mint_additional_data(
+        &mut self,
+        mint: Pubkey,
+    ) -> Result<Option<SplTokenAdditionalData>, Box<dyn Error + Send + Sync>> {
+        let mint_acc = match self.backend.get_account(mint).await? {
+            Some(a) => a,
+            None => return Ok(None),
+        };
+        if !is_known_spl_token_id(&mint_acc.owner) {
+            return Ok(None);
+        }
+        let mint_state = match StateWithExtensions::<Mint>::unpack(&mint_acc.data) {
+            Ok(m) => m,
+            Err(_) => return Ok(None),
+        };
+        let interest_bearing_config = match mint_state.get_extension::<InterestBearingConfig>() {
+            Ok(c) => {
+                let Clock { unix_timestamp, .. } = self.backend.get_sysvar().await?;
+                Some((*c, unix_timestamp))
+            }
+            Err(_) => None,
+        };
+        Ok(Some(SplTokenAdditionalData {
+            decimals: mint_state.base.decimals,
+            interest_bearing_config,
+        }))
+    }
+
+    /// Encodes `account` the same way a real RPC would, defaulting to [`UiAccountEncoding::Binary`]
+    async fn encode_account(
+        &mut self,
+        pubkey: &Pubkey,
+        account: Account,
+        encoding: Option<UiAccountEncoding>,
+        data_slice: Option<UiDataSliceConfig>,
+    ) -> Result<UiAccount, Box<dyn Error + Send + Sync>> {
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+        match encoding {
+            UiAccountEncoding::JsonParsed => {
+                let additional_data = self.token_additional_data(&account).await?;
+                Ok(UiAccount::encode(
+                    pubkey,
+                    &account,
+                    encoding,
+                    additional_data,
+                    data_slice,
+                ))
+            }
+            UiAccountEncoding::Binary | UiAccountEncoding::Base58 => {
+                let account = account_data_sliced(account, data_slice);
+                if account.data.len() > MAX_BASE58_BYTES {
+                    return Err(Box::new(jsonrpc_core::Error {
+                        code: jsonrpc_core::ErrorCode::InvalidRequest,
+                        message: format!(
+                            "Encoded binary (base 58) data should be less than {MAX_BASE58_BYTES} bytes, please use Base64 encoding."
+                        ),
+                        data: None,
+                    }));
+                }
+                Ok(UiAccount::encode(pubkey, &account, encoding, None, None))
+            }
+            UiAccountEncoding::Base64 | UiAccountEncoding::Base64Zstd => Ok(UiAccount::encode(
+                pubkey,
+                &account_data_sliced(account, data_slice),
+                encoding,
+                None,
+                None,
+            )),
+        }
     }
 
-    // TODO: handle cfg encoding, commitment, min_context_slot.
-    // This just returns base64 encoded for now
     pub async fn get_account_info(
         &mut self,
         key: Pubkey,
         cfg: Option<RpcAccountInfoConfig>,
     ) -> Result<Option<UiAccount>, Box<dyn Error + Send + Sync>> {
-        Ok(self.bc.get_account(key).await?.map(|account| {
-            UiAccount::encode(
-                &key,
-                &account_data_sliced(account, cfg.map_or_else(|| None, |c| c.data_slice)),
-                UiAccountEncoding::Base64,
-                None,
-                None,
-            )
-        }))
+        let RpcAccountInfoConfig {
+            encoding,
+            data_slice,
+            commitment,
+            ..
+        } = cfg.unwrap_or_default();
+        Ok(
+            match self
+                .backend
+                .get_account_with_commitment(key, commitment.unwrap_or_default().commitment)
+                .await?
+            {
+                Some(account) => Some(
+                    self.encode_account(&key, account, encoding, data_slice)
+                        .await?,
+                ),
+                None => None,
+            },
+        )
     }
 
-    pub async fn get_latest_blockhash(&mut self, cfg: Option<CommitmentConfig>) -> RpcBlockhash {
+    pub async fn get_latest_blockhash(
+        &mut self,
+        cfg: Option<CommitmentConfig>,
+    ) -> Result<RpcBlockhash, Box<dyn Error + Send + Sync>> {
         let (blockhash, last_valid_block_height) = self
-            .bc
-            .get_latest_blockhash_with_commitment(cfg.unwrap_or_default().commitment)
-            .await
-            .unwrap()
-            .unwrap();
-        RpcBlockhash {
+            .latest_blockhash(cfg.unwrap_or_default().commitment)
+            .await?;
+        Ok(RpcBlockhash {
             blockhash: blockhash.to_string(),
             last_valid_block_height,
-        }
+        })
+    }
+
+    /// `(blockhash, last_valid_block_height)`
+    async fn latest_blockhash(
+        &mut self,
+        commitment: CommitmentLevel,
+    ) -> Result<(Hash, u64), Box<dyn Error + Send + Sync>> {
+        self.backend
+            .get_latest_blockhash_with_commitment(commitment)
+            .await?
+            .ok_or_else(|| {
+                Box::new(jsonrpc_core::Error {
+                    code: jsonrpc_core::ErrorCode::InternalError,
+                    message: format!("no blockhash available at commitment {commitment:?}"),
+                    data: None,
+                }) as Box<dyn Error + Send + Sync>
+            })
     }
 
-    // TODO: handle cfg encoding, commitment, min_context_slot.
-    // This just returns base64 encoded for now
     pub async fn get_multiple_accounts(
         &mut self,
         keys: Vec<Pubkey>,
         cfg: Option<RpcAccountInfoConfig>,
     ) -> Result<Vec<Option<UiAccount>>, Box<dyn Error + Send + Sync>> {
         let mut res = Vec::with_capacity(keys.len());
-        let ds = cfg.map_or_else(|| None, |c| c.data_slice);
+        let RpcAccountInfoConfig {
+            encoding,
+            data_slice,
+            commitment,
+            ..
+        } = cfg.unwrap_or_default();
+        let commitment = commitment.unwrap_or_default().commitment;
         for key in keys {
-            res.push(self.bc.get_account(key).await?.map(|account| {
-                UiAccount::encode(
-                    &key,
-                    &account_data_sliced(account, ds),
-                    UiAccountEncoding::Base64,
-                    None,
-                    None,
-                )
-            }));
+            res.push(
+                match self
+                    .backend
+                    .get_account_with_commitment(key, commitment)
+                    .await?
+                {
+                    Some(account) => Some(
+                        self.encode_account(&key, account, encoding, data_slice)
+                            .await?,
+                    ),
+                    None => None,
+                },
+            );
+        }
+        Ok(res)
+    }
+
+    /// Registers `pubkeys` to be scanned by `getProgramAccounts`, see [`KnownPubkeys`]
+    pub fn with_known_pubkeys(self, pubkeys: impl IntoIterator<Item = Pubkey>) -> Self {
+        self.known_pubkeys.extend(pubkeys);
+        self
+    }
+
+    /// Returns a handle to register more pubkeys after this server has been spawned
+    pub fn known_pubkeys(&self) -> KnownPubkeys {
+        self.known_pubkeys.clone()
+    }
+
+    fn scanned_pubkeys(&self) -> BTreeSet<Pubkey> {
+        let mut res = self.known_pubkeys.to_set();
+        res.extend(self.backend.account_keys().into_iter().flatten());
+        res
+    }
+
+    /// Scans all accounts registered with [`Self::with_known_pubkeys`] or [`KnownPubkeys`]
+    /// and all accounts written to by transactions sent to this server.
+    ///
+    /// Results are sorted by pubkey.
+    pub async fn get_program_accounts(
+        &mut self,
+        program_id: Pubkey,
+        cfg: Option<RpcProgramAccountsConfig>,
+    ) -> Result<Vec<RpcKeyedAccount>, Box<dyn Error + Send + Sync>> {
+        let RpcProgramAccountsConfig {
+            filters,
+            account_config:
+                RpcAccountInfoConfig {
+                    encoding,
+                    data_slice,
+                    commitment,
+                    ..
+                },
+            ..
+        } = cfg.unwrap_or_default();
+        let commitment = commitment.unwrap_or_default().commitment;
+        let filters = filters.unwrap_or_default();
+        for filter in filters.iter() {
+            filter
+                .verify()
+                .map_err(|e| jsonrpc_core::Error::invalid_params(e.to_string()))?;
+        }
+        let mut res = Vec::new();
+        for pubkey in self.scanned_pubkeys() {
+            let account = match self
+                .backend
+                .get_account_with_commitment(pubkey, commitment)
+                .await?
+            {
+                Some(a) if a.owner == program_id => a,
+                _ => continue,
+            };
+            if !filters.iter().all(|f| filter_allows(f, &account.data)) {
+                continue;
+            }
+            res.push(RpcKeyedAccount {
+                pubkey: pubkey.to_string(),
+                account: self
+                    .encode_account(&pubkey, account, encoding, data_slice)
+                    .await?,
+            });
         }
         Ok(res)
     }
 
+    /// Resolves the addresses loaded from address lookup tables by a v0 message.
+    ///
+    /// Returns `None` if any of the lookups could not be resolved,
+    /// in which case the transaction would fail to execute anyway.
+    pub async fn load_addresses(
+        &mut self,
+        msg: &VersionedMessage,
+    ) -> Result<Option<LoadedAddresses>, Box<dyn Error + Send + Sync>> {
+        let lookups = match msg {
+            VersionedMessage::Legacy(_) => return Ok(Some(LoadedAddresses::default())),
+            VersionedMessage::V0(m) => &m.address_table_lookups,
+        };
+        let mut res = LoadedAddresses::default();
+        for MessageAddressTableLookup {
+            account_key,
+            writable_indexes,
+            readonly_indexes,
+        } in lookups
+        {
+            let lut_acc = match self.backend.get_account(*account_key).await? {
+                Some(a) => a,
+                None => return Ok(None),
+            };
+            let lut = match AddressLookupTable::deserialize(&lut_acc.data) {
+                Ok(lut) => lut,
+                Err(_) => return Ok(None),
+            };
+            for (indexes, loaded) in [
+                (writable_indexes, &mut res.writable),
+                (readonly_indexes, &mut res.readonly),
+            ] {
+                for i in indexes {
+                    match lut.addresses.get(usize::from(*i)) {
+                        Some(pk) => loaded.push(*pk),
+                        None => return Ok(None),
+                    }
+                }
+            }
+        }
+        Ok(Some(res))
+    }
+
+    /// Returns the fee the bank would charge for `msg`, or 0 if its blockhash has expired
+    pub async fn fee_for_message(
+        &mut self,
+        msg: &VersionedMessage,
+        loaded_addresses: &LoadedAddresses,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .backend
+            .get_fee_for_message(to_legacy_message(msg, loaded_addresses))
+            .await?
+            .unwrap_or_default())
+    }
+
+    async fn get_balances(
+        &mut self,
+        keys: &[Pubkey],
+    ) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>> {
+        let mut res = Vec::with_capacity(keys.len());
+        for key in keys {
+            res.push(self.backend.get_balance(*key).await?);
+        }
+        Ok(res)
+    }
+
+    /// Executes `tx` against the bank and records it for `getTransaction` if it landed
+    async fn process_and_record_tx(
+        &mut self,
+        tx: VersionedTransaction,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        // unresolvable lookups means the tx will not be executed and not be recorded
+        let loaded_addresses = self.load_addresses(&tx.message).await?.unwrap_or_default();
+        let keys: Vec<Pubkey> =
+            AccountKeys::new(tx.message.static_account_keys(), Some(&loaded_addresses))
+                .iter()
+                .copied()
+                .collect();
+        let pre_balances = self.get_balances(&keys).await?;
+        let fee = self.fee_for_message(&tx.message, &loaded_addresses).await?;
+        let Clock {
+            slot,
+            unix_timestamp,
+            ..
+        } = self.backend.get_sysvar().await?;
+
+        let BanksTransactionResultWithMetadata { result, metadata } = self
+            .backend
+            .process_transaction_with_metadata(tx.clone())
+            .await?;
+        let metadata = match metadata {
+            Some(m) => m,
+            // tx was not executed, so it never lands on a real cluster either
+            None => return Ok(()),
+        };
+        let post_balances = self.get_balances(&keys).await?;
+        let written: Vec<Pubkey> = keys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| tx.message.is_maybe_writable(*i, None))
+            .map(|(_, k)| *k)
+            .collect();
+        self.known_pubkeys.extend(written.iter().copied());
+
+        let signature = tx.signatures.first().copied().unwrap_or_default();
+        let tx_landed = TxLanded {
+            signature,
+            slot,
+            err: result.clone().err(),
+            written,
+        };
+        let confirmed = ConfirmedTransactionWithStatusMeta {
+            slot,
+            tx_with_meta: TransactionWithStatusMeta::Complete(VersionedTransactionWithStatusMeta {
+                transaction: tx,
+                meta: TransactionStatusMeta {
+                    status: result,
+                    fee,
+                    pre_balances,
+                    post_balances,
+                    inner_instructions: None,
+                    log_messages: Some(metadata.log_messages),
+                    pre_token_balances: None,
+                    post_token_balances: None,
+                    rewards: None,
+                    loaded_addresses,
+                    return_data: metadata.return_data,
+                    compute_units_consumed: Some(metadata.compute_units_consumed),
+                },
+            }),
+            block_time: Some(unix_timestamp),
+        };
+        self.txs.write().unwrap().insert(signature, confirmed);
+        // errs if there are no pubsub connections
+        let _ = self.events.send(tx_landed);
+        Ok(())
+    }
+
+    /// Processes the transaction against the `BanksClient` synchronously,
+    /// so the transaction has already landed by the time its signature is returned.
+    ///
+    /// Like a real cluster, transactions with invalid signatures are dropped if `skip_preflight`
+    pub async fn send_transaction(
+        &mut self,
+        encoded_tx: &str,
+        cfg: Option<RpcSendTransactionConfig>,
+    ) -> Result<Signature, Box<dyn Error + Send + Sync>> {
+        let RpcSendTransactionConfig {
+            skip_preflight,
+            preflight_commitment,
+            encoding,
+            ..
+        } = cfg.unwrap_or_default();
+        let tx = decode_wire_tx(
+            encoded_tx,
+            encoding.unwrap_or(UiTransactionEncoding::Base58),
+        )?;
+        let signature = tx.signatures.first().copied().unwrap_or_default();
+        let is_sig_valid = tx.verify_with_results().into_iter().all(|b| b);
+
+        if !skip_preflight {
+            if !is_sig_valid {
+                return Err(Box::new(
+                    RpcCustomError::TransactionSignatureVerificationFailure,
+                ));
+            }
+            let result = self
+                .simulate_decoded_tx(tx.clone(), preflight_commitment.unwrap_or_default(), false)
+                .await?;
+            if let Some(err) = result.err.clone() {
+                return Err(Box::new(RpcCustomError::SendTransactionPreflightFailure {
+                    message: format!("Transaction simulation failed: {err}"),
+                    result,
+                }));
+            }
+        }
+
+        if is_sig_valid {
+            self.process_and_record_tx(tx).await?;
+        }
+        Ok(signature)
+    }
+
+    async fn simulate_decoded_tx(
+        &mut self,
+        tx: VersionedTransaction,
+        commitment: CommitmentLevel,
+        inner_instructions: bool,
+    ) -> Result<RpcSimulateTransactionResult, Box<dyn Error + Send + Sync>> {
+        let loaded_addresses = self.load_addresses(&tx.message).await?.unwrap_or_default();
+        let account_keys =
+            AccountKeys::new(tx.message.static_account_keys(), Some(&loaded_addresses));
+
+        let BanksTransactionResultWithSimulation {
+            result,
+            simulation_details,
+        } = self
+            .backend
+            .simulate_transaction_with_commitment(tx.clone(), commitment)
+            .await?;
+        let err = match result {
+            Some(Err(e)) => Some(e),
+            _ => None,
+        };
+
+        let (logs, units_consumed, return_data, inner_ixs) = match simulation_details {
+            Some(TransactionSimulationDetails {
+                logs,
+                units_consumed,
+                return_data,
+                inner_instructions: inner_ixs,
+            }) => (
+                Some(logs),
+                Some(units_consumed),
+                return_data.map(Into::into),
+                inner_ixs.filter(|_| inner_instructions).map(|list| {
+                    map_inner_instructions(list)
+                        .map(|ixs| UiInnerInstructions::parse(ixs, &account_keys))
+                        .collect()
+                }),
+            ),
+            None => (None, None, None, None),
+        };
+
+        Ok(RpcSimulateTransactionResult {
+            err,
+            logs,
+            units_consumed,
+            return_data,
+            // see Self::simulate_transaction
+            accounts: None,
+            inner_instructions: inner_ixs,
+            replacement_blockhash: None,
+        })
+    }
+
+    /// Simulates the transaction with [`BanksClient::simulate_transaction_with_commitment`].
+    ///
+    /// The `accounts` config is not supported and errs,
+    /// since `BanksClient` does not return post-simulation account states.
+    pub async fn simulate_transaction(
+        &mut self,
+        encoded_tx: &str,
+        cfg: Option<RpcSimulateTransactionConfig>,
+    ) -> Result<RpcSimulateTransactionResult, Box<dyn Error + Send + Sync>> {
+        let RpcSimulateTransactionConfig {
+            sig_verify,
+            replace_recent_blockhash,
+            commitment,
+            encoding,
+            accounts,
+            inner_instructions,
+            ..
+        } = cfg.unwrap_or_default();
+        if accounts.is_some() {
+            return Err(Box::new(jsonrpc_core::Error::invalid_params(
+                "accounts config is not supported: BanksClient does not return post-simulation account states",
+            )));
+        }
+        let mut tx = decode_wire_tx(
+            encoded_tx,
+            encoding.unwrap_or(UiTransactionEncoding::Base58),
+        )?;
+        if sig_verify {
+            if replace_recent_blockhash {
+                return Err(Box::new(jsonrpc_core::Error::invalid_params(
+                    "sigVerify may not be used with replaceRecentBlockhash",
+                )));
+            }
+            if !tx.verify_with_results().into_iter().all(|b| b) {
+                return Err(Box::new(
+                    RpcCustomError::TransactionSignatureVerificationFailure,
+                ));
+            }
+        }
+        let commitment = commitment.unwrap_or_default().commitment;
+        let replacement_blockhash = if replace_recent_blockhash {
+            let (blockhash, last_valid_block_height) = self.latest_blockhash(commitment).await?;
+            tx.message.set_recent_blockhash(blockhash);
+            Some(RpcBlockhash {
+                blockhash: blockhash.to_string(),
+                last_valid_block_height,
+            })
+        } else {
+            None
+        };
+        let result = self
+            .simulate_decoded_tx(tx, commitment, inner_instructions)
+            .await?;
+        Ok(RpcSimulateTransactionResult {
+            replacement_blockhash,
+            ..result
+        })
+    }
+
+    /// `search_transaction_history` falls back to transactions recorded by this server
+    /// that are no longer in the bank's status cache
+    pub async fn get_signature_statuses(
+        &mut self,
+        sigs: Vec<Signature>,
+        cfg: Option<RpcSignatureStatusConfig>,
+    ) -> Result<Vec<Option<TransactionStatus>>, Box<dyn Error + Send + Sync>> {
+        let search_transaction_history = cfg.is_some_and(|c| c.search_transaction_history);
+        let mut res = Vec::with_capacity(sigs.len());
+        for sig in sigs {
+            let status = match self.backend.get_transaction_status(sig).await? {
+                Some(s) => Some(to_rpc_tx_status(s)),
+                None if search_transaction_history => {
+                    self.txs.read().unwrap().get(&sig).and_then(|confirmed| {
+                        let status = confirmed.tx_with_meta.get_status_meta()?.status;
+                        Some(TransactionStatus {
+                            slot: confirmed.slot,
+                            confirmations: None,
+                            err: status.clone().err(),
+                            status,
+                            confirmation_status: Some(TransactionConfirmationStatus::Finalized),
+                        })
+                    })
+                }
+                None => None,
+            };
+            res.push(status);
+        }
+        Ok(res)
+    }
+
+    /// Only transactions sent via this server's `sendTransaction` are available
+    pub async fn get_transaction(
+        &mut self,
+        sig: Signature,
+        RpcTransactionConfig {
+            encoding,
+            max_supported_transaction_version,
+            ..
+        }: RpcTransactionConfig,
+    ) -> Result<Option<EncodedConfirmedTransactionWithStatusMeta>, Box<dyn Error + Send + Sync>>
+    {
+        let confirmed = match self.txs.read().unwrap().get(&sig) {
+            Some(c) => c.clone(),
+            None => return Ok(None),
+        };
+        let encoded = confirmed
+            .encode(
+                encoding.unwrap_or(UiTransactionEncoding::Json),
+                max_supported_transaction_version,
+            )
+            .map_err(|e| match e {
+                EncodeError::UnsupportedTransactionVersion(v) => {
+                    RpcCustomError::UnsupportedTransactionVersion(v)
+                }
+            })?;
+        Ok(Some(encoded))
+    }
+
+    /// `BanksClient` has no direct way of checking if a blockhash is valid,
+    /// so this checks if the bank is still able to calculate fees for a message with the given blockhash,
+    /// which is the case for all blockhashes still in its blockhash queue
+    pub async fn is_blockhash_valid(
+        &mut self,
+        blockhash: Hash,
+        _cfg: Option<RpcContextConfig>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let msg = Message::new_with_blockhash(&[], Some(&Pubkey::new_unique()), &blockhash);
+        Ok(self.backend.get_fee_for_message(msg).await?.is_some())
+    }
+
     pub async fn handle_batched_reqs(
         &mut self,
         reqs: Vec<JsonRpcReq>,
@@ -167,7 +966,20 @@ impl BanksRpcServer {
         Ok(serde_json::to_value(res).unwrap())
     }
 
+    /// Errors that a real RPC would respond with, e.g. preflight failures,
+    /// are returned as JSON-RPC error responses
     pub async fn handle_req(
+        &mut self,
+        req: JsonRpcReq,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let id = req.id;
+        match self.route_req(req).await {
+            Ok(v) => Ok(v),
+            Err(e) => to_json_rpc_err_resp(id, e),
+        }
+    }
+
+    async fn route_req(
         &mut self,
         JsonRpcReq {
             jsonrpc: _,
@@ -179,31 +991,114 @@ impl BanksRpcServer {
         Ok(match method {
             RpcMethod::GetAccountInfo => {
                 let (key, cfg) = deser_get_account_info_params(params)?;
-                JsonRpcResp::with_ctx(
-                    id,
-                    self.get_account_info(key, cfg).await?,
-                    self.curr_slot().await,
-                )
-                .into()
+                let slot = self.context_slot(&cfg).await?;
+                JsonRpcResp::with_ctx(id, self.get_account_info(key, cfg).await?, slot).into()
+            }
+            RpcMethod::GetBalance => {
+                let (key, cfg) = deser_get_balance_params(params)?;
+                let slot = self.context_slot(&cfg).await?;
+                JsonRpcResp::with_ctx(id, self.get_balance(key, cfg).await?, slot).into()
+            }
+            RpcMethod::GetEpochInfo => {
+                let cfg = deser_get_epoch_info_params(params)?;
+                JsonRpcResp::new(id, self.get_epoch_info(cfg).await?).into()
+            }
+            RpcMethod::GetEpochSchedule => {
+                JsonRpcResp::new(id, self.get_epoch_schedule().await?).into()
+            }
+            RpcMethod::GetFeeForMessage => {
+                let (encoded_msg, cfg) = deser_get_fee_for_message_params(params)?;
+                let msg = decode_wire_msg(&encoded_msg)?;
+                let slot = self.context_slot(&cfg).await?;
+                JsonRpcResp::with_ctx(id, self.get_fee_for_message(&msg, cfg).await?, slot).into()
             }
             RpcMethod::GetLatestBlockhash => {
                 let cfg = deser_get_latest_blockhash_params(params)?;
-                JsonRpcResp::with_ctx(
+                let slot = self.context_slot(&cfg).await?;
+                JsonRpcResp::with_ctx(id, self.get_latest_blockhash(cfg).await?, slot).into()
+            }
+            RpcMethod::GetMinimumBalanceForRentExemption => {
+                let (data_len, cfg) = deser_get_minimum_balance_for_rent_exemption_params(params)?;
+                JsonRpcResp::new(
                     id,
-                    self.get_latest_blockhash(cfg).await,
-                    self.curr_slot().await,
+                    self.get_minimum_balance_for_rent_exemption(data_len, cfg)
+                        .await?,
                 )
                 .into()
             }
             RpcMethod::GetMultipleAccounts => {
                 let (keys, cfg) = deser_get_multiple_accounts_params(params)?;
+                let slot = self.context_slot(&cfg).await?;
+                JsonRpcResp::with_ctx(id, self.get_multiple_accounts(keys, cfg).await?, slot).into()
+            }
+            RpcMethod::GetProgramAccounts => {
+                let (program_id, cfg) = deser_get_program_accounts_params(params)?;
+                let with_context = cfg.as_ref().and_then(|c| c.with_context).unwrap_or(false);
+                let slot = self.context_slot(&cfg).await?;
+                let accounts = self.get_program_accounts(program_id, cfg).await?;
+                if with_context {
+                    JsonRpcResp::with_ctx(id, accounts, slot).into()
+                } else {
+                    JsonRpcResp::new(id, accounts).into()
+                }
+            }
+            RpcMethod::GetRecentPrioritizationFees => {
+                let accounts = deser_get_recent_prioritization_fees_params(params)?;
+                JsonRpcResp::new(id, self.get_recent_prioritization_fees(&accounts)).into()
+            }
+            RpcMethod::GetSignatureStatuses => {
+                let (sigs, cfg) = deser_get_signature_statuses_params(params)?;
+                // statuses are always served from the processed bank, like a real RPC
+                let slot = self.context_slot(&CommitmentConfig::processed()).await?;
+                JsonRpcResp::with_ctx(id, self.get_signature_statuses(sigs, cfg).await?, slot)
+                    .into()
+            }
+            RpcMethod::GetSlot => {
+                let cfg = deser_get_slot_params(params)?;
+                JsonRpcResp::new(id, self.get_slot(cfg).await?).into()
+            }
+            RpcMethod::GetTokenAccountBalance => {
+                let (key, cfg) = deser_get_token_account_balance_params(params)?;
+                let slot = self.context_slot(&cfg).await?;
+                JsonRpcResp::with_ctx(id, self.get_token_account_balance(key, cfg).await?, slot)
+                    .into()
+            }
+            RpcMethod::GetTokenAccountsByDelegate => {
+                let (delegate, filter, cfg) = deser_get_token_accounts_by_delegate_params(params)?;
+                let slot = self.context_slot(&cfg).await?;
                 JsonRpcResp::with_ctx(
                     id,
-                    self.get_multiple_accounts(keys, cfg).await?,
-                    self.curr_slot().await,
+                    self.get_token_accounts_by_delegate(delegate, filter, cfg)
+                        .await?,
+                    slot,
                 )
                 .into()
             }
+            RpcMethod::GetTokenAccountsByOwner => {
+                let (owner, filter, cfg) = deser_get_token_accounts_by_owner_params(params)?;
+                let slot = self.context_slot(&cfg).await?;
+                JsonRpcResp::with_ctx(
+                    id,
+                    self.get_token_accounts_by_owner(owner, filter, cfg).await?,
+                    slot,
+                )
+                .into()
+            }
+            RpcMethod::GetTokenLargestAccounts => {
+                let (mint, cfg) = deser_get_token_largest_accounts_params(params)?;
+                let slot = self.context_slot(&cfg).await?;
+                JsonRpcResp::with_ctx(id, self.get_token_largest_accounts(mint, cfg).await?, slot)
+                    .into()
+            }
+            RpcMethod::GetTokenSupply => {
+                let (mint, cfg) = deser_get_token_supply_params(params)?;
+                let slot = self.context_slot(&cfg).await?;
+                JsonRpcResp::with_ctx(id, self.get_token_supply(mint, cfg).await?, slot).into()
+            }
+            RpcMethod::GetTransaction => {
+                let (sig, cfg) = deser_get_transaction_params(params)?;
+                JsonRpcResp::new(id, self.get_transaction(sig, cfg).await?).into()
+            }
             RpcMethod::GetVersion => {
                 let version = solana_version::Version::default();
                 JsonRpcResp::new(
@@ -215,11 +1110,31 @@ impl BanksRpcServer {
                 )
                 .into()
             }
+            RpcMethod::IsBlockhashValid => {
+                let (blockhash, cfg) = deser_is_blockhash_valid_params(params)?;
+                let slot = self.context_slot(&cfg).await?;
+                JsonRpcResp::with_ctx(id, self.is_blockhash_valid(blockhash, cfg).await?, slot)
+                    .into()
+            }
+            RpcMethod::SendTransaction => {
+                let (encoded_tx, cfg) = deser_send_transaction_params(params)?;
+                JsonRpcResp::new(
+                    id,
+                    self.send_transaction(&encoded_tx, cfg).await?.to_string(),
+                )
+                .into()
+            }
+            RpcMethod::SimulateTransaction => {
+                let (encoded_tx, cfg) = deser_simulate_transaction_params(params)?;
+                let slot = self.context_slot(&cfg).await?;
+                JsonRpcResp::with_ctx(id, self.simulate_transaction(&encoded_tx, cfg).await?, slot)
+                    .into()
+            }
         })
     }
 }
 
-impl Service<Request<Incoming>> for BanksRpcServer {
+impl<B: RpcBackend> Service<Request<Incoming>> for BanksRpcServer<B> {
     type Response = Response<Full<Bytes>>;
 
     type Error = Box<dyn Error + Send + Sync>;
@@ -230,15 +1145,38 @@ impl Service<Request<Incoming>> for BanksRpcServer {
     // Actly idk, all the BanksClient methods requires &mut self
     fn call(&self, req: Request<Incoming>) -> Self::Future {
         let mut this = self.clone();
+        if is_ws_upgrade(&req) {
+            return Box::pin(async move { this.upgrade_ws(req) });
+        }
         Box::pin(async move {
             let body = req.into_body().collect().await.ok().unwrap().to_bytes();
-            let resp = if let Ok(v) = serde_json::from_reader(body.clone().reader()) {
-                this.handle_batched_reqs(v).await
-            } else if let Ok(r) = serde_json::from_reader(body.reader()) {
-                this.handle_req(r).await
+            let (reqs, is_batch) = parse_reqs(body)?;
+            let mut res = Vec::with_capacity(reqs.len());
+            for req in reqs {
+                let (method, params) = (req.method, req.params.clone());
+                match this.handle_req_with_faults(req).await? {
+                    Ok(v) => {
+                        if let Some(recorder) = &this.recorder {
+                            recorder.record(method, params, &v)?;
+                        }
+                        res.push(v)
+                    }
+                    Err(HttpFault::Status(status)) => {
+                        return Ok(Response::builder()
+                            .status(status)
+                            .body(Full::default())
+                            .unwrap())
+                    }
+                    Err(HttpFault::DropConnection) => {
+                        return Err("Connection dropped by fault injection".into())
+                    }
+                }
+            }
+            let resp = if is_batch {
+                Value::Array(res)
             } else {
-                Err("Invalid request".into())
-            }?;
+                res.pop().unwrap()
+            };
             Ok(to_http_resp(serde_json::to_vec(&resp).unwrap().into()))
         })
     }
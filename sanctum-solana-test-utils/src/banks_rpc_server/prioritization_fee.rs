@@ -0,0 +1,108 @@
+//! Scripted `getRecentPrioritizationFees` responses, since `BanksClient` doesn't track prioritization fees
+
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, RwLock},
+};
+
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client_api::response::RpcPrioritizationFee;
+
+use super::{BanksRpcServer, RpcBackend};
+
+#[derive(Clone, Debug, Default)]
+struct FeeTable {
+    default: Vec<RpcPrioritizationFee>,
+    by_accounts: Vec<(BTreeSet<Pubkey>, Vec<RpcPrioritizationFee>)>,
+}
+
+/// The `(slot, prioritization_fee)` entries a [`BanksRpcServer`] responds to `getRecentPrioritizationFees` with.
+///
+/// A request is responded to with the entries set for its exact set of writable accounts
+/// (order and duplicates don't matter), falling back to the default entries if none were set.
+///
+/// Entries are returned as-is, the slots don't have to be related to the bank's current slot.
+/// The default entries are empty, which most fee estimators treat as an error.
+///
+/// Clones share the same entries, so this can be used to change them after the server has been spawned.
+#[derive(Clone, Debug, Default)]
+pub struct PrioritizationFeeSchedule(Arc<RwLock<FeeTable>>);
+
+fn to_rpc_prio_fees(fees: impl IntoIterator<Item = (u64, u64)>) -> Vec<RpcPrioritizationFee> {
+    fees.into_iter()
+        .map(|(slot, prioritization_fee)| RpcPrioritizationFee {
+            slot,
+            prioritization_fee,
+        })
+        .collect()
+}
+
+impl PrioritizationFeeSchedule {
+    pub fn with_default(self, fees: impl IntoIterator<Item = (u64, u64)>) -> Self {
+        self.set_default(fees);
+        self
+    }
+
+    pub fn with_accounts(
+        self,
+        writable_accounts: impl IntoIterator<Item = Pubkey>,
+        fees: impl IntoIterator<Item = (u64, u64)>,
+    ) -> Self {
+        self.set_accounts(writable_accounts, fees);
+        self
+    }
+
+    pub fn set_default(&self, fees: impl IntoIterator<Item = (u64, u64)>) {
+        self.0.write().unwrap().default = to_rpc_prio_fees(fees);
+    }
+
+    /// Replaces the entries previously set for the same set of `writable_accounts`, if any
+    pub fn set_accounts(
+        &self,
+        writable_accounts: impl IntoIterator<Item = Pubkey>,
+        fees: impl IntoIterator<Item = (u64, u64)>,
+    ) {
+        let accounts: BTreeSet<Pubkey> = writable_accounts.into_iter().collect();
+        let fees = to_rpc_prio_fees(fees);
+        let mut table = self.0.write().unwrap();
+        match table.by_accounts.iter_mut().find(|(a, _)| *a == accounts) {
+            Some((_, f)) => *f = fees,
+            None => table.by_accounts.push((accounts, fees)),
+        }
+    }
+
+    /// Removes all entries, including the default ones
+    pub fn clear(&self) {
+        *self.0.write().unwrap() = FeeTable::default();
+    }
+
+    pub fn get(&self, writable_accounts: &[Pubkey]) -> Vec<RpcPrioritizationFee> {
+        let accounts: BTreeSet<Pubkey> = writable_accounts.iter().copied().collect();
+        let table = self.0.read().unwrap();
+        table
+            .by_accounts
+            .iter()
+            .find(|(a, _)| *a == accounts)
+            .map_or(&table.default, |(_, f)| f)
+            .clone()
+    }
+}
+
+impl<B: RpcBackend> BanksRpcServer<B> {
+    pub fn with_prioritization_fees(mut self, schedule: PrioritizationFeeSchedule) -> Self {
+        self.prio_fees = schedule;
+        self
+    }
+
+    /// Returns a handle to change this server's prioritization fees after it has been spawned
+    pub fn prioritization_fees(&self) -> PrioritizationFeeSchedule {
+        self.prio_fees.clone()
+    }
+
+    pub fn get_recent_prioritization_fees(
+        &self,
+        writable_accounts: &[Pubkey],
+    ) -> Vec<RpcPrioritizationFee> {
+        self.prio_fees.get(writable_accounts)
+    }
+}
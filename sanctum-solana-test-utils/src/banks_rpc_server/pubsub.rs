@@ -0,0 +1,467 @@
+//! WebSocket pubsub endpoint, served on the same port as the HTTP server
+
+use std::{collections::HashMap, error::Error, time::Duration};
+
+use futures_util::{SinkExt, StreamExt};
+use http_body_util::Full;
+use hyper::{
+    body::{Bytes, Incoming},
+    header::{CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, UPGRADE},
+    upgrade::Upgraded,
+    Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use serde_json::Value;
+use solana_account_decoder::UiAccount;
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcSignatureSubscribeConfig},
+    response::{
+        ProcessedSignatureResult, ReceivedSignatureResult, Response as RpcResponse,
+        RpcResponseContext, RpcSignatureResult, SlotInfo,
+    },
+};
+use solana_sdk::{account::Account, signature::Signature, transaction::TransactionError};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_tungstenite::{
+    tungstenite::{handshake::derive_accept_key, protocol::Role, Message},
+    WebSocketStream,
+};
+
+use super::{
+    json_rpc::{
+        deser_get_account_info_params, deser_signature_subscribe_params, deser_unsubscribe_params,
+        JsonRpcErrResp, JsonRpcNotification, JsonRpcResp, PubsubMethod, PubsubReq,
+    },
+    to_json_rpc_err_resp, BanksRpcServer, RpcBackend,
+};
+
+/// How often the bank is polled for changes made outside of the server's `sendTransaction`,
+/// e.g. warps and txs processed directly on the `BanksClient`
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Capacity of the channel that [`TxLanded`] events are broadcast to pubsub connections on
+pub(super) const TX_LANDED_CHANNEL_CAPACITY: usize = 1024;
+
+/// Broadcast to all pubsub connections whenever a transaction sent to the server lands
+#[derive(Clone, Debug)]
+pub(super) struct TxLanded {
+    pub signature: Signature,
+    pub slot: u64,
+    pub err: Option<TransactionError>,
+    pub written: Vec<Pubkey>,
+}
+
+enum Subscription {
+    Account {
+        pubkey: Pubkey,
+        cfg: Option<RpcAccountInfoConfig>,
+        last: Option<UiAccount>,
+    },
+    Signature {
+        signature: Signature,
+        enable_received_notification: bool,
+    },
+    Slot,
+}
+
+pub(super) fn is_ws_upgrade(req: &Request<Incoming>) -> bool {
+    req.headers()
+        .get(UPGRADE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("websocket"))
+}
+
+impl<B: RpcBackend> BanksRpcServer<B> {
+    /// Completes the WebSocket handshake and serves pubsub on the upgraded connection in the background
+    pub(super) fn upgrade_ws(
+        self,
+        mut req: Request<Incoming>,
+    ) -> Result<Response<Full<Bytes>>, Box<dyn Error + Send + Sync>> {
+        let accept = derive_accept_key(
+            req.headers()
+                .get(SEC_WEBSOCKET_KEY)
+                .ok_or("Missing Sec-WebSocket-Key")?
+                .as_bytes(),
+        );
+        let on_upgrade = hyper::upgrade::on(&mut req);
+        tokio::task::spawn(async move {
+            let upgraded = match on_upgrade.await {
+                Ok(u) => u,
+                Err(err) => {
+                    eprintln!("Error upgrading connection: {:?}", err);
+                    return;
+                }
+            };
+            let ws =
+                WebSocketStream::from_raw_socket(TokioIo::new(upgraded), Role::Server, None).await;
+            if let Err(err) = PubsubSession::new(self).await.serve(ws).await {
+                eprintln!("Error serving pubsub: {:?}", err);
+            }
+        });
+        Ok(Response::builder()
+            .status(StatusCode::SWITCHING_PROTOCOLS)
+            .header(CONNECTION, "upgrade")
+            .header(UPGRADE, "websocket")
+            .header(SEC_WEBSOCKET_ACCEPT, accept)
+            .body(Full::default())
+            .unwrap())
+    }
+}
+
+/// Subscriptions of a single WebSocket connection
+struct PubsubSession<B> {
+    server: BanksRpcServer<B>,
+    subs: HashMap<u64, Subscription>,
+    next_sub_id: u64,
+    last_slot: u64,
+}
+
+impl<B: RpcBackend> PubsubSession<B> {
+    async fn new(mut server: BanksRpcServer<B>) -> Self {
+        let last_slot = server.curr_slot().await;
+        Self {
+            server,
+            subs: HashMap::new(),
+            next_sub_id: 0,
+            last_slot,
+        }
+    }
+
+    async fn serve(
+        mut self,
+        ws: WebSocketStream<TokioIo<Upgraded>>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let (mut sink, mut stream) = ws.split();
+        let mut tx_landed = self.server.events.subscribe();
+        let mut poll = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            let out = tokio::select! {
+                msg = stream.next() => match msg {
+                    None | Some(Ok(Message::Close(_))) => return Ok(()),
+                    Some(Ok(Message::Text(text))) => self.handle_msg(&text).await?,
+                    // tungstenite responds to pings by itself
+                    Some(Ok(_)) => Vec::new(),
+                    Some(Err(e)) => return Err(e.into()),
+                },
+                ev = tx_landed.recv() => match ev {
+                    Ok(ev) => self.on_tx_landed(ev).await?,
+                    Err(RecvError::Lagged(_)) => Vec::new(),
+                    Err(RecvError::Closed) => return Ok(()),
+                },
+                _ = poll.tick() => self.poll().await,
+            };
+            for v in out {
+                sink.send(Message::Text(v.to_string())).await?;
+            }
+        }
+    }
+
+    fn add_sub(&mut self, sub: Subscription) -> u64 {
+        let id = self.next_sub_id;
+        self.next_sub_id += 1;
+        self.subs.insert(id, sub);
+        id
+    }
+
+    async fn handle_msg(&mut self, text: &str) -> Result<Vec<Value>, Box<dyn Error + Send + Sync>> {
+        let req: PubsubReq = match serde_json::from_str(text) {
+            Ok(r) => r,
+            Err(_) => {
+                return Ok(vec![JsonRpcErrResp::new(
+                    0,
+                    jsonrpc_core::Error::invalid_request(),
+                )
+                .into()])
+            }
+        };
+        let id = req.id;
+        match self.route_req(req).await {
+            Ok(v) => Ok(v),
+            Err(e) => Ok(vec![to_json_rpc_err_resp(id, e)?]),
+        }
+    }
+
+    async fn route_req(
+        &mut self,
+        PubsubReq {
+            jsonrpc: _,
+            id,
+            method,
+            params,
+        }: PubsubReq,
+    ) -> Result<Vec<Value>, Box<dyn Error + Send + Sync>> {
+        Ok(match method {
+            PubsubMethod::AccountSubscribe => {
+                let (pubkey, cfg) = deser_get_account_info_params(params)?;
+                let last = self.server.get_account_info(pubkey, cfg.clone()).await?;
+                let sub_id = self.add_sub(Subscription::Account { pubkey, cfg, last });
+                vec![JsonRpcResp::new(id, sub_id).into()]
+            }
+            PubsubMethod::SignatureSubscribe => {
+                let (signature, cfg) = deser_signature_subscribe_params(params)?;
+                let RpcSignatureSubscribeConfig {
+                    enable_received_notification,
+                    ..
+                } = cfg.unwrap_or_default();
+                let sub_id = self.add_sub(Subscription::Signature {
+                    signature,
+                    enable_received_notification: enable_received_notification.unwrap_or(false),
+                });
+                let mut res = vec![JsonRpcResp::new(id, sub_id).into()];
+                // notify immediately if the tx already landed
+                if let Some(status) = self
+                    .server
+                    .backend
+                    .get_transaction_status(signature)
+                    .await?
+                {
+                    self.subs.remove(&sub_id);
+                    res.push(signature_notification(
+                        sub_id,
+                        status.slot,
+                        RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult {
+                            err: status.err,
+                        }),
+                    ));
+                }
+                res
+            }
+            PubsubMethod::SlotSubscribe => {
+                self.last_slot = self.server.curr_slot().await;
+                vec![JsonRpcResp::new(id, self.add_sub(Subscription::Slot)).into()]
+            }
+            PubsubMethod::AccountUnsubscribe => {
+                self.unsubscribe(id, params, |s| matches!(s, Subscription::Account { .. }))?
+            }
+            PubsubMethod::SignatureUnsubscribe => {
+                self.unsubscribe(id, params, |s| matches!(s, Subscription::Signature { .. }))?
+            }
+            PubsubMethod::SlotUnsubscribe => {
+                self.unsubscribe(id, params, |s| matches!(s, Subscription::Slot))?
+            }
+        })
+    }
+
+    fn unsubscribe(
+        &mut self,
+        id: u64,
+        params: Value,
+        is_kind: impl Fn(&Subscription) -> bool,
+    ) -> Result<Vec<Value>, Box<dyn Error + Send + Sync>> {
+        let sub_id = deser_unsubscribe_params(params)?;
+        if !self.subs.get(&sub_id).is_some_and(is_kind) {
+            return Err(Box::new(jsonrpc_core::Error::invalid_params(
+                "Invalid subscription id.",
+            )));
+        }
+        self.subs.remove(&sub_id);
+        Ok(vec![JsonRpcResp::new(id, true).into()])
+    }
+
+    async fn on_tx_landed(
+        &mut self,
+        TxLanded {
+            signature: landed_sig,
+            slot,
+            err,
+            written,
+        }: TxLanded,
+    ) -> Result<Vec<Value>, Box<dyn Error + Send + Sync>> {
+        let mut res = Vec::new();
+        let mut done = Vec::new();
+        for (sub_id, sub) in self.subs.iter_mut() {
+            match sub {
+                Subscription::Account { pubkey, cfg, last } if written.contains(pubkey) => {
+                    match account_notification_value(&mut self.server, pubkey, cfg, last).await {
+                        Ok(Some(value)) => res.push(account_notification(*sub_id, slot, value)),
+                        Ok(None) => (),
+                        // e.g. account grew too large for base58.
+                        // Only drop this subscription instead of ending the whole connection.
+                        // No error is sent since there is no request id to respond to,
+                        // and clients like PubsubClient close the connection on unknown ids.
+                        Err(_) => done.push(*sub_id),
+                    }
+                }
+                Subscription::Signature {
+                    signature,
+                    enable_received_notification,
+                } if *signature == landed_sig => {
+                    res.extend(signature_landed_notifications(
+                        *sub_id,
+                        slot,
+                        *enable_received_notification,
+                        err.clone(),
+                    ));
+                    done.push(*sub_id);
+                }
+                _ => continue,
+            }
+        }
+        // signature subscriptions are removed after the tx lands, like a real RPC
+        // and account subscriptions are removed if they can no longer be notified
+        for sub_id in done {
+            self.subs.remove(&sub_id);
+        }
+        Ok(res)
+    }
+
+    /// Notifies of changes that did not come from this server's `sendTransaction`.
+    ///
+    /// Changes that did are usually notified by [`Self::on_tx_landed`] first,
+    /// after which polling finds nothing new.
+    async fn poll(&mut self) -> Vec<Value> {
+        let mut res = self.poll_slot().await;
+        if self.subs.values().any(|s| !matches!(s, Subscription::Slot)) {
+            let slot = self.server.curr_slot().await;
+            res.extend(self.poll_accounts(slot).await);
+            res.extend(self.poll_signatures().await);
+        }
+        res
+    }
+
+    async fn poll_accounts(&mut self, slot: u64) -> Vec<Value> {
+        let mut res = Vec::new();
+        let mut done = Vec::new();
+        for (sub_id, sub) in self.subs.iter_mut() {
+            let Subscription::Account { pubkey, cfg, last } = sub else {
+                continue;
+            };
+            match account_notification_value(&mut self.server, pubkey, cfg, last).await {
+                Ok(Some(value)) => res.push(account_notification(*sub_id, slot, value)),
+                Ok(None) => (),
+                // see on_tx_landed()
+                Err(_) => done.push(*sub_id),
+            }
+        }
+        for sub_id in done {
+            self.subs.remove(&sub_id);
+        }
+        res
+    }
+
+    async fn poll_signatures(&mut self) -> Vec<Value> {
+        let mut res = Vec::new();
+        let mut done = Vec::new();
+        for (sub_id, sub) in self.subs.iter() {
+            let Subscription::Signature {
+                signature,
+                enable_received_notification,
+            } = sub
+            else {
+                continue;
+            };
+            // failed polls are retried on the next tick
+            if let Ok(Some(status)) = self.server.backend.get_transaction_status(*signature).await {
+                res.extend(signature_landed_notifications(
+                    *sub_id,
+                    status.slot,
+                    *enable_received_notification,
+                    status.err,
+                ));
+                done.push(*sub_id);
+            }
+        }
+        for sub_id in done {
+            self.subs.remove(&sub_id);
+        }
+        res
+    }
+
+    async fn poll_slot(&mut self) -> Vec<Value> {
+        if !self.subs.values().any(|s| matches!(s, Subscription::Slot)) {
+            return Vec::new();
+        }
+        let slot = self.server.curr_slot().await;
+        if slot == self.last_slot {
+            return Vec::new();
+        }
+        let slot_info = SlotInfo {
+            slot,
+            parent: self.last_slot,
+            root: self.server.finalized_slot().await,
+        };
+        self.last_slot = slot;
+        self.subs
+            .iter()
+            .filter(|(_, s)| matches!(s, Subscription::Slot))
+            .map(|(sub_id, _)| {
+                JsonRpcNotification::new("slotNotification", *sub_id, slot_info).into()
+            })
+            .collect()
+    }
+}
+
+/// Returns `None` if the account has not changed since the last notification
+async fn account_notification_value<B: RpcBackend>(
+    server: &mut BanksRpcServer<B>,
+    pubkey: &Pubkey,
+    cfg: &Option<RpcAccountInfoConfig>,
+    last: &mut Option<UiAccount>,
+) -> Result<Option<UiAccount>, Box<dyn Error + Send + Sync>> {
+    let curr = server.get_account_info(*pubkey, cfg.clone()).await?;
+    if curr == *last {
+        return Ok(None);
+    }
+    // deleted accounts are notified as empty system accounts like a real RPC
+    let value = match curr.clone() {
+        Some(a) => a,
+        None => {
+            let RpcAccountInfoConfig {
+                encoding,
+                data_slice,
+                ..
+            } = cfg.clone().unwrap_or_default();
+            server
+                .encode_account(pubkey, Account::default(), encoding, data_slice)
+                .await?
+        }
+    };
+    *last = curr;
+    Ok(Some(value))
+}
+
+fn account_notification(sub_id: u64, slot: u64, value: UiAccount) -> Value {
+    JsonRpcNotification::new(
+        "accountNotification",
+        sub_id,
+        RpcResponse {
+            context: RpcResponseContext::new(slot),
+            value,
+        },
+    )
+    .into()
+}
+
+fn signature_landed_notifications(
+    sub_id: u64,
+    slot: u64,
+    enable_received_notification: bool,
+    err: Option<TransactionError>,
+) -> Vec<Value> {
+    let received = enable_received_notification.then(|| {
+        signature_notification(
+            sub_id,
+            slot,
+            RpcSignatureResult::ReceivedSignature(ReceivedSignatureResult::ReceivedSignature),
+        )
+    });
+    let processed = signature_notification(
+        sub_id,
+        slot,
+        RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult { err }),
+    );
+    received.into_iter().chain([processed]).collect()
+}
+
+fn signature_notification(sub_id: u64, slot: u64, result: RpcSignatureResult) -> Value {
+    JsonRpcNotification::new(
+        "signatureNotification",
+        sub_id,
+        RpcResponse {
+            context: RpcResponseContext::new(slot),
+            value: result,
+        },
+    )
+    .into()
+}
@@ -0,0 +1,64 @@
+//! Recording of served requests and responses to a JSONL file, for replay by [`super::ReplayRpcServer`]
+
+use std::{
+    fs::File,
+    io::{self, BufRead, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{json_rpc::RpcMethod, BanksRpcServer, RpcBackend};
+
+/// A single line of a recording file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct RecordedExchange {
+    pub method: RpcMethod,
+    pub params: Value,
+
+    /// The entire JSON-RPC response object, `result` or `error`
+    pub response: Value,
+}
+
+pub(super) fn read_recording(reader: impl BufRead) -> io::Result<Vec<RecordedExchange>> {
+    reader
+        .lines()
+        .filter(|line| line.as_ref().map_or(true, |l| !l.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Recorder(Arc<Mutex<File>>);
+
+impl Recorder {
+    /// Each line is written and flushed immediately so that the file
+    /// is complete even if the server is never shut down gracefully
+    pub fn record(&self, method: RpcMethod, params: Value, response: &Value) -> io::Result<()> {
+        let mut line = serde_json::to_vec(&RecordedExchange {
+            method,
+            params,
+            response: response.clone(),
+        })?;
+        line.push(b'\n');
+        let mut file = self.0.lock().unwrap();
+        file.write_all(&line)?;
+        file.flush()
+    }
+}
+
+impl<B: RpcBackend> BanksRpcServer<B> {
+    /// Writes every JSON-RPC request served and its response to a JSONL file at `path`,
+    /// one line per request, truncating the file if it already exists.
+    ///
+    /// Requests that are dropped or responded to with a non-200 HTTP status by
+    /// fault injection are not recorded.
+    ///
+    /// The file can then be served by a [`super::ReplayRpcServer`]
+    pub fn with_recording(mut self, path: impl AsRef<Path>) -> io::Result<Self> {
+        self.recorder = Some(Recorder(Arc::new(Mutex::new(File::create(path)?))));
+        Ok(self)
+    }
+}
@@ -0,0 +1,164 @@
+//! A http server that serves responses recorded by [`super::BanksRpcServer::with_recording`]
+
+use std::{
+    error::Error,
+    fs::File,
+    future::Future,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use http_body_util::{BodyExt, Full};
+use hyper::{
+    body::{Bytes, Incoming},
+    service::Service,
+    Request, Response,
+};
+use serde_json::Value;
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use super::{
+    json_rpc::{parse_reqs, to_http_resp, JsonRpcErrResp, JsonRpcReq, RpcMethod},
+    record::{read_recording, RecordedExchange},
+    spawn_http_server,
+};
+
+/// All recorded responses to a single (method, params) pair, in the order they were served
+#[derive(Debug)]
+struct ReplayEntry {
+    method: RpcMethod,
+    params: Value,
+    responses: Vec<Value>,
+    served: usize,
+}
+
+/// A simulated solana RPC server that serves responses from a recording
+/// made with [`super::BanksRpcServer::with_recording`], without needing a `BanksClient`.
+///
+/// Requests are matched to recorded ones by method and params.
+/// If the same request was recorded multiple times, e.g. `getSignatureStatuses` polling,
+/// its recorded responses are served in order, with the last one repeated once they run out.
+///
+/// Requests with no matching recording are responded to with a JSON-RPC internal error.
+///
+/// Since params must match exactly, replays are only deterministic if the recorded session was,
+/// e.g. signing keypairs and blockhashes must be the same for `sendTransaction` to match.
+#[derive(Clone, Debug)]
+pub struct ReplayRpcServer {
+    entries: Arc<Mutex<Vec<ReplayEntry>>>,
+}
+
+impl ReplayRpcServer {
+    pub fn from_reader(reader: impl BufRead) -> io::Result<Self> {
+        let mut entries: Vec<ReplayEntry> = Vec::new();
+        for RecordedExchange {
+            method,
+            params,
+            response,
+        } in read_recording(reader)?
+        {
+            match entries
+                .iter_mut()
+                .find(|e| e.method == method && e.params == params)
+            {
+                Some(e) => e.responses.push(response),
+                None => entries.push(ReplayEntry {
+                    method,
+                    params,
+                    responses: vec![response],
+                    served: 0,
+                }),
+            }
+        }
+        Ok(Self {
+            entries: Arc::new(Mutex::new(entries)),
+        })
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Spawns the HTTP server on `http://127.0.0.1:{random_unused_port}` (IPV4).
+    ///
+    /// Returns `(bound_port, ReplayRpcServer join handle)`
+    pub async fn spawn_on_random_unused_port(
+        self,
+    ) -> (u16, JoinHandle<Result<(), Box<dyn Error + Send + Sync>>>) {
+        let tcp_listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let port = tcp_listener.local_addr().unwrap().port();
+        (port, self.spawn(tcp_listener))
+    }
+
+    /// Spawn the HTTP sever in the background
+    pub fn spawn(
+        self,
+        tcp_listener: TcpListener,
+    ) -> JoinHandle<Result<(), Box<dyn Error + Send + Sync>>> {
+        spawn_http_server(self, tcp_listener)
+    }
+
+    pub fn handle_req(
+        &self,
+        JsonRpcReq {
+            jsonrpc: _,
+            id,
+            method,
+            params,
+        }: JsonRpcReq,
+    ) -> Value {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = match entries
+            .iter_mut()
+            .find(|e| e.method == method && e.params == params)
+        {
+            Some(e) => e,
+            None => {
+                return JsonRpcErrResp::new(
+                    id,
+                    jsonrpc_core::Error {
+                        code: jsonrpc_core::ErrorCode::InternalError,
+                        message: format!(
+                            "No recorded response for {} with params {params}",
+                            method.name()
+                        ),
+                        data: None,
+                    },
+                )
+                .into()
+            }
+        };
+        let i = entry.served.min(entry.responses.len() - 1);
+        entry.served += 1;
+        let mut resp = entry.responses[i].clone();
+        if let Some(resp_id) = resp.get_mut("id") {
+            *resp_id = id.into();
+        }
+        resp
+    }
+}
+
+impl Service<Request<Incoming>> for ReplayRpcServer {
+    type Response = Response<Full<Bytes>>;
+
+    type Error = Box<dyn Error + Send + Sync>;
+
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn call(&self, req: Request<Incoming>) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            let body = req.into_body().collect().await?.to_bytes();
+            let (reqs, is_batch) = parse_reqs(body)?;
+            let mut res: Vec<Value> = reqs.into_iter().map(|r| this.handle_req(r)).collect();
+            let resp = if is_batch {
+                Value::Array(res)
+            } else {
+                res.pop().unwrap()
+            };
+            Ok(to_http_resp(serde_json::to_vec(&resp).unwrap().into()))
+        })
+    }
+}
@@ -0,0 +1,109 @@
+use std::{
+    collections::BTreeSet,
+    ops::{Deref, DerefMut},
+};
+
+use solana_program::{hash::Hash, pubkey::Pubkey};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{account::Account, signature::Keypair};
+
+use crate::ExtendedProgramTest;
+
+use super::BanksRpcServer;
+
+/// A [`ProgramTest`] that records the pubkeys of the accounts added to it,
+/// so that the [`BanksRpcServer`]s started from it can scan them for `getProgramAccounts`
+/// and the token methods without listing them in [`BanksRpcServer::with_known_pubkeys`].
+///
+/// Accounts added with [`Self::add_account`] and [`ExtendedProgramTest`] methods,
+/// including those of the traits built on it like `add_fixtures_dir` or `add_upgradeable_program`, are recorded.
+/// Accounts added by other [`ProgramTest`] methods such as [`ProgramTest::add_program`] are not.
+///
+/// ## Example
+///
+/// ```rust
+/// use sanctum_solana_test_utils::{banks_rpc_server::RpcProgramTest, ExtendedProgramTest};
+/// use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+///
+/// async fn spawn_rpc_server() -> u16 {
+///     let (server, _payer, _rbh) = RpcProgramTest::default()
+///         .add_system_account(Pubkey::new_unique(), LAMPORTS_PER_SOL)
+///         .start_rpc_server()
+///         .await;
+///     let (port, _jh) = server.spawn_on_random_unused_port().await;
+///     port
+/// }
+/// ```
+#[derive(Default)]
+pub struct RpcProgramTest {
+    program_test: ProgramTest,
+    known_pubkeys: BTreeSet<Pubkey>,
+}
+
+impl RpcProgramTest {
+    pub fn new(program_test: ProgramTest) -> Self {
+        Self {
+            program_test,
+            known_pubkeys: BTreeSet::new(),
+        }
+    }
+
+    /// Same as [`ProgramTest::add_account`], but records `address`
+    pub fn add_account(&mut self, address: Pubkey, account: Account) {
+        self.program_test.add_account(address, account);
+        self.known_pubkeys.insert(address);
+    }
+
+    pub fn known_pubkeys(&self) -> &BTreeSet<Pubkey> {
+        &self.known_pubkeys
+    }
+
+    pub fn into_parts(self) -> (ProgramTest, BTreeSet<Pubkey>) {
+        (self.program_test, self.known_pubkeys)
+    }
+
+    /// Starts the [`ProgramTest`] and creates a [`BanksRpcServer`] for its `BanksClient`
+    /// with all recorded pubkeys registered.
+    ///
+    /// Returns `(server, payer, recent_blockhash)`
+    pub async fn start_rpc_server(self) -> (BanksRpcServer, Keypair, Hash) {
+        let (bc, payer, rbh) = self.program_test.start().await;
+        let server = BanksRpcServer::new(bc).with_known_pubkeys(self.known_pubkeys);
+        (server, payer, rbh)
+    }
+
+    /// Same as [`Self::start_rpc_server`], but starts a [`ProgramTestContext`]
+    pub async fn start_with_context_and_rpc_server(self) -> (BanksRpcServer, ProgramTestContext) {
+        let ctx = self.program_test.start_with_context().await;
+        let server =
+            BanksRpcServer::new(ctx.banks_client.clone()).with_known_pubkeys(self.known_pubkeys);
+        (server, ctx)
+    }
+}
+
+impl From<ProgramTest> for RpcProgramTest {
+    fn from(program_test: ProgramTest) -> Self {
+        Self::new(program_test)
+    }
+}
+
+impl Deref for RpcProgramTest {
+    type Target = ProgramTest;
+
+    fn deref(&self) -> &Self::Target {
+        &self.program_test
+    }
+}
+
+impl DerefMut for RpcProgramTest {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.program_test
+    }
+}
+
+impl ExtendedProgramTest for RpcProgramTest {
+    fn add_account_chained(mut self, address: Pubkey, account: Account) -> Self {
+        self.add_account(address, account);
+        self
+    }
+}
@@ -0,0 +1,234 @@
+//! SPL token RPC methods, for both Tokenkeg and Token-2022
+
+use std::{cmp, error::Error, str::FromStr};
+
+use solana_account_decoder::{
+    parse_account_data::SplTokenAdditionalData,
+    parse_token::{is_known_spl_token_id, token_amount_to_ui_amount_v2, UiTokenAmount},
+};
+use solana_program::{program_option::COption, pubkey::Pubkey};
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcTokenAccountsFilter},
+    response::{RpcKeyedAccount, RpcTokenAccountBalance},
+};
+use solana_sdk::{
+    account::Account,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account as TokenAccount, Mint},
+};
+
+use super::{BanksRpcServer, RpcBackend};
+
+/// Same as `solana_rpc::rpc::NUM_LARGEST_ACCOUNTS`
+const NUM_LARGEST_ACCOUNTS: usize = 20;
+
+fn unpack_token_account(account: &Account) -> Option<TokenAccount> {
+    if !is_known_spl_token_id(&account.owner) {
+        return None;
+    }
+    StateWithExtensions::<TokenAccount>::unpack(&account.data)
+        .ok()
+        .map(|s| s.base)
+}
+
+fn parse_pubkey_param(s: &str) -> Result<Pubkey, jsonrpc_core::Error> {
+    Pubkey::from_str(s)
+        .map_err(|e| jsonrpc_core::Error::invalid_params(format!("Invalid param: {e}")))
+}
+
+impl<B: RpcBackend> BanksRpcServer<B> {
+    async fn get_existing_account(
+        &mut self,
+        key: Pubkey,
+        commitment: CommitmentLevel,
+    ) -> Result<Account, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .backend
+            .get_account_with_commitment(key, commitment)
+            .await?
+            .ok_or_else(|| {
+                jsonrpc_core::Error::invalid_params("Invalid param: could not find account")
+            })?)
+    }
+
+    async fn existing_mint_additional_data(
+        &mut self,
+        mint: Pubkey,
+    ) -> Result<SplTokenAdditionalData, Box<dyn Error + Send + Sync>> {
+        Ok(self.mint_additional_data(mint).await?.ok_or_else(|| {
+            jsonrpc_core::Error::invalid_params("Invalid param: not a Token mint")
+        })?)
+    }
+
+    /// Returns `(token program to scan, mint to filter by)`
+    async fn resolve_token_accounts_filter(
+        &mut self,
+        filter: RpcTokenAccountsFilter,
+    ) -> Result<(Pubkey, Option<Pubkey>), Box<dyn Error + Send + Sync>> {
+        match filter {
+            RpcTokenAccountsFilter::Mint(mint) => {
+                let mint = parse_pubkey_param(&mint)?;
+                let mint_acc = self.backend.get_account(mint).await?.ok_or_else(|| {
+                    jsonrpc_core::Error::invalid_params("Invalid param: could not find mint")
+                })?;
+                if !is_known_spl_token_id(&mint_acc.owner) {
+                    return Err(Box::new(jsonrpc_core::Error::invalid_params(
+                        "Invalid param: not a Token mint",
+                    )));
+                }
+                Ok((mint_acc.owner, Some(mint)))
+            }
+            RpcTokenAccountsFilter::ProgramId(program_id) => {
+                let program_id = parse_pubkey_param(&program_id)?;
+                if !is_known_spl_token_id(&program_id) {
+                    return Err(Box::new(jsonrpc_core::Error::invalid_params(
+                        "Invalid param: unrecognized Token program id",
+                    )));
+                }
+                Ok((program_id, None))
+            }
+        }
+    }
+
+    /// Scans the same accounts as `getProgramAccounts` for `token_program`'s token accounts that pass `pred`
+    async fn scan_token_accounts(
+        &mut self,
+        token_program: Pubkey,
+        commitment: CommitmentLevel,
+        pred: impl Fn(&TokenAccount) -> bool,
+    ) -> Result<Vec<(Pubkey, Account, TokenAccount)>, Box<dyn Error + Send + Sync>> {
+        let mut res = Vec::new();
+        for pubkey in self.scanned_pubkeys() {
+            let account = match self
+                .backend
+                .get_account_with_commitment(pubkey, commitment)
+                .await?
+            {
+                Some(a) if a.owner == token_program => a,
+                _ => continue,
+            };
+            match unpack_token_account(&account) {
+                Some(token_acc) if pred(&token_acc) => res.push((pubkey, account, token_acc)),
+                _ => continue,
+            }
+        }
+        Ok(res)
+    }
+
+    async fn get_token_accounts_by(
+        &mut self,
+        filter: RpcTokenAccountsFilter,
+        cfg: Option<RpcAccountInfoConfig>,
+        pred: impl Fn(&TokenAccount) -> bool,
+    ) -> Result<Vec<RpcKeyedAccount>, Box<dyn Error + Send + Sync>> {
+        let RpcAccountInfoConfig {
+            encoding,
+            data_slice,
+            commitment,
+            ..
+        } = cfg.unwrap_or_default();
+        let (token_program, mint) = self.resolve_token_accounts_filter(filter).await?;
+        let accounts = self
+            .scan_token_accounts(
+                token_program,
+                commitment.unwrap_or_default().commitment,
+                |t| mint.is_none_or(|m| t.mint == m) && pred(t),
+            )
+            .await?;
+        let mut res = Vec::with_capacity(accounts.len());
+        for (pubkey, account, _) in accounts {
+            res.push(RpcKeyedAccount {
+                pubkey: pubkey.to_string(),
+                account: self
+                    .encode_account(&pubkey, account, encoding, data_slice)
+                    .await?,
+            });
+        }
+        Ok(res)
+    }
+
+    pub async fn get_token_account_balance(
+        &mut self,
+        key: Pubkey,
+        cfg: Option<CommitmentConfig>,
+    ) -> Result<UiTokenAmount, Box<dyn Error + Send + Sync>> {
+        let account = self
+            .get_existing_account(key, cfg.unwrap_or_default().commitment)
+            .await?;
+        let token_acc = unpack_token_account(&account).ok_or_else(|| {
+            jsonrpc_core::Error::invalid_params("Invalid param: not a Token account")
+        })?;
+        let additional_data = self.existing_mint_additional_data(token_acc.mint).await?;
+        Ok(token_amount_to_ui_amount_v2(
+            token_acc.amount,
+            &additional_data,
+        ))
+    }
+
+    /// Scans the same accounts as [`Self::get_program_accounts`]
+    pub async fn get_token_accounts_by_owner(
+        &mut self,
+        owner: Pubkey,
+        filter: RpcTokenAccountsFilter,
+        cfg: Option<RpcAccountInfoConfig>,
+    ) -> Result<Vec<RpcKeyedAccount>, Box<dyn Error + Send + Sync>> {
+        self.get_token_accounts_by(filter, cfg, |t| t.owner == owner)
+            .await
+    }
+
+    /// Scans the same accounts as [`Self::get_program_accounts`]
+    pub async fn get_token_accounts_by_delegate(
+        &mut self,
+        delegate: Pubkey,
+        filter: RpcTokenAccountsFilter,
+        cfg: Option<RpcAccountInfoConfig>,
+    ) -> Result<Vec<RpcKeyedAccount>, Box<dyn Error + Send + Sync>> {
+        self.get_token_accounts_by(filter, cfg, |t| t.delegate == COption::Some(delegate))
+            .await
+    }
+
+    pub async fn get_token_supply(
+        &mut self,
+        mint: Pubkey,
+        cfg: Option<CommitmentConfig>,
+    ) -> Result<UiTokenAmount, Box<dyn Error + Send + Sync>> {
+        let account = self
+            .get_existing_account(mint, cfg.unwrap_or_default().commitment)
+            .await?;
+        let supply = is_known_spl_token_id(&account.owner)
+            .then(|| StateWithExtensions::<Mint>::unpack(&account.data).ok())
+            .flatten()
+            .map(|m| m.base.supply)
+            .ok_or_else(|| {
+                jsonrpc_core::Error::invalid_params("Invalid param: not a Token mint")
+            })?;
+        let additional_data = self.existing_mint_additional_data(mint).await?;
+        Ok(token_amount_to_ui_amount_v2(supply, &additional_data))
+    }
+
+    /// Scans the same accounts as [`Self::get_program_accounts`]
+    pub async fn get_token_largest_accounts(
+        &mut self,
+        mint: Pubkey,
+        cfg: Option<CommitmentConfig>,
+    ) -> Result<Vec<RpcTokenAccountBalance>, Box<dyn Error + Send + Sync>> {
+        let commitment = cfg.unwrap_or_default().commitment;
+        let token_program = self.get_existing_account(mint, commitment).await?.owner;
+        let additional_data = self.existing_mint_additional_data(mint).await?;
+        let mut accounts = self
+            .scan_token_accounts(token_program, commitment, |t| t.mint == mint)
+            .await?;
+        accounts.sort_by_key(|(_, _, t)| cmp::Reverse(t.amount));
+        Ok(accounts
+            .into_iter()
+            .take(NUM_LARGEST_ACCOUNTS)
+            .map(|(pubkey, _, token_acc)| RpcTokenAccountBalance {
+                address: pubkey.to_string(),
+                amount: token_amount_to_ui_amount_v2(token_acc.amount, &additional_data),
+            })
+            .collect())
+    }
+}
@@ -2,9 +2,12 @@ use std::process::Output;
 
 use assert_cmd::Command;
 use async_trait::async_trait;
+use data_encoding::BASE64;
+use serde::de::DeserializeOwned;
 use solana_program_test::{BanksClientError, BanksTransactionResultWithMetadata};
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::VersionedTransaction};
 
-use crate::ExtendedBanksClient;
+use crate::{cosign_versioned_tx, ExtendedBanksClient};
 
 #[async_trait]
 pub trait ExtendedCommand {
@@ -14,6 +17,38 @@ pub trait ExtendedCommand {
         &mut self,
         bc: impl ExtendedBanksClient + Send,
     ) -> Vec<Result<BanksTransactionResultWithMetadata, BanksClientError>>;
+
+    /// Same as [`Self::exec_b64_txs`], but the transactions may be partially signed.
+    /// Each transaction is signed by those of `signers` that are required signers before it is executed.
+    async fn exec_b64_txs_cosigned(
+        &mut self,
+        bc: impl ExtendedBanksClient + Send,
+        signers: &[&Keypair],
+    ) -> Vec<Result<BanksTransactionResultWithMetadata, BanksClientError>>;
+
+    /// Runs the Command, asserts it exited with status 0 and deserializes its stdout as json
+    fn output_json<T: DeserializeOwned>(&mut self) -> T;
+
+    /// Runs the Command and asserts its stderr contains `expected`, regardless of exit status
+    fn assert_stderr_contains(&mut self, expected: &str) -> Output;
+
+    /// Runs the Command and asserts it exited with non-zero status and its stderr contains `expected`
+    fn assert_failure_stderr_contains(&mut self, expected: &str) -> Output;
+}
+
+/// Runs the command, returning its stdout, eprinting stderr if the Command did not exit with status 0
+fn output_success_stdout(cmd: &mut Command) -> String {
+    let Output {
+        stdout,
+        status,
+        stderr,
+    } = cmd.output().unwrap();
+    assert!(
+        status.success(),
+        "{}",
+        std::str::from_utf8(&stderr).unwrap()
+    );
+    String::from_utf8(stdout).unwrap()
 }
 
 #[async_trait]
@@ -22,17 +57,7 @@ impl ExtendedCommand for Command {
         &mut self,
         mut bc: impl ExtendedBanksClient + Send,
     ) -> Vec<Result<BanksTransactionResultWithMetadata, BanksClientError>> {
-        let Output {
-            stdout,
-            status,
-            stderr,
-        } = self.output().unwrap();
-        assert!(
-            status.success(),
-            "{}",
-            std::str::from_utf8(&stderr).unwrap()
-        );
-        let stdout = std::str::from_utf8(&stdout).unwrap();
+        let stdout = output_success_stdout(self);
         // run txs in sequence, waiting on result of the prev before exec-ing next
         let mut res = vec![];
         for b64 in stdout.split('\n') {
@@ -42,6 +67,63 @@ impl ExtendedCommand for Command {
         }
         res
     }
+
+    async fn exec_b64_txs_cosigned(
+        &mut self,
+        mut bc: impl ExtendedBanksClient + Send,
+        signers: &[&Keypair],
+    ) -> Vec<Result<BanksTransactionResultWithMetadata, BanksClientError>> {
+        let stdout = output_success_stdout(self);
+        let b64_txs: Vec<String> = stdout
+            .split('\n')
+            .filter(|b64| !b64.is_empty())
+            .map(|b64| {
+                let mut tx: VersionedTransaction =
+                    bincode::deserialize(&BASE64.decode(b64.as_bytes()).unwrap()).unwrap();
+                let n_signers: usize = tx.message.header().num_required_signatures.into();
+                let required_signers = &tx.message.static_account_keys()[..n_signers];
+                let tx_signers: Vec<&dyn Signer> = signers
+                    .iter()
+                    .filter(|s| required_signers.contains(&s.pubkey()))
+                    .map(|s| *s as &dyn Signer)
+                    .collect();
+                cosign_versioned_tx(&mut tx, &tx_signers);
+                BASE64.encode(&bincode::serialize(&tx).unwrap())
+            })
+            .collect();
+        // run txs in sequence, waiting on result of the prev before exec-ing next
+        let mut res = vec![];
+        for b64 in b64_txs {
+            res.push(bc.exec_b64_tx(b64.as_bytes()).await);
+        }
+        res
+    }
+
+    fn output_json<T: DeserializeOwned>(&mut self) -> T {
+        let stdout = output_success_stdout(self);
+        serde_json::from_str(&stdout)
+            .unwrap_or_else(|e| panic!("failed to parse stdout as json: {e}. stdout: {stdout}"))
+    }
+
+    fn assert_stderr_contains(&mut self, expected: &str) -> Output {
+        let output = self.output().unwrap();
+        let stderr = std::str::from_utf8(&output.stderr).unwrap();
+        assert!(
+            stderr.contains(expected),
+            "{expected:?} not found in stderr: {stderr}"
+        );
+        output
+    }
+
+    fn assert_failure_stderr_contains(&mut self, expected: &str) -> Output {
+        let output = self.assert_stderr_contains(expected);
+        assert!(
+            !output.status.success(),
+            "expected failure, exited with {}",
+            output.status
+        );
+        output
+    }
 }
 
 /// To be used with result returned from [`ExtendedCommand::exec_b64_txs`]
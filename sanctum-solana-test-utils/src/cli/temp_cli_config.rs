@@ -18,14 +18,23 @@ pub struct TempCliConfig {
 }
 
 impl TempCliConfig {
+    /// `websocket_url` is left empty, so it is computed from `json_rpc_url` the same way the solana CLI does
     pub fn from_keypair_and_rpc_url(keypair: &Keypair, json_rpc_url: String) -> Self {
+        Self::from_keypair_and_urls(keypair, json_rpc_url, "".to_owned())
+    }
+
+    pub fn from_keypair_and_urls(
+        keypair: &Keypair,
+        json_rpc_url: String,
+        websocket_url: String,
+    ) -> Self {
         let keypair = temp_keypair_file(keypair);
         let config = NamedTempFile::new().unwrap();
         serde_yaml::to_writer(
             config.as_file(),
             &Config {
                 json_rpc_url,
-                websocket_url: "".to_owned(),
+                websocket_url,
                 keypair_path: keypair.path().to_str().unwrap().to_owned(),
                 address_labels: HashMap::new(),
                 commitment: "confirmed".to_owned(),
@@ -35,9 +44,14 @@ impl TempCliConfig {
         Self { keypair, config }
     }
 
-    /// Config will point to `http://127.0.0.1:{port}`
+    /// Config will point to `http://127.0.0.1:{port}` and `ws://127.0.0.1:{port}`,
+    /// which is where `BanksRpcServer` serves both
     pub fn from_keypair_and_local_port(keypair: &Keypair, port: u16) -> Self {
-        Self::from_keypair_and_rpc_url(keypair, format!("http://127.0.0.1:{port}"))
+        Self::from_keypair_and_urls(
+            keypair,
+            format!("http://127.0.0.1:{port}"),
+            format!("ws://127.0.0.1:{port}"),
+        )
     }
 
     // Access keypair and config via getters to ensure this struct is never
@@ -69,6 +83,10 @@ mod tests {
             config.as_ref().json_rpc_url,
             format!("http://127.0.0.1:{port}")
         );
+        assert_eq!(
+            config.as_ref().websocket_url,
+            format!("ws://127.0.0.1:{port}")
+        );
         assert_eq!(config.signer().pubkey(), kp.pubkey());
     }
 }
@@ -0,0 +1,306 @@
+//! Typed reports of executed transactions, parsed from tx metadata and program logs
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use solana_program::pubkey::Pubkey;
+use solana_program_test::BanksTransactionResultWithMetadata;
+use solana_sdk::{transaction::TransactionError, transaction_context::TransactionReturnData};
+
+use crate::zero_padded_return_data;
+
+/// Set this env var to any value to create/overwrite entries in CU baseline files
+/// instead of asserting against them.
+pub const UPDATE_CU_BASELINE_ENV_VAR: &str = "UPDATE_CU_BASELINE";
+
+/// A single program invocation, parsed from the `Program <id> invoke [<depth>]` log
+/// and its corresponding `Program <id> success` / `Program <id> failed: <err>` log
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Invocation {
+    pub program_id: Pubkey,
+
+    /// 1 for top-level instructions
+    pub depth: usize,
+
+    /// `None` for builtin programs, which do not log compute units consumed
+    pub compute_units_consumed: Option<u64>,
+
+    /// `None` if the invocation did not complete e.g. logs were truncated
+    pub success: Option<bool>,
+
+    /// "Program log: " messages emitted by this invocation, prefix stripped
+    pub logs: Vec<String>,
+
+    /// CPIs made by this invocation, in order
+    pub children: Vec<Invocation>,
+}
+
+impl Invocation {
+    fn new(program_id: Pubkey, depth: usize) -> Self {
+        Self {
+            program_id,
+            depth,
+            compute_units_consumed: None,
+            success: None,
+            logs: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Parses the tree of program invocations from a tx's log messages.
+///
+/// Returns the top-level invocations, one per instruction in the tx.
+pub fn parse_invocations<S: AsRef<str>>(log_messages: &[S]) -> Vec<Invocation> {
+    let mut top_level = Vec::new();
+    let mut stack: Vec<Invocation> = Vec::new();
+    for msg in log_messages {
+        let msg = msg.as_ref();
+        if let Some(log) = msg.strip_prefix("Program log: ") {
+            if let Some(curr) = stack.last_mut() {
+                curr.logs.push(log.to_owned());
+            }
+            continue;
+        }
+        let Some(rest) = msg.strip_prefix("Program ") else {
+            continue;
+        };
+        let Some((program_id, rest)) = rest.split_once(' ') else {
+            continue;
+        };
+        let Ok(program_id) = Pubkey::from_str(program_id) else {
+            continue;
+        };
+        if let Some(depth) = rest
+            .strip_prefix("invoke [")
+            .and_then(|d| d.strip_suffix(']'))
+            .and_then(|d| d.parse().ok())
+        {
+            stack.push(Invocation::new(program_id, depth));
+        } else if let Some(cus) = rest
+            .strip_prefix("consumed ")
+            .and_then(|r| r.split_once(' '))
+            .and_then(|(cus, _)| cus.parse().ok())
+        {
+            if let Some(curr) = stack.last_mut() {
+                curr.compute_units_consumed = Some(cus);
+            }
+        } else {
+            let success = match rest {
+                "success" => true,
+                r if r.starts_with("failed") => false,
+                _ => continue,
+            };
+            let Some(mut curr) = stack.pop() else {
+                continue;
+            };
+            curr.success = Some(success);
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(curr),
+                None => top_level.push(curr),
+            }
+        }
+    }
+    // logs truncated, unwind incomplete invocations
+    while let Some(curr) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(curr),
+            None => top_level.push(curr),
+        }
+    }
+    top_level
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutionReport {
+    pub result: Result<(), TransactionError>,
+
+    /// Total compute units consumed by the tx
+    pub compute_units_consumed: u64,
+
+    pub log_messages: Vec<String>,
+
+    /// NB: truncated. Use [`Self::return_data_padded`] to get the zero-padded data
+    pub return_data: Option<TransactionReturnData>,
+
+    /// Top-level invocations, one per instruction
+    pub invocations: Vec<Invocation>,
+}
+
+impl From<BanksTransactionResultWithMetadata> for ExecutionReport {
+    fn from(
+        BanksTransactionResultWithMetadata { result, metadata }: BanksTransactionResultWithMetadata,
+    ) -> Self {
+        let (log_messages, compute_units_consumed, return_data) = match metadata {
+            Some(m) => (m.log_messages, m.compute_units_consumed, m.return_data),
+            None => (Vec::new(), 0, None),
+        };
+        let invocations = parse_invocations(&log_messages);
+        Self {
+            result,
+            compute_units_consumed,
+            log_messages,
+            return_data,
+            invocations,
+        }
+    }
+}
+
+impl ExecutionReport {
+    /// Compute units consumed by each top-level instruction, in order.
+    /// `None` for builtin programs.
+    pub fn ix_compute_units(&self) -> Vec<Option<u64>> {
+        self.invocations
+            .iter()
+            .map(|i| i.compute_units_consumed)
+            .collect()
+    }
+
+    /// Return data passed through [`zero_padded_return_data`]
+    pub fn return_data_padded<const N: usize>(&self) -> Option<[u8; N]> {
+        self.return_data
+            .as_ref()
+            .map(|rd| zero_padded_return_data(&rd.data))
+    }
+
+    pub fn assert_success(&self) {
+        if let Err(e) = &self.result {
+            panic!("tx failed: {e}. Logs: {:#?}", self.log_messages);
+        }
+    }
+
+    pub fn assert_cu_below(&self, max: u64) {
+        assert!(
+            self.compute_units_consumed < max,
+            "tx consumed {} CUs, expected < {max}",
+            self.compute_units_consumed
+        );
+    }
+
+    /// Panics if instruction `ix_index` is a builtin program invocation
+    pub fn assert_ix_cu_below(&self, ix_index: usize, max: u64) {
+        let cus = self.invocations[ix_index]
+            .compute_units_consumed
+            .unwrap_or_else(|| panic!("ix {ix_index} did not log compute units consumed"));
+        assert!(
+            cus < max,
+            "ix {ix_index} consumed {cus} CUs, expected < {max}"
+        );
+    }
+
+    pub fn assert_log_contains(&self, substr: &str) {
+        assert!(
+            self.log_messages.iter().any(|l| l.contains(substr)),
+            "{substr:?} not found in logs: {:#?}",
+            self.log_messages
+        );
+    }
+
+    /// Compares [`Self::compute_units_consumed`] against entry `name` in the
+    /// JSON CU baseline file at `path`.
+    ///
+    /// If [`UPDATE_CU_BASELINE_ENV_VAR`] is set, creates or overwrites the entry
+    /// with the current value instead.
+    ///
+    /// ## Panics
+    /// If compute units consumed is greater than the baseline's,
+    /// or the entry does not exist and [`UPDATE_CU_BASELINE_ENV_VAR`] is not set
+    pub fn assert_cu_baseline(&self, path: impl AsRef<Path>, name: &str) {
+        let mut baseline = CuBaseline::load(path.as_ref());
+        if std::env::var_os(UPDATE_CU_BASELINE_ENV_VAR).is_some() {
+            baseline
+                .entries
+                .insert(name.to_owned(), self.compute_units_consumed);
+            baseline.save();
+            return;
+        }
+        let expected = baseline.entries.get(name).unwrap_or_else(|| {
+            panic!("no baseline entry `{name}`, set {UPDATE_CU_BASELINE_ENV_VAR}")
+        });
+        assert!(
+            self.compute_units_consumed <= *expected,
+            "{name} consumed {} CUs, up from baseline {expected}. Set {UPDATE_CU_BASELINE_ENV_VAR} to update baseline",
+            self.compute_units_consumed
+        );
+    }
+}
+
+/// A JSON file of `{ name: compute_units_consumed }`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CuBaseline {
+    pub path: PathBuf,
+    pub entries: BTreeMap<String, u64>,
+}
+
+impl CuBaseline {
+    /// Returns an empty baseline if file at `path` does not exist
+    pub fn load(path: &Path) -> Self {
+        let entries = match std::fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => panic!("{e}"),
+        };
+        Self {
+            path: path.to_owned(),
+            entries,
+        }
+    }
+
+    pub fn save(&self) {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        let mut json = serde_json::to_string_pretty(&self.entries).unwrap();
+        json.push('\n');
+        std::fs::write(&self.path, json).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_invocations_nested() {
+        let [a, b, c] = [(); 3].map(|_| Pubkey::new_unique());
+        let logs = [
+            format!("Program {a} invoke [1]"),
+            "Program log: hello".to_owned(),
+            format!("Program {b} invoke [2]"),
+            format!("Program {b} success"),
+            format!("Program {c} invoke [2]"),
+            format!("Program {c} consumed 100 of 1000 compute units"),
+            format!("Program {c} failed: custom program error: 0x1"),
+            format!("Program {a} consumed 500 of 1200 compute units"),
+            format!("Program {a} failed: custom program error: 0x1"),
+        ];
+        let res = parse_invocations(&logs);
+        assert_eq!(res.len(), 1);
+        let top = &res[0];
+        assert_eq!(top.program_id, a);
+        assert_eq!(top.compute_units_consumed, Some(500));
+        assert_eq!(top.success, Some(false));
+        assert_eq!(top.logs, ["hello"]);
+        assert_eq!(top.children.len(), 2);
+        assert_eq!(top.children[0].program_id, b);
+        assert_eq!(top.children[0].compute_units_consumed, None);
+        assert_eq!(top.children[0].success, Some(true));
+        assert_eq!(top.children[1].compute_units_consumed, Some(100));
+        assert_eq!(top.children[1].depth, 2);
+    }
+
+    #[test]
+    fn parse_invocations_truncated() {
+        let a = Pubkey::new_unique();
+        let logs = [
+            format!("Program {a} invoke [1]"),
+            "Log truncated".to_owned(),
+        ];
+        let res = parse_invocations(&logs);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].success, None);
+    }
+}
@@ -7,6 +7,8 @@ use solana_sdk::{
     account::Account, transaction::VersionedTransaction, transaction_context::TransactionReturnData,
 };
 
+use crate::{AccountsDiff, AccountsSnapshot, ExecutionReport};
+
 #[async_trait]
 pub trait ExtendedBanksClient {
     /// NB: return data is truncated. Probably wanna pass it through zero_padded_return_data() first
@@ -15,6 +17,14 @@ pub trait ExtendedBanksClient {
         tx: T,
     ) -> TransactionReturnData;
 
+    /// Executes `tx`, returning its result, logs, CUs consumed and return data.
+    ///
+    /// Does not panic if the tx fails, use [`ExecutionReport::assert_success`]
+    async fn exec_with_report<T: Into<VersionedTransaction> + Send>(
+        &mut self,
+        tx: T,
+    ) -> ExecutionReport;
+
     async fn get_account_unwrapped(&mut self, addr: Pubkey) -> Account;
 
     async fn get_account_data(&mut self, addr: Pubkey) -> Vec<u8>;
@@ -31,6 +41,12 @@ pub trait ExtendedBanksClient {
         &mut self,
         b64_tx: &[u8],
     ) -> Result<BanksTransactionResultWithMetadata, BanksClientError>;
+
+    /// Fetches the current state of `addrs`. Nonexistent accounts are recorded as `None`
+    async fn snapshot_accounts(&mut self, addrs: &[Pubkey]) -> AccountsSnapshot;
+
+    /// Takes a new snapshot of the same accounts as `before` and diffs it against `before`
+    async fn diff_since_snapshot(&mut self, before: &AccountsSnapshot) -> AccountsDiff;
 }
 
 #[async_trait]
@@ -42,6 +58,13 @@ impl<B: ExtendedBanksClient + Send> ExtendedBanksClient for &mut B {
         (*self).exec_get_return_data(tx).await
     }
 
+    async fn exec_with_report<T: Into<VersionedTransaction> + Send>(
+        &mut self,
+        tx: T,
+    ) -> ExecutionReport {
+        (*self).exec_with_report(tx).await
+    }
+
     async fn get_account_unwrapped(&mut self, addr: Pubkey) -> Account {
         (*self).get_account_unwrapped(addr).await
     }
@@ -64,6 +87,14 @@ impl<B: ExtendedBanksClient + Send> ExtendedBanksClient for &mut B {
     ) -> Result<BanksTransactionResultWithMetadata, BanksClientError> {
         (*self).exec_b64_tx(b64_tx).await
     }
+
+    async fn snapshot_accounts(&mut self, addrs: &[Pubkey]) -> AccountsSnapshot {
+        (*self).snapshot_accounts(addrs).await
+    }
+
+    async fn diff_since_snapshot(&mut self, before: &AccountsSnapshot) -> AccountsDiff {
+        (*self).diff_since_snapshot(before).await
+    }
 }
 
 #[async_trait]
@@ -78,6 +109,16 @@ impl ExtendedBanksClient for BanksClient {
         metadata.unwrap().return_data.unwrap()
     }
 
+    async fn exec_with_report<T: Into<VersionedTransaction> + Send>(
+        &mut self,
+        tx: T,
+    ) -> ExecutionReport {
+        self.process_transaction_with_metadata(tx)
+            .await
+            .unwrap()
+            .into()
+    }
+
     async fn get_account_unwrapped(&mut self, addr: Pubkey) -> Account {
         self.get_account(addr).await.unwrap().unwrap()
     }
@@ -104,6 +145,21 @@ impl ExtendedBanksClient for BanksClient {
         let tx: VersionedTransaction = bincode::deserialize(&bytes).unwrap();
         self.process_transaction_with_metadata(tx).await
     }
+
+    async fn snapshot_accounts(&mut self, addrs: &[Pubkey]) -> AccountsSnapshot {
+        let mut res = AccountsSnapshot::default();
+        for addr in addrs {
+            let account = self.get_account(*addr).await.unwrap();
+            res.accounts.insert(*addr, account);
+        }
+        res
+    }
+
+    async fn diff_since_snapshot(&mut self, before: &AccountsSnapshot) -> AccountsDiff {
+        let addrs: Vec<Pubkey> = before.keys().copied().collect();
+        let after = self.snapshot_accounts(&addrs).await;
+        before.diff(&after)
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,20 @@
-use std::{io::Write, path::Path};
+use std::{borrow::Cow, io::Write, path::Path};
 
-use solana_program::{pubkey::Pubkey, system_program};
+use solana_program::{
+    address_lookup_table::{
+        self,
+        state::{AddressLookupTable, LookupTableMeta},
+        AddressLookupTableAccount,
+    },
+    clock::Slot,
+    hash::Hash,
+    nonce::{
+        self,
+        state::{Data, DurableNonce, Versions},
+    },
+    pubkey::Pubkey,
+    system_program,
+};
 use solana_program_test::ProgramTest;
 use solana_readonly_account::keyed::Keyed;
 use solana_sdk::{
@@ -8,38 +22,15 @@ use solana_sdk::{
     bpf_loader_upgradeable::{self, UpgradeableLoaderState},
 };
 
-use crate::{default_rent_exempt_lamports, load_program_so, test_fixtures_dir, KeyedUiAccount};
+use crate::{
+    apply_fixture_overrides, default_rent_exempt_lamports, is_non_upgradeable_program,
+    load_program_so, so_file_program_id, test_fixtures_dir, FixtureFiles, FixturesDirOpts,
+    KeyedUiAccount,
+};
 
 /// For nice method syntax on [`ProgramTest`]
-pub trait ExtendedProgramTest {
+pub trait ExtendedProgramTest: Sized {
     fn add_account_chained(self, address: Pubkey, account: Account) -> Self;
-    fn add_keyed_account(self, keyed_account: Keyed<Account>) -> Self;
-    fn add_keyed_ui_account(self, keyed_ui_account: KeyedUiAccount) -> Self;
-    fn add_account_from_file<P: AsRef<Path>>(self, json_file_path: P) -> Self;
-    fn add_test_fixtures_account<P: AsRef<Path>>(self, relative_json_file_path: P) -> Self;
-    fn add_system_account(self, address: Pubkey, lamports: u64) -> Self;
-
-    /// Adds a compiled BPF program as an upgradeable program.
-    /// Like [ProgramTest::add_program], the program_name must match `{program_name}.so`
-    ///
-    /// Works the same way as [ProgramTest::add_program], except:
-    /// - sets the program's owner to BpfLoaderUpgradeable instead of BpfLoader
-    /// - always equivalent to prefer_bpf = true, only works with compiled .so files
-    fn add_upgradeable_program(
-        self,
-        program_id: Pubkey,
-        program_name: &str,
-        upgrade_auth_addr: Option<Pubkey>,
-        last_upgrade_slot: u64,
-    ) -> Self;
-}
-
-impl ExtendedProgramTest for ProgramTest {
-    fn add_account_chained(mut self, address: Pubkey, account: Account) -> Self {
-        self.add_account(address, account);
-        self
-    }
-
     fn add_keyed_account(self, Keyed { pubkey, account }: Keyed<Account>) -> Self {
         self.add_account_chained(pubkey, account)
     }
@@ -70,22 +61,44 @@ impl ExtendedProgramTest for ProgramTest {
         )
     }
 
+    /// Adds a compiled BPF program as an upgradeable program.
+    /// Like [ProgramTest::add_program], the program_name must match `{program_name}.so`
+    ///
+    /// Works the same way as [ProgramTest::add_program], except:
+    /// - sets the program's owner to BpfLoaderUpgradeable instead of BpfLoader
+    /// - always equivalent to prefer_bpf = true, only works with compiled .so files
     fn add_upgradeable_program(
-        mut self,
+        self,
         program_id: Pubkey,
         program_name: &str,
         upgrade_auth_addr: Option<Pubkey>,
         last_upgrade_slot: u64,
     ) -> Self {
-        let (prog_data_addr, _bump) =
-            Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID);
         let so_prog_data = load_program_so(program_name);
+        self.add_upgradeable_program_from_elf(
+            program_id,
+            &so_prog_data,
+            upgrade_auth_addr,
+            last_upgrade_slot,
+        )
+    }
 
+    /// Same as [`Self::add_upgradeable_program`], but with the program's ELF bytes
+    /// instead of loading them from `{program_name}.so`
+    fn add_upgradeable_program_from_elf(
+        mut self,
+        program_id: Pubkey,
+        so_prog_data: &[u8],
+        upgrade_auth_addr: Option<Pubkey>,
+        last_upgrade_slot: u64,
+    ) -> Self {
+        let (prog_data_addr, _bump) =
+            Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID);
         // add program account
         let mut prog_acc_data = Vec::with_capacity(UpgradeableLoaderState::size_of_program());
         prog_acc_data.write_all(&2u32.to_le_bytes()).unwrap();
         prog_acc_data.write_all(prog_data_addr.as_ref()).unwrap();
-        self.add_account(
+        self = self.add_account_chained(
             program_id,
             Account {
                 lamports: default_rent_exempt_lamports(UpgradeableLoaderState::size_of_program()),
@@ -113,8 +126,8 @@ impl ExtendedProgramTest for ProgramTest {
                 prog_data_acc_data.write_all(&[0u8; 33]).unwrap();
             }
         }
-        prog_data_acc_data.write_all(&so_prog_data).unwrap();
-        self.add_account(
+        prog_data_acc_data.write_all(so_prog_data).unwrap();
+        self = self.add_account_chained(
             prog_data_addr,
             Account {
                 lamports: default_rent_exempt_lamports(prog_data_acc_data.len()),
@@ -127,4 +140,133 @@ impl ExtendedProgramTest for ProgramTest {
 
         self
     }
+
+    /// Recursively adds all fixtures under `dir`:
+    /// - every `*.json` file as a [`KeyedUiAccount`].
+    ///   Executable accounts owned by the non-upgradeable BPF loaders are added as upgradeable programs.
+    /// - every `<program_id>.so` file as an upgradeable program
+    ///
+    /// Upgradeable programs are added with `opts.upgrade_authority` and
+    /// `opts.programdata_slot` (or 0) as last upgrade slot.
+    fn add_fixtures_dir<P: AsRef<Path>>(mut self, dir: P, opts: FixturesDirOpts) -> Self {
+        let FixtureFiles { accounts, programs } = FixtureFiles::collect(dir);
+        let last_upgrade_slot = opts.programdata_slot.unwrap_or(0);
+        for json_file_path in accounts {
+            let Keyed {
+                pubkey,
+                mut account,
+            } = KeyedUiAccount::from_file(json_file_path).to_keyed_account();
+            self = if is_non_upgradeable_program(&account) {
+                self.add_upgradeable_program_from_elf(
+                    pubkey,
+                    &account.data,
+                    opts.upgrade_authority,
+                    last_upgrade_slot,
+                )
+            } else {
+                apply_fixture_overrides(&mut account, &opts);
+                self.add_account_chained(pubkey, account)
+            };
+        }
+        for so_file_path in programs {
+            let elf = std::fs::read(&so_file_path).unwrap();
+            self = self.add_upgradeable_program_from_elf(
+                so_file_program_id(&so_file_path),
+                &elf,
+                opts.upgrade_authority,
+                last_upgrade_slot,
+            );
+        }
+        self
+    }
+
+    /// Recursively adds all fixtures under `<test_fixtures_dir()>/relative_dir`.
+    /// See [`Self::add_fixtures_dir`]
+    fn add_test_fixtures_dir<P: AsRef<Path>>(self, relative_dir: P, opts: FixturesDirOpts) -> Self {
+        self.add_fixtures_dir(test_fixtures_dir().join(relative_dir), opts)
+    }
+
+    /// Adds an address lookup table that was last extended at slot 0,
+    /// so that all `addresses` are usable right away.
+    ///
+    /// `authority = None` means the lookup table is frozen.
+    /// `deactivation_slot = None` means the lookup table is active.
+    ///
+    /// Returns the [`AddressLookupTableAccount`] for compiling v0 messages
+    fn add_address_lookup_table(
+        self,
+        addr: Pubkey,
+        authority: Option<Pubkey>,
+        addresses: Vec<Pubkey>,
+        deactivation_slot: Option<Slot>,
+    ) -> (Self, AddressLookupTableAccount) {
+        let data = AddressLookupTable {
+            meta: LookupTableMeta {
+                deactivation_slot: deactivation_slot.unwrap_or(Slot::MAX),
+                authority,
+                ..Default::default()
+            },
+            addresses: Cow::Borrowed(&addresses),
+        }
+        .serialize_for_tests()
+        .unwrap();
+        let pt = self.add_account_chained(
+            addr,
+            Account {
+                lamports: default_rent_exempt_lamports(data.len()),
+                data,
+                owner: address_lookup_table::program::ID,
+                executable: false,
+                rent_epoch: u64::MAX,
+            },
+        );
+        (
+            pt,
+            AddressLookupTableAccount {
+                key: addr,
+                addresses,
+            },
+        )
+    }
+
+    /// Adds an initialized durable nonce account.
+    ///
+    /// `durable_nonce` is the stored nonce value, which is the `recent_blockhash`
+    /// transactions using this nonce account must use.
+    fn add_nonce_account(self, addr: Pubkey, authority: Pubkey, durable_nonce: Hash) -> Self {
+        // DurableNonce can only be created from a blockhash,
+        // so deserialize it from the raw hash to store `durable_nonce` as-is
+        let durable_nonce: DurableNonce = bincode::deserialize(durable_nonce.as_ref()).unwrap();
+        let mut data = vec![0u8; nonce::State::size()];
+        bincode::serialize_into(
+            data.as_mut_slice(),
+            &Versions::new(nonce::State::Initialized(Data::new(
+                authority,
+                durable_nonce,
+                NONCE_ACCOUNT_LAMPORTS_PER_SIGNATURE,
+            ))),
+        )
+        .unwrap();
+        self.add_account_chained(
+            addr,
+            Account {
+                lamports: default_rent_exempt_lamports(data.len()),
+                data,
+                owner: system_program::ID,
+                executable: false,
+                rent_epoch: u64::MAX,
+            },
+        )
+    }
+}
+
+/// Lamports per signature recorded in nonce accounts added with
+/// [`ExtendedProgramTest::add_nonce_account`]
+pub const NONCE_ACCOUNT_LAMPORTS_PER_SIGNATURE: u64 = 5000;
+
+impl ExtendedProgramTest for ProgramTest {
+    fn add_account_chained(mut self, address: Pubkey, account: Account) -> Self {
+        self.add_account(address, account);
+        self
+    }
 }
@@ -1,4 +1,4 @@
-use std::io::Write;
+use std::{io::Write, path::Path};
 
 use async_trait::async_trait;
 use solana_program_test::ProgramTestContext;
@@ -11,7 +11,9 @@ use solana_sdk::{
     transaction::Transaction,
 };
 
-use crate::{default_rent_exempt_lamports, load_program_so, ExtendedBanksClient};
+use solana_readonly_account::keyed::Keyed;
+
+use crate::{default_rent_exempt_lamports, load_program_so, ExtendedBanksClient, KeyedUiAccount};
 
 /// Payer should have enough SOL for 2 transactions
 #[derive(Debug, Copy, Clone)]
@@ -42,6 +44,12 @@ pub trait ExtendedProgramTestContext {
         program_name: &str,
         signers: UpgradeProgramSigners<'async_trait>,
     ) -> &mut Self;
+
+    /// Writes each account in `addrs` to `<dir>/<pubkey>.json` in the [`KeyedUiAccount`] format,
+    /// which can be loaded back with [`crate::ExtendedProgramTest::add_fixtures_dir`].
+    ///
+    /// Accounts that do not exist are skipped.
+    async fn dump_accounts_to_dir<P: AsRef<Path> + Send>(&mut self, addrs: &[Pubkey], dir: P);
 }
 
 #[async_trait]
@@ -133,6 +141,21 @@ impl ExtendedProgramTestContext for ProgramTestContext {
 
         self
     }
+
+    async fn dump_accounts_to_dir<P: AsRef<Path> + Send>(&mut self, addrs: &[Pubkey], dir: P) {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir).unwrap();
+        for pubkey in addrs {
+            let Some(account) = self.banks_client.get_account(*pubkey).await.unwrap() else {
+                continue;
+            };
+            KeyedUiAccount::from_keyed_account(&Keyed {
+                pubkey: *pubkey,
+                account,
+            })
+            .to_file(dir.join(format!("{pubkey}.json")));
+        }
+    }
 }
 
 /*
@@ -0,0 +1,136 @@
+//! Bulk loading and dumping of account fixture directories
+
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use solana_program::{clock::Slot, pubkey::Pubkey, stake_history::Epoch};
+use solana_sdk::{
+    account::Account,
+    bpf_loader, bpf_loader_deprecated,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+};
+
+/// Options for [`crate::ExtendedProgramTest::add_fixtures_dir`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixturesDirOpts {
+    /// Overwrites the last deployed slot of upgradeable program data accounts.
+    ///
+    /// Mainnet-dumped program data accounts have a last deployed slot far ahead of
+    /// the test validator's, which means the program cannot be invoked.
+    pub programdata_slot: Option<Slot>,
+
+    /// Overwrites the `rent_epoch` of all loaded accounts
+    pub rent_epoch: Option<Epoch>,
+
+    /// Upgrade authority of programs loaded from `.so` files and
+    /// non-upgradeable programs converted to upgradeable ones
+    pub upgrade_authority: Option<Pubkey>,
+}
+
+impl Default for FixturesDirOpts {
+    fn default() -> Self {
+        Self {
+            programdata_slot: Some(0),
+            rent_epoch: None,
+            upgrade_authority: None,
+        }
+    }
+}
+
+/// Fixture files under a directory, sorted by path
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FixtureFiles {
+    /// `*.json` `KeyedUiAccount` dumps
+    pub accounts: Vec<PathBuf>,
+
+    /// `<program_id>.so` compiled programs
+    pub programs: Vec<PathBuf>,
+}
+
+impl FixtureFiles {
+    /// Recursively collects all fixture files under `dir`
+    pub fn collect<P: AsRef<Path>>(dir: P) -> Self {
+        let mut res = Self::default();
+        res.collect_into(dir.as_ref());
+        res.accounts.sort();
+        res.programs.sort();
+        res
+    }
+
+    fn collect_into(&mut self, dir: &Path) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                self.collect_into(&path);
+                continue;
+            }
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("json") => self.accounts.push(path),
+                Some("so") => self.programs.push(path),
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Parses the program ID from a `<program_id>.so` file path
+///
+/// ## Panics
+/// If file stem is not a valid pubkey
+pub fn so_file_program_id(so_file_path: &Path) -> Pubkey {
+    let stem = so_file_path.file_stem().unwrap().to_str().unwrap();
+    Pubkey::from_str(stem)
+        .unwrap_or_else(|_| panic!("{} must be named <program_id>.so", so_file_path.display()))
+}
+
+/// Returns true if `account` is an executable program owned by
+/// one of the non-upgradeable BPF loaders
+pub fn is_non_upgradeable_program(account: &Account) -> bool {
+    account.executable
+        && (account.owner == bpf_loader::ID || account.owner == bpf_loader_deprecated::ID)
+}
+
+/// Applies overrides in `opts` to a loaded fixture account
+pub fn apply_fixture_overrides(account: &mut Account, opts: &FixturesDirOpts) {
+    if let Some(rent_epoch) = opts.rent_epoch {
+        account.rent_epoch = rent_epoch;
+    }
+    if let Some(slot) = opts.programdata_slot {
+        if account.owner == bpf_loader_upgradeable::ID
+            && account.data.len() >= UpgradeableLoaderState::size_of_programdata_metadata()
+            && account.data[..4] == 3u32.to_le_bytes()
+        {
+            account.data[4..12].copy_from_slice(&slot.to_le_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_programdata_slot() {
+        let mut data = vec![0u8; UpgradeableLoaderState::size_of_programdata_metadata() + 8];
+        data[..4].copy_from_slice(&3u32.to_le_bytes());
+        data[4..12].copy_from_slice(&250_000_000u64.to_le_bytes());
+        let mut account = Account {
+            lamports: 1,
+            data,
+            owner: bpf_loader_upgradeable::ID,
+            executable: false,
+            rent_epoch: 500,
+        };
+        apply_fixture_overrides(
+            &mut account,
+            &FixturesDirOpts {
+                rent_epoch: Some(u64::MAX),
+                ..Default::default()
+            },
+        );
+        assert_eq!(account.data[4..12], 0u64.to_le_bytes());
+        assert_eq!(account.rent_epoch, u64::MAX);
+    }
+}
@@ -1,7 +1,7 @@
 use std::{fs::File, path::Path, str::FromStr};
 
 use serde::{Deserialize, Serialize};
-use solana_account_decoder::UiAccount;
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
 use solana_program::pubkey::Pubkey;
 use solana_readonly_account::keyed::Keyed;
 use solana_sdk::account::Account;
@@ -27,6 +27,20 @@ impl KeyedUiAccount {
         Self::from_file(test_fixtures_dir().join(relative_json_file_path))
     }
 
+    /// Writes this KeyedUiAccount as json to `json_file_path`, overwriting the file if it exists
+    pub fn to_file<P: AsRef<Path>>(&self, json_file_path: P) {
+        let mut file = File::create(json_file_path).unwrap();
+        serde_json::to_writer_pretty(&mut file, self).unwrap();
+    }
+
+    /// Base64-encodes account data, same as `solana account --output json`
+    pub fn from_keyed_account(Keyed { pubkey, account }: &Keyed<Account>) -> Self {
+        Self {
+            pubkey: pubkey.to_string(),
+            account: UiAccount::encode(pubkey, account, UiAccountEncoding::Base64, None, None),
+        }
+    }
+
     pub fn to_keyed_account(&self) -> Keyed<Account> {
         Keyed {
             pubkey: Pubkey::from_str(&self.pubkey).unwrap(),
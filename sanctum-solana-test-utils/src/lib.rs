@@ -1,16 +1,22 @@
+mod accounts_snapshot;
 mod consts;
+mod execution_report;
 mod extended_banks_client;
 mod extended_program_test;
 mod extended_program_test_context;
+mod fixtures_dir;
 mod into_account;
 mod keyed_ui_account;
 mod paths;
 mod tx;
 
+pub use accounts_snapshot::*;
 pub use consts::*;
+pub use execution_report::*;
 pub use extended_banks_client::*;
 pub use extended_program_test::*;
 pub use extended_program_test_context::*;
+pub use fixtures_dir::*;
 pub use into_account::*;
 pub use keyed_ui_account::*;
 pub use paths::*;
@@ -35,6 +41,10 @@ pub mod proptest_utils;
 #[cfg_attr(docsrs, doc(cfg(feature = "stake")))]
 pub mod stake;
 
+#[cfg(feature = "stake-pool")]
+#[cfg_attr(docsrs, doc(cfg(feature = "stake-pool")))]
+pub mod stake_pool;
+
 #[cfg(any(feature = "token", feature = "token-2022"))]
 #[cfg_attr(docsrs, doc(cfg(any(feature = "token", feature = "token-2022"))))]
 pub mod token;
@@ -1,14 +1,24 @@
 use borsh::BorshSerialize;
 use solana_program::{
+    clock::Clock,
     pubkey::Pubkey,
     stake::state::{Authorized, Delegation, Lockup, Meta, Stake, StakeStateV2},
-    stake_history::Epoch,
+    stake_history::{Epoch, StakeHistory},
 };
+use solana_program_test::ProgramTestContext;
 use solana_readonly_account::keyed::Keyed;
 use solana_sdk::{account::Account, stake::stake_flags::StakeFlags};
 
 use crate::{est_rent_exempt_lamports, ExtendedProgramTest, IntoAccount};
 
+mod stake_history;
+mod sysvars;
+mod vote;
+
+pub use stake_history::*;
+pub use sysvars::*;
+pub use vote::*;
+
 #[cfg(feature = "proptest")]
 pub mod proptest_utils;
 
@@ -91,6 +101,8 @@ pub trait StakeProgramTest {
         addr: Pubkey,
         params: ActiveOrActivatingUnlockedStakeAccount,
     ) -> Self;
+
+    fn add_vote_account(self, addr: Pubkey, args: MockVoteAccountArgs) -> Self;
 }
 
 impl<T: ExtendedProgramTest> StakeProgramTest for T {
@@ -168,6 +180,53 @@ impl<T: ExtendedProgramTest> StakeProgramTest for T {
     ) -> Self {
         self.add_live_stake_account(addr, params.into())
     }
+
+    fn add_vote_account(self, addr: Pubkey, args: MockVoteAccountArgs) -> Self {
+        self.add_keyed_account(Keyed {
+            pubkey: addr,
+            account: mock_vote_state(args).into_account(),
+        })
+    }
+}
+
+/// For nice method syntax on [`ProgramTestContext`]
+pub trait StakeProgramTestContext {
+    /// Warps to the first slot of `epoch`, then sets the `StakeHistory` sysvar to
+    /// [`mock_stake_history`] of `delegations` and returns it.
+    ///
+    /// ## Panics
+    ///
+    /// If the warp fails, e.g. `epoch` is not after the current epoch
+    fn warp_to_epoch_with_stake_history(
+        &mut self,
+        epoch: Epoch,
+        delegations: &[Delegation],
+        new_rate_activation_epoch: Option<Epoch>,
+    ) -> StakeHistory;
+
+    /// Sets the `Clock` sysvar to [`mock_clock_at_epoch`] without warping,
+    /// for programs that only read the clock. Overwritten by the next warp.
+    fn set_clock_to_epoch(&mut self, epoch: Epoch) -> Clock;
+}
+
+impl StakeProgramTestContext for ProgramTestContext {
+    fn warp_to_epoch_with_stake_history(
+        &mut self,
+        epoch: Epoch,
+        delegations: &[Delegation],
+        new_rate_activation_epoch: Option<Epoch>,
+    ) -> StakeHistory {
+        self.warp_to_epoch(epoch).unwrap();
+        let history = mock_stake_history(delegations, epoch, new_rate_activation_epoch);
+        self.set_sysvar(&history);
+        history
+    }
+
+    fn set_clock_to_epoch(&mut self, epoch: Epoch) -> Clock {
+        let clock = mock_clock_at_epoch(epoch, &self.genesis_config().epoch_schedule);
+        self.set_sysvar(&clock);
+        clock
+    }
 }
 
 impl IntoAccount for StakeStateAndLamports {
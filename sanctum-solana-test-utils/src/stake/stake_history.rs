@@ -0,0 +1,36 @@
+use solana_program::{
+    clock::Epoch,
+    stake::state::Delegation,
+    stake_history::{StakeHistory, StakeHistoryEntry},
+};
+
+/// Computes the [`StakeHistory`] the runtime would have produced for epochs `0..current_epoch`
+/// if `delegations` were the only stake delegations in the cluster.
+///
+/// Warmup and cooldown are rate-limited relative to the cluster's effective stake,
+/// so include a large bootstrap delegation (`activation_epoch = u64::MAX`) to model the rest of the cluster.
+/// Pass `Some(0)` for `new_rate_activation_epoch` to match `ProgramTest`, which activates all features at genesis.
+///
+/// The runtime overwrites the sysvar at every epoch boundary, so set it after warping to `current_epoch`,
+/// e.g. with [`super::StakeProgramTestContext::warp_to_epoch_with_stake_history`].
+pub fn mock_stake_history(
+    delegations: &[Delegation],
+    current_epoch: Epoch,
+    new_rate_activation_epoch: Option<Epoch>,
+) -> StakeHistory {
+    let mut history = StakeHistory::default();
+    for epoch in 0..current_epoch {
+        let entry = delegations
+            .iter()
+            .fold(StakeHistoryEntry::default(), |entry, delegation| {
+                entry
+                    + delegation.stake_activating_and_deactivating(
+                        epoch,
+                        &history,
+                        new_rate_activation_epoch,
+                    )
+            });
+        history.add(epoch, entry);
+    }
+    history
+}
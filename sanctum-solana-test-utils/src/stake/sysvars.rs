@@ -0,0 +1,42 @@
+use solana_program::{
+    clock::{Clock, Epoch, DEFAULT_MS_PER_SLOT},
+    epoch_rewards::EpochRewards,
+    epoch_schedule::EpochSchedule,
+};
+
+/// The [`Clock`] at the first slot of `epoch`,
+/// with timestamps assuming [`DEFAULT_MS_PER_SLOT`] since a unix timestamp of 0 at slot 0.
+///
+/// Use `ProgramTestContext::genesis_config().epoch_schedule` for `epoch_schedule`
+/// to match the test chain.
+pub fn mock_clock_at_epoch(epoch: Epoch, epoch_schedule: &EpochSchedule) -> Clock {
+    let slot = epoch_schedule.get_first_slot_in_epoch(epoch);
+    let unix_timestamp = (slot * DEFAULT_MS_PER_SLOT / 1000) as i64;
+    Clock {
+        slot,
+        epoch_start_timestamp: unix_timestamp,
+        epoch,
+        leader_schedule_epoch: epoch_schedule.get_leader_schedule_epoch(slot),
+        unix_timestamp,
+    }
+}
+
+/// An [`EpochRewards`] sysvar with a single-partition rewards distribution in progress,
+/// none of `total_rewards` distributed yet.
+///
+/// The stake program fails every instruction except `GetMinimumDelegation`
+/// with `StakeError::EpochRewardsActive` while this is set.
+/// Set [`EpochRewards::default`] to end the distribution.
+pub fn mock_active_epoch_rewards(
+    distribution_starting_block_height: u64,
+    total_rewards: u64,
+) -> EpochRewards {
+    EpochRewards {
+        distribution_starting_block_height,
+        num_partitions: 1,
+        total_points: total_rewards.into(),
+        total_rewards,
+        active: true,
+        ..Default::default()
+    }
+}
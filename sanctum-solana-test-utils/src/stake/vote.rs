@@ -0,0 +1,74 @@
+use solana_program::{
+    clock::{Clock, Epoch},
+    pubkey::Pubkey,
+    vote::state::{VoteInit, VoteState, VoteStateVersions},
+};
+use solana_sdk::account::Account;
+
+use crate::{est_rent_exempt_lamports, IntoAccount};
+
+#[derive(Clone, Debug)]
+pub struct MockVoteAccountArgs {
+    pub node_pubkey: Pubkey,
+
+    /// Authorized voter from epoch 0 onwards
+    pub authorized_voter: Pubkey,
+
+    pub authorized_withdrawer: Pubkey,
+    pub commission: u8,
+
+    /// `(epoch, credits, prev_credits)` in ascending order of epoch.
+    /// See [`cumulative_epoch_credits`]
+    pub epoch_credits: Vec<(Epoch, u64, u64)>,
+}
+
+pub fn mock_vote_state(
+    MockVoteAccountArgs {
+        node_pubkey,
+        authorized_voter,
+        authorized_withdrawer,
+        commission,
+        epoch_credits,
+    }: MockVoteAccountArgs,
+) -> VoteState {
+    let mut vote_state = VoteState::new(
+        &VoteInit {
+            node_pubkey,
+            authorized_voter,
+            authorized_withdrawer,
+            commission,
+        },
+        &Clock::default(),
+    );
+    vote_state.epoch_credits = epoch_credits;
+    vote_state
+}
+
+/// Converts `(epoch, credits earned in that epoch)` pairs
+/// into the `(epoch, credits, prev_credits)` format of [`VoteState::epoch_credits`]
+pub fn cumulative_epoch_credits(
+    earned: impl IntoIterator<Item = (Epoch, u64)>,
+) -> Vec<(Epoch, u64, u64)> {
+    earned
+        .into_iter()
+        .scan(0, |total, (epoch, credits)| {
+            let prev_credits = *total;
+            *total += credits;
+            Some((epoch, *total, prev_credits))
+        })
+        .collect()
+}
+
+impl IntoAccount for VoteState {
+    fn into_account(self) -> Account {
+        let mut data = vec![0u8; VoteState::size_of()];
+        VoteState::serialize(&VoteStateVersions::new_current(self), &mut data).unwrap();
+        Account {
+            lamports: est_rent_exempt_lamports(VoteState::size_of()),
+            data,
+            owner: solana_program::vote::program::ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        }
+    }
+}
@@ -0,0 +1,458 @@
+use std::num::NonZeroU32;
+
+use borsh::BorshSerialize;
+use sanctum_spl_stake_pool_lib::{
+    FindDepositAuthority, FindTransientStakeAccount, FindTransientStakeAccountArgs,
+    FindValidatorStakeAccount, FindValidatorStakeAccountArgs, FindWithdrawAuthority,
+    STAKE_POOL_SIZE, ZERO_FEE,
+};
+use solana_program::{
+    pubkey::Pubkey,
+    stake::state::{Authorized, Meta, StakeStateV2},
+};
+use solana_readonly_account::keyed::Keyed;
+use solana_sdk::account::Account;
+use spl_stake_pool_interface::{
+    AccountType, Fee, FutureEpochFee, Lockup, StakePool, StakeStatus, ValidatorList,
+    ValidatorListHeader, ValidatorStakeInfo,
+};
+
+use crate::{
+    est_rent_exempt_lamports,
+    stake::{LiveStakeAccountParams, StakeProgramTest, StakeStateAndLamports},
+    token::{
+        tokenkeg::{mock_tokenkeg_account, mock_tokenkeg_mint, TokenkegProgramTest},
+        MockMintArgs, MockTokenAccountArgs,
+    },
+    ExtendedProgramTest,
+};
+
+#[cfg(feature = "proptest")]
+pub mod proptest_utils;
+
+const STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS: u64 = est_rent_exempt_lamports(StakeStateV2::size_of());
+
+#[derive(Clone, Debug)]
+pub struct MockValidatorArgs {
+    pub vote: Pubkey,
+
+    /// Total lamports of the validator stake account, including its rent-exempt reserve.
+    /// The validator stake account is not created if this is 0
+    pub active_stake_lamports: u64,
+
+    /// Total lamports of the transient stake account, including its rent-exempt reserve.
+    /// The transient stake account is not created if this is 0
+    pub transient_stake_lamports: u64,
+
+    pub transient_seed_suffix: u64,
+    pub validator_seed_suffix: Option<NonZeroU32>,
+
+    /// - `DeactivatingValidator` and `DeactivatingAll` create a deactivating validator stake account
+    /// - `DeactivatingTransient` and `DeactivatingAll` create a deactivating transient stake account,
+    ///   it is activating otherwise
+    pub status: StakeStatus,
+}
+
+impl MockValidatorArgs {
+    /// An active validator with no transient stake
+    pub fn active(vote: Pubkey, active_stake_lamports: u64) -> Self {
+        Self {
+            vote,
+            active_stake_lamports,
+            transient_stake_lamports: 0,
+            transient_seed_suffix: 0,
+            validator_seed_suffix: None,
+            status: StakeStatus::Active,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MockSplStakePoolArgs {
+    pub program_id: Pubkey,
+    pub stake_pool: Pubkey,
+    pub validator_list: Pubkey,
+    pub reserve_stake: Pubkey,
+    pub pool_mint: Pubkey,
+    pub manager_fee_account: Pubkey,
+    pub manager: Pubkey,
+    pub staker: Pubkey,
+
+    /// Lamports in the reserve stake account excluding its rent-exempt reserve
+    pub reserve_lamports: u64,
+
+    pub pool_token_supply: u64,
+
+    /// Also the epoch the transient stake accounts were activated/deactivated in.
+    pub last_update_epoch: u64,
+
+    /// Must be >= `validators.len()`
+    pub max_validators: u32,
+
+    pub epoch_fee: Fee,
+    pub stake_deposit_fee: Fee,
+    pub stake_withdrawal_fee: Fee,
+    pub sol_deposit_fee: Fee,
+    pub sol_withdrawal_fee: Fee,
+    pub stake_referral_fee: u8,
+    pub sol_referral_fee: u8,
+
+    pub validators: Vec<MockValidatorArgs>,
+}
+
+impl MockSplStakePoolArgs {
+    /// Zero fees with unique keys for all non-PDA accounts
+    pub fn new_unique(program_id: Pubkey, validators: Vec<MockValidatorArgs>) -> Self {
+        Self {
+            program_id,
+            stake_pool: Pubkey::new_unique(),
+            validator_list: Pubkey::new_unique(),
+            reserve_stake: Pubkey::new_unique(),
+            pool_mint: Pubkey::new_unique(),
+            manager_fee_account: Pubkey::new_unique(),
+            manager: Pubkey::new_unique(),
+            staker: Pubkey::new_unique(),
+            reserve_lamports: 0,
+            pool_token_supply: 0,
+            last_update_epoch: 0,
+            max_validators: validators.len().try_into().unwrap(),
+            epoch_fee: ZERO_FEE,
+            stake_deposit_fee: ZERO_FEE,
+            stake_withdrawal_fee: ZERO_FEE,
+            sol_deposit_fee: ZERO_FEE,
+            sol_withdrawal_fee: ZERO_FEE,
+            stake_referral_fee: 0,
+            sol_referral_fee: 0,
+            validators,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SplStakePoolValidatorKeys {
+    pub vote: Pubkey,
+    pub validator_stake_account: Pubkey,
+    pub transient_stake_account: Pubkey,
+}
+
+/// Keys and deserialized state of a stake pool added with [`StakePoolProgramTest::add_spl_stake_pool`]
+#[derive(Clone, Debug)]
+pub struct SplStakePoolHandle {
+    pub program_id: Pubkey,
+    pub stake_pool: Pubkey,
+    pub validator_list: Pubkey,
+    pub reserve_stake: Pubkey,
+    pub pool_mint: Pubkey,
+    pub manager_fee_account: Pubkey,
+    pub manager: Pubkey,
+    pub staker: Pubkey,
+    pub deposit_authority: Pubkey,
+    pub withdraw_authority: Pubkey,
+    pub validators: Vec<SplStakePoolValidatorKeys>,
+    pub stake_pool_state: StakePool,
+    pub validator_list_state: ValidatorList,
+}
+
+impl SplStakePoolHandle {
+    pub fn keyed_stake_pool(&self) -> Keyed<&StakePool> {
+        Keyed {
+            pubkey: self.stake_pool,
+            account: &self.stake_pool_state,
+        }
+    }
+}
+
+pub trait StakePoolProgramTest: Sized {
+    /// Adds a consistent SPL stake pool:
+    /// the stake pool, validator list, reserve, validator and transient stake accounts,
+    /// pool mint and manager fee account.
+    ///
+    /// Vote accounts are not added.
+    ///
+    /// ## Panics
+    ///
+    /// If a validator's nonzero active or transient stake lamports is less than
+    /// the rent-exempt reserve of a stake account
+    fn add_spl_stake_pool(self, args: MockSplStakePoolArgs) -> (Self, SplStakePoolHandle);
+}
+
+impl<T: ExtendedProgramTest> StakePoolProgramTest for T {
+    fn add_spl_stake_pool(self, args: MockSplStakePoolArgs) -> (Self, SplStakePoolHandle) {
+        let handle = mock_spl_stake_pool(&args);
+        let withdraw_auth = Authorized {
+            staker: handle.withdraw_authority,
+            withdrawer: handle.withdraw_authority,
+        };
+
+        let mut pt = self
+            .add_keyed_account(Keyed {
+                pubkey: handle.stake_pool,
+                account: borsh_account(&handle.stake_pool_state, STAKE_POOL_SIZE, args.program_id),
+            })
+            .add_keyed_account(Keyed {
+                pubkey: handle.validator_list,
+                account: borsh_account(
+                    &handle.validator_list_state,
+                    validator_list_size(args.max_validators),
+                    args.program_id,
+                ),
+            })
+            .add_stake_account(
+                handle.reserve_stake,
+                StakeStateAndLamports {
+                    stake_state: StakeStateV2::Initialized(Meta {
+                        rent_exempt_reserve: STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS,
+                        authorized: withdraw_auth,
+                        lockup: Default::default(),
+                    }),
+                    total_lamports: STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS + args.reserve_lamports,
+                },
+            )
+            .add_tokenkeg_mint_account(
+                handle.pool_mint,
+                mock_tokenkeg_mint(MockMintArgs {
+                    mint_authority: Some(handle.withdraw_authority),
+                    freeze_authority: None,
+                    supply: args.pool_token_supply,
+                    decimals: 9,
+                }),
+            )
+            .add_tokenkeg_account(
+                handle.manager_fee_account,
+                mock_tokenkeg_account(MockTokenAccountArgs {
+                    mint: handle.pool_mint,
+                    authority: handle.manager,
+                    amount: 0,
+                }),
+            );
+
+        for (v, keys) in args.validators.iter().zip(handle.validators.iter()) {
+            let (is_validator_deactivating, is_transient_deactivating) = match v.status {
+                StakeStatus::Active | StakeStatus::ReadyForRemoval => (false, false),
+                StakeStatus::DeactivatingTransient => (false, true),
+                StakeStatus::DeactivatingValidator => (true, false),
+                StakeStatus::DeactivatingAll => (true, true),
+            };
+            if v.active_stake_lamports > 0 {
+                pt = pt.add_live_stake_account(
+                    keys.validator_stake_account,
+                    LiveStakeAccountParams {
+                        staked_lamports: staked_lamports(
+                            v.active_stake_lamports,
+                            &v.vote,
+                            "active",
+                        ),
+                        voter: v.vote,
+                        authorized: withdraw_auth,
+                        // bootstrap stake, fully active regardless of the current epoch
+                        activation_epoch: u64::MAX,
+                        deactivation_epoch: if is_validator_deactivating {
+                            args.last_update_epoch
+                        } else {
+                            u64::MAX
+                        },
+                        lockup: Default::default(),
+                        credits_observed: 0,
+                    },
+                );
+            }
+            if v.transient_stake_lamports > 0 {
+                let (activation_epoch, deactivation_epoch) = if is_transient_deactivating {
+                    (u64::MAX, args.last_update_epoch)
+                } else {
+                    (args.last_update_epoch, u64::MAX)
+                };
+                pt = pt.add_live_stake_account(
+                    keys.transient_stake_account,
+                    LiveStakeAccountParams {
+                        staked_lamports: staked_lamports(
+                            v.transient_stake_lamports,
+                            &v.vote,
+                            "transient",
+                        ),
+                        voter: v.vote,
+                        authorized: withdraw_auth,
+                        activation_epoch,
+                        deactivation_epoch,
+                        lockup: Default::default(),
+                        credits_observed: 0,
+                    },
+                );
+            }
+        }
+
+        (pt, handle)
+    }
+}
+
+/// `total_lamports` of a validator's stake account excluding its rent-exempt reserve
+fn staked_lamports(total_lamports: u64, vote: &Pubkey, kind: &str) -> u64 {
+    total_lamports
+        .checked_sub(STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS)
+        .unwrap_or_else(|| {
+            panic!(
+                "validator {vote} {kind}_stake_lamports {total_lamports} < minimum {STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS}"
+            )
+        })
+}
+
+/// Derives all PDAs and computes the [`StakePool`] and [`ValidatorList`] for `args`,
+/// with `total_lamports` = reserve lamports + all active and transient stake lamports
+pub fn mock_spl_stake_pool(
+    MockSplStakePoolArgs {
+        program_id,
+        stake_pool,
+        validator_list,
+        reserve_stake,
+        pool_mint,
+        manager_fee_account,
+        manager,
+        staker,
+        reserve_lamports,
+        pool_token_supply,
+        last_update_epoch,
+        max_validators,
+        epoch_fee,
+        stake_deposit_fee,
+        stake_withdrawal_fee,
+        sol_deposit_fee,
+        sol_withdrawal_fee,
+        stake_referral_fee,
+        sol_referral_fee,
+        validators,
+    }: &MockSplStakePoolArgs,
+) -> SplStakePoolHandle {
+    let (deposit_authority, _bump) =
+        FindDepositAuthority { pool: *stake_pool }.run_for_prog(program_id);
+    let (withdraw_authority, stake_withdraw_bump_seed) =
+        FindWithdrawAuthority { pool: *stake_pool }.run_for_prog(program_id);
+
+    let validator_keys: Vec<SplStakePoolValidatorKeys> = validators
+        .iter()
+        .map(
+            |MockValidatorArgs {
+                 vote,
+                 transient_seed_suffix,
+                 validator_seed_suffix,
+                 ..
+             }| {
+                let (validator_stake_account, _bump) =
+                    FindValidatorStakeAccount::new(FindValidatorStakeAccountArgs {
+                        pool: *stake_pool,
+                        vote: *vote,
+                        seed: *validator_seed_suffix,
+                    })
+                    .run_for_prog(program_id);
+                let (transient_stake_account, _bump) =
+                    FindTransientStakeAccount::new(FindTransientStakeAccountArgs {
+                        pool: *stake_pool,
+                        vote: *vote,
+                        seed: *transient_seed_suffix,
+                    })
+                    .run_for_prog(program_id);
+                SplStakePoolValidatorKeys {
+                    vote: *vote,
+                    validator_stake_account,
+                    transient_stake_account,
+                }
+            },
+        )
+        .collect();
+
+    let validator_list_state = ValidatorList {
+        header: ValidatorListHeader {
+            account_type: AccountType::ValidatorList,
+            max_validators: *max_validators,
+        },
+        validators: validators
+            .iter()
+            .map(|v| ValidatorStakeInfo {
+                active_stake_lamports: v.active_stake_lamports,
+                transient_stake_lamports: v.transient_stake_lamports,
+                last_update_epoch: *last_update_epoch,
+                transient_seed_suffix: v.transient_seed_suffix,
+                unused: 0,
+                validator_seed_suffix: v.validator_seed_suffix.map_or(0, NonZeroU32::get),
+                status: v.status.clone(),
+                vote_account_address: v.vote,
+            })
+            .collect(),
+    };
+
+    let total_lamports = validators.iter().fold(*reserve_lamports, |sum, v| {
+        sum + v.active_stake_lamports + v.transient_stake_lamports
+    });
+
+    let stake_pool_state = StakePool {
+        account_type: AccountType::StakePool,
+        manager: *manager,
+        staker: *staker,
+        stake_deposit_authority: deposit_authority,
+        stake_withdraw_bump_seed,
+        validator_list: *validator_list,
+        reserve_stake: *reserve_stake,
+        pool_mint: *pool_mint,
+        manager_fee_account: *manager_fee_account,
+        token_program: spl_token::ID,
+        total_lamports,
+        pool_token_supply: *pool_token_supply,
+        last_update_epoch: *last_update_epoch,
+        lockup: Lockup {
+            unix_timestamp: 0,
+            epoch: 0,
+            custodian: Pubkey::default(),
+        },
+        epoch_fee: epoch_fee.clone(),
+        next_epoch_fee: FutureEpochFee::None,
+        preferred_deposit_validator_vote_address: None,
+        preferred_withdraw_validator_vote_address: None,
+        stake_deposit_fee: stake_deposit_fee.clone(),
+        stake_withdrawal_fee: stake_withdrawal_fee.clone(),
+        next_stake_withdrawal_fee: FutureEpochFee::None,
+        stake_referral_fee: *stake_referral_fee,
+        sol_deposit_authority: None,
+        sol_deposit_fee: sol_deposit_fee.clone(),
+        sol_referral_fee: *sol_referral_fee,
+        sol_withdraw_authority: None,
+        sol_withdrawal_fee: sol_withdrawal_fee.clone(),
+        next_sol_withdrawal_fee: FutureEpochFee::None,
+        last_epoch_pool_token_supply: *pool_token_supply,
+        last_epoch_total_lamports: total_lamports,
+    };
+
+    SplStakePoolHandle {
+        program_id: *program_id,
+        stake_pool: *stake_pool,
+        validator_list: *validator_list,
+        reserve_stake: *reserve_stake,
+        pool_mint: *pool_mint,
+        manager_fee_account: *manager_fee_account,
+        manager: *manager,
+        staker: *staker,
+        deposit_authority,
+        withdraw_authority,
+        validators: validator_keys,
+        stake_pool_state,
+        validator_list_state,
+    }
+}
+
+/// header (account_type + max_validators) + vec len + max_validators * `ValidatorStakeInfo`
+pub fn validator_list_size(max_validators: u32) -> usize {
+    const VALIDATOR_LIST_HEADER_SIZE: usize = 1 + 4;
+    const VEC_LEN_SIZE: usize = 4;
+    const VALIDATOR_STAKE_INFO_SIZE: usize = 8 + 8 + 8 + 8 + 4 + 4 + 1 + 32;
+    VALIDATOR_LIST_HEADER_SIZE + VEC_LEN_SIZE + max_validators as usize * VALIDATOR_STAKE_INFO_SIZE
+}
+
+fn borsh_account<T: BorshSerialize>(state: &T, len: usize, owner: Pubkey) -> Account {
+    let mut data = vec![0u8; len];
+    state.serialize(&mut data.as_mut_slice()).unwrap();
+    Account {
+        lamports: est_rent_exempt_lamports(len),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: u64::MAX,
+    }
+}
@@ -0,0 +1,206 @@
+use proptest::{array, collection, option, prelude::*, strategy::Union};
+use spl_stake_pool_interface::{
+    AccountType, Fee, FutureEpochFee, Lockup, StakePool, StakeStatus, ValidatorList,
+    ValidatorListHeader, ValidatorStakeInfo,
+};
+
+use crate::proptest_utils::pubkey;
+
+prop_compose! {
+    /// Arbitrary, possibly invalid, fee
+    pub fn fee()
+        (denominator: u64, numerator: u64) -> Fee {
+            Fee { denominator, numerator }
+        }
+}
+
+prop_compose! {
+    /// numerator <= denominator. 0/0 is a valid zero fee
+    pub fn valid_fee()
+        (denominator in any::<u64>())
+        (numerator in 0..=denominator, denominator in Just(denominator)) -> Fee {
+            Fee { denominator, numerator }
+        }
+}
+
+fn future_epoch_fee_from<S: Strategy<Value = Fee> + 'static>(
+    fee: impl Fn() -> S,
+) -> impl Strategy<Value = FutureEpochFee> {
+    Union::new([
+        Just(FutureEpochFee::None).boxed(),
+        fee().prop_map(|fee| FutureEpochFee::One { fee }).boxed(),
+        fee().prop_map(|fee| FutureEpochFee::Two { fee }).boxed(),
+    ])
+}
+
+/// Arbitrary, possibly invalid, future epoch fee
+pub fn future_epoch_fee() -> impl Strategy<Value = FutureEpochFee> {
+    future_epoch_fee_from(fee)
+}
+
+pub fn valid_future_epoch_fee() -> impl Strategy<Value = FutureEpochFee> {
+    future_epoch_fee_from(valid_fee)
+}
+
+pub fn stake_status() -> impl Strategy<Value = StakeStatus> {
+    Union::new([
+        Just(StakeStatus::Active),
+        Just(StakeStatus::DeactivatingTransient),
+        Just(StakeStatus::ReadyForRemoval),
+        Just(StakeStatus::DeactivatingValidator),
+        Just(StakeStatus::DeactivatingAll),
+    ])
+}
+
+prop_compose! {
+    pub fn lockup()
+        (unix_timestamp: i64, epoch: u64, custodian in pubkey()) -> Lockup {
+            Lockup { unix_timestamp, epoch, custodian }
+        }
+}
+
+/// Max lamports of each of a pool's reserve and its validators' active and transient stake
+/// such that the pool's total lamports does not overflow
+pub const fn max_lamports_per_stake_account(max_validators: u32) -> u64 {
+    u64::MAX / (2 * max_validators as u64 + 1)
+}
+
+prop_compose! {
+    pub fn validator_stake_info(max_lamports: u64, last_update_epoch: u64)
+        (
+            active_stake_lamports in 0..=max_lamports,
+            transient_stake_lamports in 0..=max_lamports,
+            transient_seed_suffix: u64,
+            validator_seed_suffix: u32,
+            status in stake_status(),
+            vote_account_address in pubkey(),
+        ) -> ValidatorStakeInfo {
+            ValidatorStakeInfo {
+                active_stake_lamports,
+                transient_stake_lamports,
+                last_update_epoch,
+                transient_seed_suffix,
+                unused: 0,
+                validator_seed_suffix,
+                status,
+                vote_account_address,
+            }
+        }
+}
+
+prop_compose! {
+    /// Up to `max_validators` validators, each updated at `last_update_epoch`,
+    /// with lamports bounded by [`max_lamports_per_stake_account`]
+    pub fn validator_list(max_validators: u32, last_update_epoch: u64)
+        (
+            validators in collection::vec(
+                validator_stake_info(max_lamports_per_stake_account(max_validators), last_update_epoch),
+                0..=max_validators as usize,
+            ),
+        ) -> ValidatorList {
+            ValidatorList {
+                header: ValidatorListHeader {
+                    account_type: AccountType::ValidatorList,
+                    max_validators,
+                },
+                validators,
+            }
+        }
+}
+
+/// Sum of active and transient stake lamports of all validators in the list
+pub fn validator_list_total_lamports(ValidatorList { validators, .. }: &ValidatorList) -> u64 {
+    validators
+        .iter()
+        .map(|v| v.active_stake_lamports + v.transient_stake_lamports)
+        .sum()
+}
+
+/// A consistent, up-to-date stake pool and its validator list:
+/// - `total_lamports` = reserve lamports + sum of validators' lamports
+/// - 1 <= `pool_token_supply` <= `total_lamports`, or both 0
+/// - all fees are valid and referral fees are <= 100%
+pub fn stake_pool_with_validator_list(
+    max_validators: u32,
+) -> impl Strategy<Value = (StakePool, ValidatorList)> {
+    any::<u64>().prop_flat_map(move |last_update_epoch| {
+        (
+            validator_list(max_validators, last_update_epoch),
+            0..=max_lamports_per_stake_account(max_validators),
+            any::<u64>(),
+            array::uniform7(pubkey()),
+            array::uniform5(valid_fee()),
+            array::uniform3(valid_future_epoch_fee()),
+            array::uniform4(option::of(pubkey())),
+            array::uniform2(0..=100u8),
+            any::<u8>(),
+            lockup(),
+        )
+            .prop_map(
+                move |(
+                    validator_list,
+                    reserve_lamports,
+                    supply_frac,
+                    [manager, staker, stake_deposit_authority, validator_list_addr, reserve_stake, pool_mint, manager_fee_account],
+                    [epoch_fee, stake_deposit_fee, stake_withdrawal_fee, sol_deposit_fee, sol_withdrawal_fee],
+                    [next_epoch_fee, next_stake_withdrawal_fee, next_sol_withdrawal_fee],
+                    [preferred_deposit_validator_vote_address, preferred_withdraw_validator_vote_address, sol_deposit_authority, sol_withdraw_authority],
+                    [stake_referral_fee, sol_referral_fee],
+                    stake_withdraw_bump_seed,
+                    lockup,
+                )| {
+                    let total_lamports =
+                        reserve_lamports + validator_list_total_lamports(&validator_list);
+                    // scale supply_frac from [0, u64::MAX] to [1, total_lamports]
+                    let pool_token_supply = match total_lamports {
+                        0 => 0,
+                        t => (u128::from(t) * u128::from(supply_frac) / u128::from(u64::MAX))
+                            .max(1)
+                            .try_into()
+                            .unwrap(),
+                    };
+                    let stake_pool = StakePool {
+                        account_type: AccountType::StakePool,
+                        manager,
+                        staker,
+                        stake_deposit_authority,
+                        stake_withdraw_bump_seed,
+                        validator_list: validator_list_addr,
+                        reserve_stake,
+                        pool_mint,
+                        manager_fee_account,
+                        token_program: spl_token::ID,
+                        total_lamports,
+                        pool_token_supply,
+                        last_update_epoch,
+                        lockup,
+                        epoch_fee,
+                        next_epoch_fee,
+                        preferred_deposit_validator_vote_address,
+                        preferred_withdraw_validator_vote_address,
+                        stake_deposit_fee,
+                        stake_withdrawal_fee,
+                        next_stake_withdrawal_fee,
+                        stake_referral_fee,
+                        sol_deposit_authority,
+                        sol_deposit_fee,
+                        sol_referral_fee,
+                        sol_withdraw_authority,
+                        sol_withdrawal_fee,
+                        next_sol_withdrawal_fee,
+                        last_epoch_pool_token_supply: pool_token_supply,
+                        last_epoch_total_lamports: total_lamports,
+                    };
+                    (stake_pool, validator_list)
+                },
+            )
+    })
+}
+
+/// Max validators of [`stake_pool`]
+pub const DEFAULT_PROPTEST_MAX_VALIDATORS: u32 = 16;
+
+/// [`stake_pool_with_validator_list`] with [`DEFAULT_PROPTEST_MAX_VALIDATORS`], discarding the validator list
+pub fn stake_pool() -> impl Strategy<Value = StakePool> {
+    stake_pool_with_validator_list(DEFAULT_PROPTEST_MAX_VALIDATORS).prop_map(|(pool, _list)| pool)
+}
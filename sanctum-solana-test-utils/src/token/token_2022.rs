@@ -0,0 +1,449 @@
+use solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey};
+use solana_readonly_account::keyed::Keyed;
+use solana_sdk::account::Account;
+use spl_pod::{optional_keys::OptionalNonZeroPubkey, primitives::PodU64};
+use spl_token_2022::{
+    extension::{
+        default_account_state::DefaultAccountState,
+        immutable_owner::ImmutableOwner,
+        interest_bearing_mint::InterestBearingConfig,
+        metadata_pointer::MetadataPointer,
+        mint_close_authority::MintCloseAuthority,
+        non_transferable::{NonTransferable, NonTransferableAccount},
+        permanent_delegate::PermanentDelegate,
+        transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensions, BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+    },
+    state::{AccountState, Mint},
+};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+use crate::{est_rent_exempt_lamports, ExtendedProgramTest, IntoAccount};
+
+use super::{MockMintArgs, MockTokenAccountArgs};
+
+pub trait Token2022ProgramTest {
+    fn add_token22_account(self, addr: Pubkey, account: spl_token_2022::state::Account) -> Self;
+    fn add_token22_account_from_args(self, addr: Pubkey, args: MockToken22AccountArgs) -> Self;
+    fn add_token22_mint_account(self, addr: Pubkey, mint: Mint) -> Self;
+    fn add_token22_mint_from_args(self, addr: Pubkey, args: MockToken22MintArgs) -> Self;
+}
+
+impl<T: ExtendedProgramTest> Token2022ProgramTest for T {
+    fn add_token22_account(self, addr: Pubkey, account: spl_token_2022::state::Account) -> Self {
+        self.add_keyed_account(Keyed {
+            pubkey: addr,
+            account: account.into_account(),
+        })
+    }
+
+    fn add_token22_account_from_args(self, addr: Pubkey, args: MockToken22AccountArgs) -> Self {
+        self.add_keyed_account(Keyed {
+            pubkey: addr,
+            account: mock_token22_account(args),
+        })
+    }
+
+    fn add_token22_mint_account(self, addr: Pubkey, mint: Mint) -> Self {
+        self.add_keyed_account(Keyed {
+            pubkey: addr,
+            account: mint.into_account(),
+        })
+    }
+
+    fn add_token22_mint_from_args(self, addr: Pubkey, args: MockToken22MintArgs) -> Self {
+        self.add_keyed_account(Keyed {
+            pubkey: addr,
+            account: mock_token22_mint(addr, args),
+        })
+    }
+}
+
+pub const TOKEN22_ACC_NO_EXTENSIONS_RENT_EXEMPT_LAMPORTS: u64 =
+    est_rent_exempt_lamports(spl_token_2022::state::Account::LEN);
+
+#[derive(Clone, Copy, Debug)]
+pub struct MockToken22AccountArgs {
+    pub account: MockTokenAccountArgs,
+    pub state: AccountState,
+    pub extensions: MockToken22AccountExtensions,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MockToken22AccountExtensions {
+    pub immutable_owner: bool,
+
+    /// Required if the mint is non-transferable
+    pub non_transferable: bool,
+
+    /// Withheld transfer fees.
+    /// Required (`Some(0)`) if the mint has a transfer fee config
+    pub transfer_fee_withheld_amount: Option<u64>,
+}
+
+impl MockToken22AccountExtensions {
+    pub fn extension_types(&self) -> Vec<ExtensionType> {
+        let Self {
+            immutable_owner,
+            non_transferable,
+            transfer_fee_withheld_amount,
+        } = self;
+        [
+            (*immutable_owner, ExtensionType::ImmutableOwner),
+            (*non_transferable, ExtensionType::NonTransferableAccount),
+            (
+                transfer_fee_withheld_amount.is_some(),
+                ExtensionType::TransferFeeAmount,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(is_present, ty)| is_present.then_some(ty))
+        .collect()
+    }
+}
+
+/// Creates a token-2022 token account with the given extensions,
+/// with rent-exempt lamports computed for the extended size
+pub fn mock_token22_account(
+    MockToken22AccountArgs {
+        account:
+            MockTokenAccountArgs {
+                mint,
+                authority,
+                amount,
+            },
+        state,
+        extensions,
+    }: MockToken22AccountArgs,
+) -> Account {
+    let MockToken22AccountExtensions {
+        immutable_owner,
+        non_transferable,
+        transfer_fee_withheld_amount,
+    } = extensions;
+    let len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(
+        &extensions.extension_types(),
+    )
+    .unwrap();
+    let rent_exempt_lamports = est_rent_exempt_lamports(len);
+    let is_native = mint == spl_token_2022::native_mint::ID;
+
+    let mut data = vec![0u8; len];
+    let mut s =
+        StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack_uninitialized(&mut data)
+            .unwrap();
+    if immutable_owner {
+        s.init_extension::<ImmutableOwner>(false).unwrap();
+    }
+    if non_transferable {
+        s.init_extension::<NonTransferableAccount>(false).unwrap();
+    }
+    if let Some(withheld_amount) = transfer_fee_withheld_amount {
+        s.init_extension::<TransferFeeAmount>(false)
+            .unwrap()
+            .withheld_amount = withheld_amount.into();
+    }
+    s.base = spl_token_2022::state::Account {
+        mint,
+        owner: authority,
+        amount,
+        delegate: COption::None,
+        state,
+        is_native: if is_native {
+            COption::Some(rent_exempt_lamports)
+        } else {
+            COption::None
+        },
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+    s.pack_base();
+    s.init_account_type().unwrap();
+
+    Account {
+        lamports: if is_native {
+            rent_exempt_lamports + amount
+        } else {
+            rent_exempt_lamports
+        },
+        data,
+        owner: spl_token_2022::ID,
+        executable: false,
+        rent_epoch: u64::MAX,
+    }
+}
+
+/// Converts a token-2022 token account with no extensions
+impl IntoAccount for spl_token_2022::state::Account {
+    fn into_account(self) -> Account {
+        let mut data = vec![0u8; spl_token_2022::state::Account::LEN];
+        let mut lamports = TOKEN22_ACC_NO_EXTENSIONS_RENT_EXEMPT_LAMPORTS;
+        if self.is_native.is_some() {
+            lamports += self.amount;
+        }
+        spl_token_2022::state::Account::pack(self, &mut data).unwrap();
+        Account {
+            lamports,
+            data,
+            owner: spl_token_2022::ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MockToken22MintArgs {
+    pub mint: MockMintArgs,
+    pub extensions: MockToken22MintExtensions,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MockToken22MintExtensions {
+    pub transfer_fee_config: Option<MockTransferFeeConfigArgs>,
+    pub interest_bearing_config: Option<MockInterestBearingConfigArgs>,
+    pub close_authority: Option<Pubkey>,
+    pub permanent_delegate: Option<Pubkey>,
+    pub metadata_pointer: Option<MockMetadataPointerArgs>,
+
+    /// Token metadata stored on the mint itself.
+    /// `metadata_pointer.metadata_address` should point to the mint for this to be discoverable
+    pub token_metadata: Option<MockTokenMetadataArgs>,
+
+    pub non_transferable: bool,
+    pub default_account_state: Option<AccountState>,
+}
+
+impl MockToken22MintExtensions {
+    /// Fixed-length extension types only, excludes token metadata
+    pub fn fixed_len_extension_types(&self) -> Vec<ExtensionType> {
+        let Self {
+            transfer_fee_config,
+            interest_bearing_config,
+            close_authority,
+            permanent_delegate,
+            metadata_pointer,
+            token_metadata: _,
+            non_transferable,
+            default_account_state,
+        } = self;
+        [
+            (
+                transfer_fee_config.is_some(),
+                ExtensionType::TransferFeeConfig,
+            ),
+            (
+                interest_bearing_config.is_some(),
+                ExtensionType::InterestBearingConfig,
+            ),
+            (close_authority.is_some(), ExtensionType::MintCloseAuthority),
+            (
+                permanent_delegate.is_some(),
+                ExtensionType::PermanentDelegate,
+            ),
+            (metadata_pointer.is_some(), ExtensionType::MetadataPointer),
+            (*non_transferable, ExtensionType::NonTransferable),
+            (
+                default_account_state.is_some(),
+                ExtensionType::DefaultAccountState,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(is_present, ty)| is_present.then_some(ty))
+        .collect()
+    }
+}
+
+/// The same fee is used for both the older and newer transfer fee
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MockTransferFeeConfigArgs {
+    pub transfer_fee_config_authority: Option<Pubkey>,
+    pub withdraw_withheld_authority: Option<Pubkey>,
+    pub withheld_amount: u64,
+    pub transfer_fee_basis_points: u16,
+    pub maximum_fee: u64,
+}
+
+/// The rate has been `current_rate` since `initialization_timestamp`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MockInterestBearingConfigArgs {
+    pub rate_authority: Option<Pubkey>,
+    pub initialization_timestamp: i64,
+    pub current_rate: i16,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MockMetadataPointerArgs {
+    pub authority: Option<Pubkey>,
+    pub metadata_address: Option<Pubkey>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MockTokenMetadataArgs {
+    pub update_authority: Option<Pubkey>,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub additional_metadata: Vec<(String, String)>,
+}
+
+fn optional_nonzero_pubkey(pk: Option<Pubkey>) -> OptionalNonZeroPubkey {
+    OptionalNonZeroPubkey::try_from(pk).unwrap()
+}
+
+/// Creates a token-2022 mint account at `addr` with the given extensions,
+/// with rent-exempt lamports computed for the extended size.
+///
+/// `addr` is required to set the mint of the token metadata extension, if any.
+pub fn mock_token22_mint(
+    addr: Pubkey,
+    MockToken22MintArgs {
+        mint:
+            MockMintArgs {
+                mint_authority,
+                freeze_authority,
+                supply,
+                decimals,
+            },
+        extensions,
+    }: MockToken22MintArgs,
+) -> Account {
+    let len =
+        ExtensionType::try_calculate_account_len::<Mint>(&extensions.fixed_len_extension_types())
+            .unwrap();
+    let mut data = vec![0u8; len];
+    let MockToken22MintExtensions {
+        transfer_fee_config,
+        interest_bearing_config,
+        close_authority,
+        permanent_delegate,
+        metadata_pointer,
+        token_metadata,
+        non_transferable,
+        default_account_state,
+    } = extensions;
+
+    let mut s = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    if let Some(MockTransferFeeConfigArgs {
+        transfer_fee_config_authority,
+        withdraw_withheld_authority,
+        withheld_amount,
+        transfer_fee_basis_points,
+        maximum_fee,
+    }) = transfer_fee_config
+    {
+        let fee = TransferFee {
+            epoch: 0.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: transfer_fee_basis_points.into(),
+        };
+        *s.init_extension::<TransferFeeConfig>(false).unwrap() = TransferFeeConfig {
+            transfer_fee_config_authority: optional_nonzero_pubkey(transfer_fee_config_authority),
+            withdraw_withheld_authority: optional_nonzero_pubkey(withdraw_withheld_authority),
+            withheld_amount: PodU64::from(withheld_amount),
+            older_transfer_fee: fee,
+            newer_transfer_fee: fee,
+        };
+    }
+    if let Some(MockInterestBearingConfigArgs {
+        rate_authority,
+        initialization_timestamp,
+        current_rate,
+    }) = interest_bearing_config
+    {
+        *s.init_extension::<InterestBearingConfig>(false).unwrap() = InterestBearingConfig {
+            rate_authority: optional_nonzero_pubkey(rate_authority),
+            initialization_timestamp: initialization_timestamp.into(),
+            pre_update_average_rate: current_rate.into(),
+            last_update_timestamp: initialization_timestamp.into(),
+            current_rate: current_rate.into(),
+        };
+    }
+    if let Some(close_authority) = close_authority {
+        s.init_extension::<MintCloseAuthority>(false)
+            .unwrap()
+            .close_authority = optional_nonzero_pubkey(Some(close_authority));
+    }
+    if let Some(delegate) = permanent_delegate {
+        s.init_extension::<PermanentDelegate>(false)
+            .unwrap()
+            .delegate = optional_nonzero_pubkey(Some(delegate));
+    }
+    if let Some(MockMetadataPointerArgs {
+        authority,
+        metadata_address,
+    }) = metadata_pointer
+    {
+        *s.init_extension::<MetadataPointer>(false).unwrap() = MetadataPointer {
+            authority: optional_nonzero_pubkey(authority),
+            metadata_address: optional_nonzero_pubkey(metadata_address),
+        };
+    }
+    if non_transferable {
+        s.init_extension::<NonTransferable>(false).unwrap();
+    }
+    if let Some(state) = default_account_state {
+        s.init_extension::<DefaultAccountState>(false)
+            .unwrap()
+            .state = state.into();
+    }
+
+    if let Some(MockTokenMetadataArgs {
+        update_authority,
+        name,
+        symbol,
+        uri,
+        additional_metadata,
+    }) = token_metadata
+    {
+        let token_metadata = TokenMetadata {
+            update_authority: optional_nonzero_pubkey(update_authority),
+            mint: addr,
+            name,
+            symbol,
+            uri,
+            additional_metadata,
+        };
+        // variable-length extension: grow the account the same way the program does
+        let new_len = s
+            .try_get_new_account_len_for_variable_len_extension(&token_metadata)
+            .unwrap();
+        data.resize(new_len, 0);
+        s = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        s.init_variable_len_extension(&token_metadata, false)
+            .unwrap();
+    }
+
+    s.base = Mint {
+        mint_authority: COption::from(mint_authority),
+        supply,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::from(freeze_authority),
+    };
+    s.pack_base();
+    s.init_account_type().unwrap();
+
+    Account {
+        lamports: est_rent_exempt_lamports(data.len()),
+        data,
+        owner: spl_token_2022::ID,
+        executable: false,
+        rent_epoch: u64::MAX,
+    }
+}
+
+/// Converts a token-2022 mint with no extensions
+impl IntoAccount for Mint {
+    fn into_account(self) -> Account {
+        let mut data = vec![0u8; Mint::LEN];
+        Mint::pack(self, &mut data).unwrap();
+        Account {
+            lamports: est_rent_exempt_lamports(Mint::LEN),
+            data,
+            owner: spl_token_2022::ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        }
+    }
+}
@@ -11,6 +11,7 @@ use solana_sdk::{
     message::{v0::Message, VersionedMessage},
     pubkey::Pubkey,
     signature::Signature,
+    signer::Signer,
     transaction::{TransactionError, VersionedTransaction},
 };
 
@@ -23,6 +24,25 @@ pub fn zero_padded_return_data<const N: usize>(return_data: &[u8]) -> [u8; N] {
     res
 }
 
+/// Signs a partially signed tx with `signers`, replacing
+/// the signatures of the corresponding required signers.
+///
+/// ## Panics
+/// If any of `signers` is not a required signer of the tx
+pub fn cosign_versioned_tx(tx: &mut VersionedTransaction, signers: &[&dyn Signer]) {
+    let n_signers: usize = tx.message.header().num_required_signatures.into();
+    let msg_data = tx.message.serialize();
+    tx.signatures.resize(n_signers, Signature::default());
+    for signer in signers {
+        let pk = signer.pubkey();
+        let i = tx.message.static_account_keys()[..n_signers]
+            .iter()
+            .position(|k| *k == pk)
+            .unwrap_or_else(|| panic!("{pk} is not a required signer"));
+        tx.signatures[i] = signer.sign_message(&msg_data);
+    }
+}
+
 pub fn extract_ix_err(banks_client_err: BanksClientError) -> InstructionError {
     let tx_err = extract_tx_err(banks_client_err);
     match tx_err {
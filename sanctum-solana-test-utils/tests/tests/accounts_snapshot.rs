@@ -0,0 +1,90 @@
+use sanctum_solana_test_utils::ExtendedBanksClient;
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::{signer::Signer, transaction::Transaction};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn diff_system_transfer() {
+    let [recipient, untouched] = [(); 2].map(|_| Pubkey::new_unique());
+    let (mut bc, payer, rbh) = ProgramTest::default().start().await;
+
+    let before = bc
+        .snapshot_accounts(&[payer.pubkey(), recipient, untouched])
+        .await;
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &recipient,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    bc.process_transaction(tx).await.unwrap();
+    let diff = bc.diff_since_snapshot(&before).await;
+
+    diff.assert_only_changed(&[payer.pubkey(), recipient]);
+    diff.assert_unchanged(&untouched);
+    diff.assert_lamports_delta(&recipient, LAMPORTS_PER_SOL.into());
+    let payer_diff = diff.account(&payer.pubkey());
+    assert!(payer_diff.lamports.delta() < -i128::from(LAMPORTS_PER_SOL));
+    assert!(!payer_diff.owner.is_changed());
+    assert!(payer_diff.changed_byte_ranges.is_empty());
+
+    let recipient_diff = diff.account(&recipient);
+    assert!(recipient_diff.before.is_none());
+    assert_eq!(
+        recipient_diff.owner.after,
+        Some(solana_program::system_program::ID)
+    );
+    assert_eq!(recipient_diff.token_amount.after, None);
+}
+
+#[cfg(feature = "token")]
+#[tokio::test(flavor = "multi_thread")]
+async fn diff_tokenkeg_transfer() {
+    use sanctum_solana_test_utils::token::{
+        tokenkeg::TokenkegProgramTest, MockMintArgs, MockTokenAccountArgs,
+    };
+    use solana_sdk::signature::Keypair;
+
+    const AMOUNT: u64 = 1_000;
+
+    let owner = Keypair::new();
+    let [mint, src, dst] = [(); 3].map(|_| Pubkey::new_unique());
+    let account_args = |amount| MockTokenAccountArgs {
+        mint,
+        authority: owner.pubkey(),
+        amount,
+    };
+    let pt = ProgramTest::default()
+        .add_tokenkeg_mint_from_args(
+            mint,
+            MockMintArgs {
+                mint_authority: None,
+                freeze_authority: None,
+                supply: AMOUNT,
+                decimals: 0,
+            },
+        )
+        .add_tokenkeg_account_from_args(src, account_args(AMOUNT))
+        .add_tokenkeg_account_from_args(dst, account_args(0));
+    let (mut bc, payer, rbh) = pt.start().await;
+
+    let before = bc.snapshot_accounts(&[mint, src, dst]).await;
+    let ix =
+        spl_token::instruction::transfer(&spl_token::ID, &src, &dst, &owner.pubkey(), &[], AMOUNT)
+            .unwrap();
+    let tx =
+        Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[&payer, &owner], rbh);
+    bc.process_transaction(tx).await.unwrap();
+    let diff = bc.diff_since_snapshot(&before).await;
+
+    diff.assert_only_changed(&[src, dst]);
+    diff.assert_token_balance_delta(&src, -i128::from(AMOUNT));
+    diff.assert_token_balance_delta(&dst, AMOUNT.into());
+    diff.assert_lamports_delta(&dst, 0);
+    // amount field at offset 64
+    assert_eq!(diff.account(&dst).changed_byte_ranges, vec![64..66]);
+}
@@ -1,12 +1,87 @@
-use sanctum_solana_test_utils::banks_rpc_server::BanksRpcServer;
+use sanctum_solana_test_utils::banks_rpc_server::RpcProgramTest;
+#[cfg(all(feature = "token", feature = "token-2022"))]
+use sanctum_solana_test_utils::{
+    token::{
+        token_2022::{
+            mock_token22_account, mock_token22_mint, MockToken22AccountArgs, MockToken22MintArgs,
+        },
+        tokenkeg::{mock_tokenkeg_account, mock_tokenkeg_mint},
+        MockMintArgs, MockTokenAccountArgs,
+    },
+    IntoAccount,
+};
 use solana_client::rpc_client::RpcClient;
-use solana_program::hash::Hash;
-use solana_program_test::ProgramTest;
+use solana_program::{hash::Hash, pubkey::Pubkey};
+#[cfg(all(feature = "token", feature = "token-2022"))]
+use solana_program::{program_option::COption, program_pack::Pack};
+#[cfg(all(feature = "token", feature = "token-2022"))]
+use solana_sdk::account::Account;
 use solana_sdk::signature::Keypair;
+#[cfg(all(feature = "token", feature = "token-2022"))]
+use spl_token_2022::state::{Account as TokenAccount, AccountState};
 
-pub async fn setup(pt: ProgramTest) -> (RpcClient, Keypair, Hash) {
-    let (bc, payer, rbh) = pt.start().await;
-    let (port, _jh) = BanksRpcServer::spawn_random_unused(bc).await;
+/// Accounts added to `pt` are found by `getProgramAccounts` and the token methods
+/// that scan accounts if `pt` is a [`RpcProgramTest`]
+pub async fn setup(pt: impl Into<RpcProgramTest>) -> (RpcClient, Keypair, Hash) {
+    let (server, payer, rbh) = pt.into().start_rpc_server().await;
+    let (port, _jh) = server.spawn_on_random_unused_port().await;
     let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
     (client, payer, rbh)
 }
+
+#[cfg(all(feature = "token", feature = "token-2022"))]
+pub const TOKEN_PROGRAMS: [Pubkey; 2] = [spl_token::ID, spl_token_2022::ID];
+
+#[cfg(all(feature = "token", feature = "token-2022"))]
+pub fn mint_account(token_program: Pubkey, supply: u64, decimals: u8) -> Account {
+    let mint = MockMintArgs {
+        mint_authority: None,
+        freeze_authority: None,
+        supply,
+        decimals,
+    };
+    if token_program == spl_token::ID {
+        mock_tokenkeg_mint(mint).into_account()
+    } else {
+        // mint address is only used by the token metadata extension
+        mock_token22_mint(
+            Pubkey::default(),
+            MockToken22MintArgs {
+                mint,
+                extensions: Default::default(),
+            },
+        )
+    }
+}
+
+#[cfg(all(feature = "token", feature = "token-2022"))]
+pub fn token_account(
+    token_program: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+    delegate: Option<Pubkey>,
+) -> Account {
+    let args = MockTokenAccountArgs {
+        mint,
+        authority: owner,
+        amount,
+    };
+    let mut account = if token_program == spl_token::ID {
+        mock_tokenkeg_account(args).into_account()
+    } else {
+        mock_token22_account(MockToken22AccountArgs {
+            account: args,
+            state: AccountState::Initialized,
+            extensions: Default::default(),
+        })
+    };
+    if let Some(delegate) = delegate {
+        // token-2022 accounts without extensions have the same layout as tokenkeg's
+        let mut state = TokenAccount::unpack(&account.data).unwrap();
+        state.delegate = COption::Some(delegate);
+        state.delegated_amount = amount;
+        TokenAccount::pack(state, &mut account.data).unwrap();
+    }
+    account
+}
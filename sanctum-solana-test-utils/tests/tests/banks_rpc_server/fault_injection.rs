@@ -0,0 +1,171 @@
+use std::time::{Duration, Instant};
+
+use sanctum_solana_test_utils::banks_rpc_server::{BanksRpcServer, Fault, FaultRule};
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_rpc_client_api::{
+    client_error::ErrorKind,
+    custom_error::JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED,
+    request::{RpcError, RpcRequest},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signer::Signer, transaction::Transaction};
+
+/// Returns the error code of a `getAccountInfo` request that is expected to fail.
+///
+/// [`RpcClient::get_account`] wraps RPC errors in an AccountNotFound error, so the raw request is sent instead
+fn get_account_info_err_code(client: &RpcClient, pubkey: &Pubkey) -> i64 {
+    let err = client
+        .send::<Value>(RpcRequest::GetAccountInfo, json!([pubkey.to_string()]))
+        .unwrap_err();
+    match err.kind() {
+        ErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => *code,
+        _ => panic!("Unexpected err {err}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fault_json_rpc_error_first_n() {
+    let (bc, payer, _rbh) = ProgramTest::default().start().await;
+    let server = BanksRpcServer::new(bc).with_fault_rules(vec![FaultRule {
+        method: Some("getAccountInfo".to_owned()),
+        fault: Fault::JsonRpcError(jsonrpc_core::Error::internal_error()),
+        times: Some(2),
+    }]);
+    let (port, _jh) = server.spawn_on_random_unused_port().await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    for _ in 0..2 {
+        assert_eq!(
+            get_account_info_err_code(&client, &payer.pubkey()),
+            jsonrpc_core::ErrorCode::InternalError.code()
+        );
+    }
+    assert!(client.get_account(&payer.pubkey()).is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fault_http_429_retried_by_client() {
+    let (bc, payer, _rbh) = ProgramTest::default().start().await;
+    let server = BanksRpcServer::new(bc).with_fault_rules(vec![FaultRule {
+        method: None,
+        fault: Fault::HttpStatus(429),
+        times: Some(1),
+    }]);
+    let (port, _jh) = server.spawn_on_random_unused_port().await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    // RpcClient retries on 429 by itself
+    assert!(client.get_account(&payer.pubkey()).is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fault_runtime_changes() {
+    let (bc, payer, _rbh) = ProgramTest::default().start().await;
+    let server = BanksRpcServer::new(bc);
+    let faults = server.fault_injector();
+    let (port, _jh) = server.spawn_on_random_unused_port().await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    assert!(client.get_account(&payer.pubkey()).is_ok());
+
+    faults.push_rule(FaultRule {
+        method: Some("getAccountInfo".to_owned()),
+        fault: Fault::MinContextSlotNotReached,
+        times: None,
+    });
+    for _ in 0..3 {
+        assert_eq!(
+            get_account_info_err_code(&client, &payer.pubkey()),
+            JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED
+        );
+    }
+    // other methods are unaffected
+    assert!(client.get_latest_blockhash().is_ok());
+
+    faults.set_rules(vec![FaultRule {
+        method: None,
+        fault: Fault::DropConnection,
+        times: None,
+    }]);
+    assert!(client.get_account(&payer.pubkey()).is_err());
+
+    faults.clear();
+    assert!(client.get_account(&payer.pubkey()).is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fault_delay_and_stale_context_slot() {
+    const DELAY: Duration = Duration::from_millis(500);
+
+    let (bc, _payer, _rbh) = ProgramTest::default().start().await;
+    let server = BanksRpcServer::new(bc).with_fault_rules(vec![
+        FaultRule {
+            method: Some("getAccountInfo".to_owned()),
+            fault: Fault::Delay(DELAY),
+            times: None,
+        },
+        FaultRule {
+            method: Some("getAccountInfo".to_owned()),
+            fault: Fault::StaleContextSlot(0),
+            times: None,
+        },
+    ]);
+    let faults = server.fault_injector();
+    let (port, _jh) = server.spawn_on_random_unused_port().await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    let start = Instant::now();
+    let res = client
+        .get_account_with_commitment(&Pubkey::new_unique(), CommitmentConfig::default())
+        .unwrap();
+    assert!(start.elapsed() >= DELAY);
+    assert_eq!(res.context.slot, 0);
+
+    faults.set_rules(vec![FaultRule {
+        method: None,
+        fault: Fault::Delay(Duration::from_secs(5)),
+        times: None,
+    }]);
+    let client = RpcClient::new_with_timeout(
+        format!("http://127.0.0.1:{port}"),
+        Duration::from_millis(100),
+    );
+    assert!(client.get_latest_blockhash().is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fault_drop_transaction() {
+    let (bc, payer, rbh) = ProgramTest::default().start().await;
+    let server = BanksRpcServer::new(bc).with_fault_rules(vec![FaultRule {
+        method: Some("sendTransaction".to_owned()),
+        fault: Fault::DropTransaction,
+        times: Some(1),
+    }]);
+    let faults = server.fault_injector();
+    let (port, _jh) = server.spawn_on_random_unused_port().await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    let dst = Pubkey::new_unique();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    assert_eq!(client.send_transaction(&tx).unwrap(), tx.signatures[0]);
+    assert_eq!(
+        client.get_signature_status(&tx.signatures[0]).unwrap(),
+        None
+    );
+    assert!(client.get_account(&dst).is_err());
+    assert!(faults.rules().is_empty());
+
+    client.send_and_confirm_transaction(&tx).unwrap();
+    assert_eq!(client.get_account(&dst).unwrap().lamports, LAMPORTS_PER_SOL);
+}
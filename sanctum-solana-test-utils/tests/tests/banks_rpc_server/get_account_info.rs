@@ -1,7 +1,21 @@
 use sanctum_solana_test_utils::{ExtendedProgramTest, Keyed};
-use solana_program::pubkey::Pubkey;
+use serde_json::json;
+use solana_account_decoder::{UiAccount, UiAccountData, UiAccountEncoding};
+use solana_program::{
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    stake::{
+        self,
+        state::{Authorized, Meta, StakeStateV2},
+    },
+};
 use solana_program_test::ProgramTest;
+use solana_rpc_client_api::{
+    config::RpcAccountInfoConfig, request::RpcRequest, response::Response,
+};
 use solana_sdk::{account::Account, commitment_config::CommitmentConfig};
+use spl_token_2022::state::{AccountState, Mint};
 
 use crate::tests::banks_rpc_server::common::setup;
 
@@ -35,3 +49,141 @@ async fn get_account_info_basic() {
         None
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_account_info_binary_encodings() {
+    let [small, large, o] = [(); 3].map(|_| Pubkey::new_unique());
+    let small_acc = Account {
+        lamports: 1_000_000,
+        data: vec![1; 128],
+        owner: o,
+        executable: false,
+        rent_epoch: 0,
+    };
+    let large_acc = Account {
+        data: vec![1; 129],
+        ..small_acc.clone()
+    };
+    let pt = ProgramTest::default()
+        .add_account_chained(small, small_acc.clone())
+        .add_account_chained(large, large_acc.clone());
+
+    let (client, _payer, _rbh) = setup(pt).await;
+
+    for encoding in [
+        UiAccountEncoding::Base58,
+        UiAccountEncoding::Base64,
+        UiAccountEncoding::Base64Zstd,
+    ] {
+        assert_eq!(
+            client
+                .get_account_with_config(
+                    &small,
+                    RpcAccountInfoConfig {
+                        encoding: Some(encoding),
+                        ..Default::default()
+                    }
+                )
+                .unwrap()
+                .value
+                .unwrap(),
+            small_acc
+        );
+    }
+    assert_eq!(
+        client
+            .get_account_with_config(
+                &large,
+                RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64Zstd),
+                    ..Default::default()
+                }
+            )
+            .unwrap()
+            .value
+            .unwrap(),
+        large_acc
+    );
+    assert!(client
+        .get_account_with_config(
+            &large,
+            RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base58),
+                ..Default::default()
+            }
+        )
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_account_info_json_parsed() {
+    let [mint, token_acc, owner, stake_acc] = [(); 4].map(|_| Pubkey::new_unique());
+
+    let mut mint_data = vec![0u8; Mint::LEN];
+    Mint::pack(
+        Mint {
+            mint_authority: COption::None,
+            supply: 1_000,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        },
+        &mut mint_data,
+    )
+    .unwrap();
+    let mut token_acc_data = vec![0u8; spl_token_2022::state::Account::LEN];
+    spl_token_2022::state::Account::pack(
+        spl_token_2022::state::Account {
+            mint,
+            owner,
+            amount: 1_000,
+            state: AccountState::Initialized,
+            ..Default::default()
+        },
+        &mut token_acc_data,
+    )
+    .unwrap();
+    let stake_acc_data = bincode::serialize(&StakeStateV2::Initialized(Meta {
+        authorized: Authorized::auto(&owner),
+        ..Default::default()
+    }))
+    .unwrap();
+
+    let pt = [
+        (mint, mint_data, spl_token_2022::ID),
+        (token_acc, token_acc_data, spl_token_2022::ID),
+        (stake_acc, stake_acc_data, stake::program::ID),
+    ]
+    .into_iter()
+    .fold(ProgramTest::default(), |pt, (pubkey, data, owner)| {
+        pt.add_account_chained(
+            pubkey,
+            Account {
+                lamports: 1_000_000_000,
+                data,
+                owner,
+                executable: false,
+                rent_epoch: u64::MAX,
+            },
+        )
+    });
+
+    let (client, _payer, _rbh) = setup(pt).await;
+
+    let ui_token_acc = client.get_token_account(&token_acc).unwrap().unwrap();
+    assert_eq!(ui_token_acc.mint, mint.to_string());
+    assert_eq!(ui_token_acc.owner, owner.to_string());
+    assert_eq!(ui_token_acc.token_amount.decimals, 6);
+    assert_eq!(ui_token_acc.token_amount.ui_amount, Some(0.001));
+
+    let ui_stake_acc: Response<Option<UiAccount>> = client
+        .send(
+            RpcRequest::GetAccountInfo,
+            json!([stake_acc.to_string(), { "encoding": "jsonParsed" }]),
+        )
+        .unwrap();
+    match ui_stake_acc.value.unwrap().data {
+        UiAccountData::Json(parsed) => assert_eq!(parsed.program, "stake"),
+        d => panic!("Unexpected data {d:?}"),
+    }
+}
@@ -0,0 +1,24 @@
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_program};
+use solana_program_test::ProgramTest;
+use solana_sdk::{account::Account, signer::Signer};
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_balance_basic() {
+    let key = Pubkey::new_unique();
+    let mut pt = ProgramTest::default();
+    pt.add_account(
+        key,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            owner: system_program::ID,
+            ..Default::default()
+        },
+    );
+    let (client, payer, _rbh) = setup(pt).await;
+
+    assert_eq!(client.get_balance(&key).unwrap(), LAMPORTS_PER_SOL);
+    assert!(client.get_balance(&payer.pubkey()).unwrap() > 0);
+    assert_eq!(client.get_balance(&Pubkey::new_unique()).unwrap(), 0);
+}
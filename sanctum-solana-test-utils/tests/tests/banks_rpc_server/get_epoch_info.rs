@@ -0,0 +1,30 @@
+use sanctum_solana_test_utils::banks_rpc_server::BanksRpcServer;
+use solana_client::rpc_client::RpcClient;
+use solana_program::epoch_schedule::EpochSchedule;
+use solana_program_test::ProgramTest;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_epoch_info_after_warp() {
+    const SLOT: u64 = 1_000_000;
+
+    let mut ctx = ProgramTest::default().start_with_context().await;
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(ctx.banks_client.clone()).await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+    let epoch_schedule: EpochSchedule = ctx.banks_client.get_sysvar().await.unwrap();
+
+    ctx.warp_to_slot(SLOT).unwrap();
+    let epoch_info = client.get_epoch_info().unwrap();
+
+    let (epoch, slot_index) = epoch_schedule.get_epoch_and_slot_index(SLOT);
+    assert_eq!(epoch_info.absolute_slot, SLOT);
+    assert_eq!(epoch_info.epoch, epoch);
+    assert_eq!(epoch_info.slot_index, slot_index);
+    assert_eq!(
+        epoch_info.slots_in_epoch,
+        epoch_schedule.get_slots_in_epoch(epoch)
+    );
+    assert_eq!(
+        epoch_info.block_height,
+        ctx.banks_client.get_root_block_height().await.unwrap()
+    );
+}
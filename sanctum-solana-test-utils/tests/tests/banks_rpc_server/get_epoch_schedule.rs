@@ -0,0 +1,16 @@
+use sanctum_solana_test_utils::banks_rpc_server::BanksRpcServer;
+use solana_client::rpc_client::RpcClient;
+use solana_program::epoch_schedule::EpochSchedule;
+use solana_program_test::ProgramTest;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_epoch_schedule_basic() {
+    let (mut bc, _payer, _rbh) = ProgramTest::default().start().await;
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(bc.clone()).await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    assert_eq!(
+        client.get_epoch_schedule().unwrap(),
+        bc.get_sysvar::<EpochSchedule>().await.unwrap()
+    );
+}
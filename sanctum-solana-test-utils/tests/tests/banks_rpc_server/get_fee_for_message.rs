@@ -0,0 +1,44 @@
+use sanctum_solana_test_utils::banks_rpc_server::BanksRpcServer;
+use solana_client::rpc_client::RpcClient;
+use solana_program::{
+    hash::Hash, message::Message, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::ProgramTest;
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, message::v0, signer::Signer};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_fee_for_message_basic() {
+    let (mut bc, payer, rbh) = ProgramTest::default().start().await;
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(bc.clone()).await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    let ixs = [
+        ComputeBudgetInstruction::set_compute_unit_price(1_000_000),
+        system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), LAMPORTS_PER_SOL),
+    ];
+    let msg = Message::new_with_blockhash(&ixs, Some(&payer.pubkey()), &rbh);
+    let expected = bc.get_fee_for_message(msg.clone()).await.unwrap().unwrap();
+
+    assert_eq!(client.get_fee_for_message(&msg).unwrap(), expected);
+    let v0_msg = v0::Message::try_compile(&payer.pubkey(), &ixs, &[], rbh).unwrap();
+    assert_eq!(client.get_fee_for_message(&v0_msg).unwrap(), expected);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_fee_for_message_invalid_blockhash() {
+    let (bc, payer, _rbh) = ProgramTest::default().start().await;
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(bc).await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    let msg = Message::new_with_blockhash(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &Hash::new_unique(),
+    );
+    assert!(client.get_fee_for_message(&msg).is_err());
+}
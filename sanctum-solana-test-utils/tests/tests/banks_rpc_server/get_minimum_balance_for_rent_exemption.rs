@@ -0,0 +1,20 @@
+use sanctum_solana_test_utils::banks_rpc_server::BanksRpcServer;
+use solana_client::rpc_client::RpcClient;
+use solana_program_test::ProgramTest;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_minimum_balance_for_rent_exemption_basic() {
+    let (mut bc, _payer, _rbh) = ProgramTest::default().start().await;
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(bc.clone()).await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+    let rent = bc.get_rent().await.unwrap();
+
+    for data_len in [0, 165, 10_000] {
+        assert_eq!(
+            client
+                .get_minimum_balance_for_rent_exemption(data_len)
+                .unwrap(),
+            rent.minimum_balance(data_len)
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use sanctum_solana_test_utils::{
+    banks_rpc_server::{BanksRpcServer, RpcProgramTest},
+    ExtendedProgramTest,
+};
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::rpc_client::RpcClient;
+use solana_program::{
+    bpf_loader_upgradeable, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction,
+    system_program,
+};
+use solana_program_test::ProgramTest;
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{account::Account, signer::Signer, transaction::Transaction};
+
+use crate::tests::banks_rpc_server::common::setup;
+
+fn program_account(owner: Pubkey, data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1_000_000,
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_program_accounts_filters() {
+    let program_id = Pubkey::new_unique();
+    let [p1, p2, p3] = [(); 3].map(|_| Pubkey::new_unique());
+    let a1 = program_account(program_id, vec![1, 2, 3, 4]);
+    let a2 = program_account(program_id, vec![1, 2, 5, 6, 7]);
+    let a3 = program_account(Pubkey::new_unique(), vec![1, 2, 3, 4]);
+    let pt = RpcProgramTest::default()
+        .add_account_chained(p1, a1.clone())
+        .add_account_chained(p2, a2.clone())
+        .add_account_chained(p3, a3);
+
+    let (client, _payer, _rbh) = setup(pt).await;
+
+    let mut all = client.get_program_accounts(&program_id).unwrap();
+    all.sort_by_key(|(pk, _)| *pk);
+    let mut expected = vec![(p1, a1.clone()), (p2, a2)];
+    expected.sort_by_key(|(pk, _)| *pk);
+    assert_eq!(all, expected);
+
+    let cfg = |filters| RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert_eq!(
+        client
+            .get_program_accounts_with_config(&program_id, cfg(vec![RpcFilterType::DataSize(4)]))
+            .unwrap(),
+        vec![(p1, a1.clone())]
+    );
+    assert_eq!(
+        client
+            .get_program_accounts_with_config(
+                &program_id,
+                cfg(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    1,
+                    vec![2, 3]
+                ))])
+            )
+            .unwrap(),
+        vec![(p1, a1)]
+    );
+    assert!(client
+        .get_program_accounts_with_config(
+            &program_id,
+            cfg(vec![
+                RpcFilterType::DataSize(5),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(2, vec![3])),
+            ])
+        )
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_program_accounts_data_slice_with_context() {
+    let program_id = Pubkey::new_unique();
+    let pk = Pubkey::new_unique();
+    let account = program_account(program_id, vec![0, 1, 2, 3, 4, 5]);
+    let pt = RpcProgramTest::default().add_account_chained(pk, account.clone());
+
+    let (client, _payer, _rbh) = setup(pt).await;
+
+    let res = client
+        .get_program_accounts_with_config(
+            &program_id,
+            RpcProgramAccountsConfig {
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    data_slice: Some(UiDataSliceConfig {
+                        offset: 2,
+                        length: 3,
+                    }),
+                    ..Default::default()
+                },
+                with_context: Some(true),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        vec![(
+            pk,
+            Account {
+                data: vec![2, 3, 4],
+                ..account
+            }
+        )]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_program_accounts_finds_accounts_added_by_extended_program_test() {
+    let program_id = Pubkey::new_unique();
+    let (prog_data_addr, _bump) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID);
+    let pt =
+        RpcProgramTest::default().add_upgradeable_program_from_elf(program_id, &[0; 8], None, 0);
+
+    let (client, _payer, _rbh) = setup(pt).await;
+
+    let mut keys: Vec<_> = client
+        .get_program_accounts(&bpf_loader_upgradeable::ID)
+        .unwrap()
+        .into_iter()
+        .map(|(pk, _)| pk)
+        .collect();
+    keys.sort();
+    let mut expected = vec![program_id, prog_data_addr];
+    expected.sort();
+    assert_eq!(keys, expected);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_program_accounts_written_since() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+
+    let dst = Pubkey::new_unique();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    client.send_and_confirm_transaction(&tx).unwrap();
+
+    let res = client.get_program_accounts(&system_program::ID).unwrap();
+    assert!(res
+        .iter()
+        .any(|(pk, acc)| *pk == dst && acc.lamports == LAMPORTS_PER_SOL));
+    assert!(res.iter().any(|(pk, _)| *pk == payer.pubkey()));
+}
+
+async fn spawn_client(server: BanksRpcServer) -> RpcClient {
+    let (port, _jh) = server.spawn_on_random_unused_port().await;
+    RpcClient::new(format!("http://127.0.0.1:{port}"))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_program_accounts_known_pubkeys_per_server() {
+    let program_id = Pubkey::new_unique();
+    let pk = Pubkey::new_unique();
+    let account = program_account(program_id, vec![1]);
+    let (mut bc, payer, rbh) = ProgramTest::default()
+        .add_account_chained(pk, account.clone())
+        .start()
+        .await;
+
+    let seeded = BanksRpcServer::new(bc.clone()).with_known_pubkeys([pk]);
+    let unseeded = BanksRpcServer::new(bc.clone());
+    let known_pubkeys = unseeded.known_pubkeys();
+    let seeded = spawn_client(seeded).await;
+    let unseeded = spawn_client(unseeded).await;
+
+    assert_eq!(
+        seeded.get_program_accounts(&program_id).unwrap(),
+        vec![(pk, account.clone())]
+    );
+    assert!(unseeded
+        .get_program_accounts(&program_id)
+        .unwrap()
+        .is_empty());
+
+    // accounts created by txs sent directly through BanksClient must be registered
+    let dst = Pubkey::new_unique();
+    bc.process_transaction(Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    ))
+    .await
+    .unwrap();
+    let has_dst = |client: &RpcClient| {
+        client
+            .get_program_accounts(&system_program::ID)
+            .unwrap()
+            .iter()
+            .any(|(k, _)| *k == dst)
+    };
+    assert!(!has_dst(&unseeded));
+    known_pubkeys.extend([pk, dst]);
+    assert!(has_dst(&unseeded));
+    assert_eq!(
+        unseeded.get_program_accounts(&program_id).unwrap(),
+        vec![(pk, account)]
+    );
+}
@@ -0,0 +1,95 @@
+use sanctum_solana_client_utils::{
+    calc_slot_weighted_median_prioritization_fees, get_compute_budget_ixs_auto,
+    get_slot_weighted_median_unit_price, ComputeBudgetFeeLimit, ComputeBudgetIxs,
+};
+use sanctum_solana_test_utils::banks_rpc_server::{BanksRpcServer, PrioritizationFeeSchedule};
+use solana_client::rpc_client::RpcClient;
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_rpc_client_api::response::RpcPrioritizationFee;
+use solana_sdk::signer::Signer;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_recent_prioritization_fees_by_accounts() {
+    let [a, b] = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let (bc, _payer, _rbh) = ProgramTest::default().start().await;
+    let server = BanksRpcServer::new(bc).with_prioritization_fees(
+        PrioritizationFeeSchedule::default()
+            .with_default([(1, 10)])
+            .with_accounts([a, b], [(1, 100), (2, 200)]),
+    );
+    let fees = server.prioritization_fees();
+    let (port, _jh) = server.spawn_on_random_unused_port().await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    let expected_ab = vec![
+        RpcPrioritizationFee {
+            slot: 1,
+            prioritization_fee: 100,
+        },
+        RpcPrioritizationFee {
+            slot: 2,
+            prioritization_fee: 200,
+        },
+    ];
+    assert_eq!(
+        client.get_recent_prioritization_fees(&[b, a]).unwrap(),
+        expected_ab
+    );
+    assert_eq!(
+        client.get_recent_prioritization_fees(&[a, b, a]).unwrap(),
+        expected_ab
+    );
+    for accounts in [vec![], vec![a]] {
+        assert_eq!(
+            client.get_recent_prioritization_fees(&accounts).unwrap(),
+            vec![RpcPrioritizationFee {
+                slot: 1,
+                prioritization_fee: 10
+            }]
+        );
+    }
+
+    fees.clear();
+    assert!(client
+        .get_recent_prioritization_fees(&[])
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fee_estimation_end_to_end() {
+    let (bc, payer, _rbh) = ProgramTest::default().start().await;
+    let dst = Pubkey::new_unique();
+    let schedule = PrioritizationFeeSchedule::default().with_accounts(
+        [payer.pubkey(), dst],
+        [(1, 1_000), (2, 0), (3, 3_000), (4, 5_000)],
+    );
+    let expected_median =
+        calc_slot_weighted_median_prioritization_fees(&schedule.get(&[payer.pubkey(), dst]))
+            .unwrap();
+    let server = BanksRpcServer::new(bc).with_prioritization_fees(schedule);
+    let (port, _jh) = server.spawn_on_random_unused_port().await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    let median = get_slot_weighted_median_unit_price(&client, &[payer.pubkey(), dst]).unwrap();
+    assert_eq!(median, expected_median);
+
+    let ComputeBudgetIxs { set_price, .. } = get_compute_budget_ixs_auto(
+        &client,
+        &payer.pubkey(),
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        &[],
+        &ComputeBudgetFeeLimit::MicroLamportsPerCu(u64::MAX),
+        1.0,
+    )
+    .unwrap();
+    assert_eq!(set_price, ComputeBudgetIxs::new(0, median).set_price);
+
+    // no samples set for this account set
+    assert!(get_slot_weighted_median_unit_price(&client, &[dst]).is_err());
+}
@@ -0,0 +1,37 @@
+use solana_program::{pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::{signature::Signature, signer::Signer, transaction::Transaction};
+use solana_transaction_status::TransactionConfirmationStatus;
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_signature_statuses_basic() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            1_000_000_000,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    let sig = client.send_and_confirm_transaction(&tx).unwrap();
+
+    let [landed, unknown]: [_; 2] = client
+        .get_signature_statuses(&[sig, Signature::new_unique()])
+        .unwrap()
+        .value
+        .try_into()
+        .unwrap();
+    let landed = landed.unwrap();
+    assert!(landed.err.is_none());
+    assert_eq!(
+        landed.confirmation_status,
+        Some(TransactionConfirmationStatus::Finalized)
+    );
+    assert!(unknown.is_none());
+}
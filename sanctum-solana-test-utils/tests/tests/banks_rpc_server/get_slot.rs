@@ -0,0 +1,56 @@
+use sanctum_solana_test_utils::banks_rpc_server::BanksRpcServer;
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_program_test::ProgramTest;
+use solana_rpc_client_api::{
+    client_error::ErrorKind, config::RpcAccountInfoConfig,
+    custom_error::JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED, request::RpcError,
+    request::RpcRequest,
+};
+use solana_sdk::signer::Signer;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_slot_after_warp() {
+    let mut ctx = ProgramTest::default().start_with_context().await;
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(ctx.banks_client.clone()).await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    ctx.warp_to_slot(1_000).unwrap();
+    assert_eq!(client.get_slot().unwrap(), 1_000);
+
+    // responses report the slot they were served at
+    let res = client
+        .get_account_with_config(
+            &ctx.payer.pubkey(),
+            RpcAccountInfoConfig {
+                min_context_slot: Some(1_000),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(res.context.slot, 1_000);
+    assert!(res.value.is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_slot_min_context_slot_not_reached() {
+    let ctx = ProgramTest::default().start_with_context().await;
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(ctx.banks_client.clone()).await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    for (method, params) in [
+        (RpcRequest::GetSlot, json!([{ "minContextSlot": 1_000 }])),
+        (
+            RpcRequest::GetBalance,
+            json!([ctx.payer.pubkey().to_string(), { "minContextSlot": 1_000 }]),
+        ),
+    ] {
+        let err = client.send::<Value>(method, params).unwrap_err();
+        match err.kind() {
+            ErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+                assert_eq!(*code, JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED)
+            }
+            _ => panic!("Unexpected err {err}"),
+        }
+    }
+}
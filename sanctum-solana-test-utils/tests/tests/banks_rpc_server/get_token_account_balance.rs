@@ -0,0 +1,29 @@
+use sanctum_solana_test_utils::ExtendedProgramTest;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTest;
+
+use crate::tests::banks_rpc_server::common::{mint_account, setup, token_account, TOKEN_PROGRAMS};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_token_account_balance_basic() {
+    for token_program in TOKEN_PROGRAMS {
+        let [mint, owner, acc] = [(); 3].map(|_| Pubkey::new_unique());
+        let pt = ProgramTest::default()
+            .add_account_chained(mint, mint_account(token_program, 1_500_000_000, 9))
+            .add_account_chained(
+                acc,
+                token_account(token_program, mint, owner, 1_500_000_000, None),
+            );
+
+        let (client, _payer, _rbh) = setup(pt).await;
+
+        let amt = client.get_token_account_balance(&acc).unwrap();
+        assert_eq!(amt.amount, "1500000000");
+        assert_eq!(amt.decimals, 9);
+        assert_eq!(amt.ui_amount, Some(1.5));
+        assert_eq!(amt.ui_amount_string, "1.5");
+
+        // not a token account
+        assert!(client.get_token_account_balance(&mint).is_err());
+    }
+}
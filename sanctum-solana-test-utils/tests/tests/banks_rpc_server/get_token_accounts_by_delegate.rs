@@ -0,0 +1,57 @@
+use sanctum_solana_test_utils::{banks_rpc_server::RpcProgramTest, ExtendedProgramTest};
+use serde_json::json;
+use solana_client::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client_api::{
+    config::RpcTokenAccountsFilter,
+    request::RpcRequest,
+    response::{Response, RpcKeyedAccount},
+};
+
+use crate::tests::banks_rpc_server::common::{mint_account, setup, token_account, TOKEN_PROGRAMS};
+
+// RpcClient::get_token_accounts_by_delegate() calls getTokenAccountsByOwner instead
+fn get_token_accounts_by_delegate(
+    client: &RpcClient,
+    delegate: &Pubkey,
+    filter: RpcTokenAccountsFilter,
+) -> Vec<RpcKeyedAccount> {
+    let res: Response<Vec<RpcKeyedAccount>> = client
+        .send(
+            RpcRequest::GetTokenAccountsByDelegate,
+            json!([delegate.to_string(), filter, { "encoding": "base64" }]),
+        )
+        .unwrap();
+    res.value
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_token_accounts_by_delegate_basic() {
+    for token_program in TOKEN_PROGRAMS {
+        let [mint, owner, delegate, acc1, acc2] = [(); 5].map(|_| Pubkey::new_unique());
+        let pt = RpcProgramTest::default()
+            .add_account_chained(mint, mint_account(token_program, 3_000, 3))
+            .add_account_chained(
+                acc1,
+                token_account(token_program, mint, owner, 1_000, Some(delegate)),
+            )
+            .add_account_chained(acc2, token_account(token_program, mint, owner, 2_000, None));
+
+        let (client, _payer, _rbh) = setup(pt).await;
+
+        let res = get_token_accounts_by_delegate(
+            &client,
+            &delegate,
+            RpcTokenAccountsFilter::Mint(mint.to_string()),
+        );
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].pubkey, acc1.to_string());
+
+        assert!(get_token_accounts_by_delegate(
+            &client,
+            &owner,
+            RpcTokenAccountsFilter::ProgramId(token_program.to_string())
+        )
+        .is_empty());
+    }
+}
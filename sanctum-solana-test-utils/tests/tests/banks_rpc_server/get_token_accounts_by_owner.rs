@@ -0,0 +1,54 @@
+use sanctum_solana_test_utils::{banks_rpc_server::RpcProgramTest, ExtendedProgramTest};
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_program::pubkey::Pubkey;
+
+use crate::tests::banks_rpc_server::common::{mint_account, setup, token_account, TOKEN_PROGRAMS};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_token_accounts_by_owner_basic() {
+    for token_program in TOKEN_PROGRAMS {
+        let [mint1, mint2, owner, other_owner, acc1, acc2, acc3] =
+            [(); 7].map(|_| Pubkey::new_unique());
+        let pt = RpcProgramTest::default()
+            .add_account_chained(mint1, mint_account(token_program, 3_000, 3))
+            .add_account_chained(mint2, mint_account(token_program, 2_000, 0))
+            .add_account_chained(
+                acc1,
+                token_account(token_program, mint1, owner, 1_000, None),
+            )
+            .add_account_chained(
+                acc2,
+                token_account(token_program, mint2, owner, 2_000, None),
+            )
+            .add_account_chained(
+                acc3,
+                token_account(token_program, mint1, other_owner, 2_000, None),
+            );
+
+        let (client, _payer, _rbh) = setup(pt).await;
+
+        let by_mint = client
+            .get_token_accounts_by_owner(&owner, TokenAccountsFilter::Mint(mint1))
+            .unwrap();
+        assert_eq!(by_mint.len(), 1);
+        assert_eq!(by_mint[0].pubkey, acc1.to_string());
+
+        let mut by_program: Vec<_> = client
+            .get_token_accounts_by_owner(&owner, TokenAccountsFilter::ProgramId(token_program))
+            .unwrap()
+            .into_iter()
+            .map(|a| a.pubkey)
+            .collect();
+        by_program.sort();
+        let mut expected = vec![acc1.to_string(), acc2.to_string()];
+        expected.sort();
+        assert_eq!(by_program, expected);
+
+        assert!(client
+            .get_token_accounts_by_owner(
+                &owner,
+                TokenAccountsFilter::ProgramId(Pubkey::new_unique())
+            )
+            .is_err());
+    }
+}
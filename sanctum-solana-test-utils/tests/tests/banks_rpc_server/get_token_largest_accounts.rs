@@ -0,0 +1,41 @@
+use sanctum_solana_test_utils::{banks_rpc_server::RpcProgramTest, ExtendedProgramTest};
+use solana_program::pubkey::Pubkey;
+
+use crate::tests::banks_rpc_server::common::{mint_account, setup, token_account, TOKEN_PROGRAMS};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_token_largest_accounts_basic() {
+    for token_program in TOKEN_PROGRAMS {
+        let [mint, other_mint, other_acc, owner] = [(); 4].map(|_| Pubkey::new_unique());
+        let amounts = [5, 300, 20];
+        let accs = amounts.map(|_| Pubkey::new_unique());
+        let pt = RpcProgramTest::default()
+            .add_account_chained(mint, mint_account(token_program, 325, 0))
+            .add_account_chained(other_mint, mint_account(token_program, 1_000, 0))
+            .add_account_chained(
+                other_acc,
+                token_account(token_program, other_mint, owner, 1_000, None),
+            );
+        let pt = accs.iter().zip(amounts).fold(pt, |pt, (acc, amount)| {
+            pt.add_account_chained(
+                *acc,
+                token_account(token_program, mint, owner, amount, None),
+            )
+        });
+
+        let (client, _payer, _rbh) = setup(pt).await;
+
+        let largest = client.get_token_largest_accounts(&mint).unwrap();
+        assert_eq!(
+            largest
+                .iter()
+                .map(|b| (b.address.as_str(), b.amount.amount.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (accs[1].to_string().as_str(), "300"),
+                (accs[2].to_string().as_str(), "20"),
+                (accs[0].to_string().as_str(), "5"),
+            ]
+        );
+    }
+}
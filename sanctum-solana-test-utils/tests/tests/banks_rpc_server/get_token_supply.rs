@@ -0,0 +1,25 @@
+use sanctum_solana_test_utils::ExtendedProgramTest;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTest;
+
+use crate::tests::banks_rpc_server::common::{mint_account, setup, token_account, TOKEN_PROGRAMS};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_token_supply_basic() {
+    for token_program in TOKEN_PROGRAMS {
+        let [mint, owner, acc] = [(); 3].map(|_| Pubkey::new_unique());
+        let pt = ProgramTest::default()
+            .add_account_chained(mint, mint_account(token_program, 12_345, 2))
+            .add_account_chained(acc, token_account(token_program, mint, owner, 1, None));
+
+        let (client, _payer, _rbh) = setup(pt).await;
+
+        let supply = client.get_token_supply(&mint).unwrap();
+        assert_eq!(supply.amount, "12345");
+        assert_eq!(supply.decimals, 2);
+        assert_eq!(supply.ui_amount_string, "123.45");
+
+        // not a mint
+        assert!(client.get_token_supply(&acc).is_err());
+    }
+}
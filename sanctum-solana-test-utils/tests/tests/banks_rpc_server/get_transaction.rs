@@ -0,0 +1,63 @@
+use solana_program::{
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::ProgramTest;
+use solana_rpc_client_api::config::RpcTransactionConfig;
+use solana_sdk::{signer::Signer, transaction::VersionedTransaction};
+use solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionEncoding};
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_transaction_basic() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+
+    let tx = VersionedTransaction::try_new(
+        VersionedMessage::V0(
+            v0::Message::try_compile(
+                &payer.pubkey(),
+                &[system_instruction::transfer(
+                    &payer.pubkey(),
+                    &Pubkey::new_unique(),
+                    1_000_000_000,
+                )],
+                &[],
+                rbh,
+            )
+            .unwrap(),
+        ),
+        &[&payer],
+    )
+    .unwrap();
+    let sig = client.send_and_confirm_transaction(&tx).unwrap();
+
+    let res = client
+        .get_transaction_with_config(
+            &sig,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: None,
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.transaction.transaction.decode().unwrap(), tx);
+    let meta = res.transaction.meta.unwrap();
+    assert!(meta.err.is_none());
+    assert_eq!(meta.fee, 5000);
+    assert_eq!(
+        meta.pre_balances[0] - meta.post_balances[0],
+        1_000_000_000 + meta.fee
+    );
+    match meta.log_messages {
+        OptionSerializer::Some(logs) => assert!(!logs.is_empty()),
+        _ => panic!("missing log messages"),
+    }
+
+    // v0 txs require max_supported_transaction_version
+    client
+        .get_transaction(&sig, UiTransactionEncoding::Base64)
+        .unwrap_err();
+}
@@ -0,0 +1,17 @@
+use solana_program::hash::Hash;
+use solana_program_test::ProgramTest;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn is_blockhash_valid_basic() {
+    let (client, _payer, rbh) = setup(ProgramTest::default()).await;
+
+    assert!(client
+        .is_blockhash_valid(&rbh, CommitmentConfig::processed())
+        .unwrap());
+    assert!(!client
+        .is_blockhash_valid(&Hash::new_unique(), CommitmentConfig::processed())
+        .unwrap());
+}
@@ -1,5 +1,33 @@
 mod common;
+mod fault_injection;
 mod get_account_info;
+mod get_balance;
+mod get_epoch_info;
+mod get_epoch_schedule;
+mod get_fee_for_message;
 mod get_latest_blockhash;
+mod get_minimum_balance_for_rent_exemption;
 mod get_multiple_accounts;
+mod get_program_accounts;
+mod get_recent_prioritization_fees;
+mod get_signature_statuses;
+mod get_slot;
+#[cfg(all(feature = "token", feature = "token-2022"))]
+mod get_token_account_balance;
+#[cfg(all(feature = "token", feature = "token-2022"))]
+mod get_token_accounts_by_delegate;
+#[cfg(all(feature = "token", feature = "token-2022"))]
+mod get_token_accounts_by_owner;
+#[cfg(all(feature = "token", feature = "token-2022"))]
+mod get_token_largest_accounts;
+#[cfg(all(feature = "token", feature = "token-2022"))]
+mod get_token_supply;
+mod get_transaction;
 mod get_version;
+mod is_blockhash_valid;
+mod pubsub;
+mod record_replay;
+mod send_transaction;
+mod simulate_transaction;
+#[cfg(all(feature = "token", feature = "token-2022"))]
+mod stored_accounts_backend;
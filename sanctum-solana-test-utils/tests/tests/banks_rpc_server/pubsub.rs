@@ -0,0 +1,243 @@
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use sanctum_solana_test_utils::banks_rpc_server::BanksRpcServer;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{nonblocking::pubsub_client::PubsubClient, rpc_client::RpcClient};
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcSignatureSubscribeConfig},
+    response::{ProcessedSignatureResult, RpcSignatureResult},
+};
+use solana_sdk::{
+    account::Account,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    signature::Keypair,
+    signer::Signer,
+    transaction::Transaction,
+};
+use tokio::time::timeout;
+
+const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn account_and_signature_subscribe() {
+    let (bc, payer, rbh) = ProgramTest::default().start().await;
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(bc).await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+    let pubsub = PubsubClient::new(&format!("ws://127.0.0.1:{port}"))
+        .await
+        .unwrap();
+
+    let dst = Pubkey::new_unique();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+
+    let (mut account_notifs, _unsub) = pubsub
+        .account_subscribe(
+            &dst,
+            Some(RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    let (mut sig_notifs, _unsub) = pubsub
+        .signature_subscribe(
+            &tx.signatures[0],
+            Some(RpcSignatureSubscribeConfig {
+                commitment: Some(CommitmentConfig::processed()),
+                enable_received_notification: None,
+            }),
+        )
+        .await
+        .unwrap();
+
+    client.send_transaction(&tx).unwrap();
+
+    let notif = timeout(NOTIFICATION_TIMEOUT, account_notifs.next())
+        .await
+        .unwrap()
+        .unwrap();
+    let account: Account = notif.value.decode().unwrap();
+    assert_eq!(account.lamports, LAMPORTS_PER_SOL);
+
+    let notif = timeout(NOTIFICATION_TIMEOUT, sig_notifs.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        notif.value,
+        RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult { err: None })
+    );
+
+    // subscribing to an already landed tx notifies immediately
+    let (mut sig_notifs, _unsub) = pubsub
+        .signature_subscribe(&tx.signatures[0], None)
+        .await
+        .unwrap();
+    assert!(timeout(NOTIFICATION_TIMEOUT, sig_notifs.next())
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn slot_subscribe_warp() {
+    let mut ctx = ProgramTest::default().start_with_context().await;
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(ctx.banks_client.clone()).await;
+    let pubsub = PubsubClient::new(&format!("ws://127.0.0.1:{port}"))
+        .await
+        .unwrap();
+
+    let (mut slot_notifs, _unsub) = pubsub.slot_subscribe().await.unwrap();
+
+    ctx.warp_to_slot(1_000).unwrap();
+
+    let notif = timeout(NOTIFICATION_TIMEOUT, slot_notifs.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(notif.slot, 1_000);
+    assert_eq!(
+        notif.root,
+        ctx.banks_client
+            .get_slot_with_context(tarpc::context::current(), CommitmentLevel::Finalized)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn account_subscribe_err_keeps_connection() {
+    let (bc, payer, rbh) = ProgramTest::default().start().await;
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(bc).await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+    let pubsub = PubsubClient::new(&format!("ws://127.0.0.1:{port}"))
+        .await
+        .unwrap();
+
+    let kp = Keypair::new();
+    // base58 cannot encode data > 128 bytes, so this subscription errors once the account grows
+    let (_b58_notifs, _unsub) = pubsub
+        .account_subscribe(
+            &kp.pubkey(),
+            Some(RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base58),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    let (mut b64_notifs, _unsub) = pubsub
+        .account_subscribe(
+            &kp.pubkey(),
+            Some(RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            system_instruction::transfer(&payer.pubkey(), &kp.pubkey(), LAMPORTS_PER_SOL),
+            system_instruction::allocate(&kp.pubkey(), 200),
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &kp],
+        rbh,
+    );
+    client.send_transaction(&tx).unwrap();
+
+    let notif = timeout(NOTIFICATION_TIMEOUT, b64_notifs.next())
+        .await
+        .unwrap()
+        .unwrap();
+    let account: Account = notif.value.decode().unwrap();
+    assert_eq!(account.data.len(), 200);
+
+    // the connection is still alive after the base58 subscription errored
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &kp.pubkey(),
+            1,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    client.send_transaction(&tx).unwrap();
+
+    let notif = timeout(NOTIFICATION_TIMEOUT, b64_notifs.next())
+        .await
+        .unwrap()
+        .unwrap();
+    let account: Account = notif.value.decode().unwrap();
+    assert_eq!(account.lamports, LAMPORTS_PER_SOL + 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn subscriptions_notified_of_txs_processed_on_banks_client() {
+    let (mut bc, payer, rbh) = ProgramTest::default().start().await;
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(bc.clone()).await;
+    let pubsub = PubsubClient::new(&format!("ws://127.0.0.1:{port}"))
+        .await
+        .unwrap();
+
+    let dst = Pubkey::new_unique();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    let (mut account_notifs, _unsub) = pubsub
+        .account_subscribe(
+            &dst,
+            Some(RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    let (mut sig_notifs, _unsub) = pubsub
+        .signature_subscribe(&tx.signatures[0], None)
+        .await
+        .unwrap();
+
+    bc.process_transaction(tx).await.unwrap();
+
+    let notif = timeout(NOTIFICATION_TIMEOUT, account_notifs.next())
+        .await
+        .unwrap()
+        .unwrap();
+    let account: Account = notif.value.decode().unwrap();
+    assert_eq!(account.lamports, LAMPORTS_PER_SOL);
+
+    let notif = timeout(NOTIFICATION_TIMEOUT, sig_notifs.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        notif.value,
+        RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult { err: None })
+    );
+}
@@ -0,0 +1,85 @@
+use sanctum_solana_test_utils::banks_rpc_server::{BanksRpcServer, ReplayRpcServer};
+use solana_client::rpc_client::RpcClient;
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::{hash::Hash, signature::Keypair, signer::Signer, transaction::Transaction};
+
+/// Transfers to `dst` and returns the balance of `dst` after the transfer
+fn transfer_session(client: &RpcClient, payer: &Keypair, dst: &Pubkey, rbh: Hash) -> u64 {
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[payer],
+        rbh,
+    );
+    let sig = client.send_and_confirm_transaction(&tx).unwrap();
+    assert_eq!(sig, tx.signatures[0]);
+    client.get_account(dst).unwrap().lamports
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn record_then_replay() {
+    let recording = tempfile::NamedTempFile::new().unwrap();
+    let dst = Pubkey::new_unique();
+
+    let (bc, payer, rbh) = ProgramTest::default().start().await;
+    let (port, _jh) = BanksRpcServer::new(bc)
+        .with_recording(recording.path())
+        .unwrap()
+        .spawn_on_random_unused_port()
+        .await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+    let recorded_blockhash = client.get_latest_blockhash().unwrap();
+    let recorded_balance = transfer_session(&client, &payer, &dst, rbh);
+    assert_eq!(recorded_balance, LAMPORTS_PER_SOL);
+
+    let (port, _jh) = ReplayRpcServer::from_file(recording.path())
+        .unwrap()
+        .spawn_on_random_unused_port()
+        .await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+    assert_eq!(client.get_latest_blockhash().unwrap(), recorded_blockhash);
+    assert_eq!(
+        transfer_session(&client, &payer, &dst, rbh),
+        recorded_balance
+    );
+
+    // not recorded
+    let err = client.get_account(&Pubkey::new_unique()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("No recorded response for getAccountInfo"),
+        "{err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn replay_repeated_request_in_order() {
+    let recording = tempfile::NamedTempFile::new().unwrap();
+    let dst = Pubkey::new_unique();
+
+    let (bc, payer, rbh) = ProgramTest::default().start().await;
+    let (port, _jh) = BanksRpcServer::new(bc)
+        .with_recording(recording.path())
+        .unwrap()
+        .spawn_on_random_unused_port()
+        .await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+    assert!(client.get_account(&dst).is_err());
+    transfer_session(&client, &payer, &dst, rbh);
+
+    let (port, _jh) = ReplayRpcServer::from_file(recording.path())
+        .unwrap()
+        .spawn_on_random_unused_port()
+        .await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+    // same getAccountInfo request served in recorded order, last one repeated
+    assert!(client.get_account(&dst).is_err());
+    for _ in 0..2 {
+        assert_eq!(client.get_account(&dst).unwrap().lamports, LAMPORTS_PER_SOL);
+    }
+}
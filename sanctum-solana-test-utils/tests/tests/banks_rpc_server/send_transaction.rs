@@ -0,0 +1,634 @@
+use std::time::Duration;
+
+use sanctum_solana_cli_utils::{
+    durable_nonce_account_of, BuildTxArgs, ComputeBudgetArgs, HandleTxArgs, HandleTxOutput,
+    NonceArgs, RebroadcastArgs, RebroadcastOutcome, TxOutputFormat, TxSendMode,
+    TxSendingNonblockingRpcClient, TxSendingRpcClient,
+};
+use sanctum_solana_test_utils::{
+    banks_rpc_server::{BanksRpcServer, Fault, FaultRule, PrioritizationFeeSchedule},
+    ExtendedProgramTest,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_program::{
+    clock::MAX_PROCESSING_AGE, hash::Hash, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::ProgramTest;
+use solana_rpc_client_api::{
+    client_error::ErrorKind,
+    custom_error::JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE, request::RpcError,
+};
+use solana_sdk::{
+    commitment_config::CommitmentLevel,
+    compute_budget,
+    instruction::InstructionError,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    system_instruction::SystemError,
+    transaction::{Transaction, TransactionError},
+};
+use solana_transaction_status::TransactionConfirmationStatus;
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn send_transaction_basic() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+
+    let dst = Pubkey::new_unique();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    let sig = client.send_and_confirm_transaction(&tx).unwrap();
+
+    assert_eq!(sig, tx.signatures[0]);
+    assert_eq!(client.get_account(&dst).unwrap().lamports, LAMPORTS_PER_SOL);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn send_transaction_preflight_failure() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+
+    let payer_balance = client.get_account(&payer.pubkey()).unwrap().lamports;
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            payer_balance + 1,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    let err = client.send_transaction(&tx).unwrap_err();
+
+    match err.kind() {
+        ErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            assert_eq!(
+                *code,
+                JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE
+            )
+        }
+        _ => panic!("Unexpected err {err}"),
+    }
+    assert_eq!(
+        client.get_account(&payer.pubkey()).unwrap().lamports,
+        payer_balance
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handle_tx_send_actual() {
+    let (client, payer, _rbh) = setup(ProgramTest::default()).await;
+
+    let dst = Pubkey::new_unique();
+    let rbh = client.get_confirmed_blockhash().unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh.hash,
+    );
+    client
+        .handle_tx(&tx, TxSendMode::SendActual, HandleTxArgs::cli_default())
+        .unwrap();
+
+    assert_eq!(client.get_account(&dst).unwrap().lamports, LAMPORTS_PER_SOL);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handle_tx_json_output() {
+    let (client, payer, _rbh) = setup(ProgramTest::default()).await;
+
+    let rbh = client.get_confirmed_blockhash().unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh.hash,
+    );
+    let args = HandleTxArgs::cli_default().with_output_format(TxOutputFormat::Json);
+
+    let sim = client.handle_tx(&tx, TxSendMode::SimOnly, args).unwrap();
+    assert_eq!(sim.err, None);
+    assert!(sim.slot.is_some());
+    assert_eq!(sim.confirmation_status, None);
+    assert!(!sim.logs.unwrap().is_empty());
+
+    let sent = client.handle_tx(&tx, TxSendMode::SendActual, args).unwrap();
+    assert_eq!(sent.signature, Some(tx.signatures[0].to_string()));
+    assert_eq!(sent.err, None);
+    assert!(sent.slot.is_some());
+    assert!(sent
+        .confirmation_status
+        .as_ref()
+        .is_some_and(|status| *status != TransactionConfirmationStatus::Processed));
+    assert!(sent
+        .logs
+        .as_ref()
+        .unwrap()
+        .iter()
+        .any(|log| log.contains("success")));
+
+    let dumped = client.handle_tx(&tx, TxSendMode::DumpMsg, args).unwrap();
+    assert_eq!(dumped.tx_base64, sent.tx_base64);
+    assert_eq!(dumped.slot, None);
+
+    let json = serde_json::to_string(&sent).unwrap();
+    assert!(json.contains("\"txBase64\""));
+    assert_eq!(serde_json::from_str::<HandleTxOutput>(&json).unwrap(), sent);
+}
+
+/// Prints the json outputs of failed sends for [`handle_tx_json_output_send_err`],
+/// since stdout cannot be captured in-process
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "ran in a subprocess by handle_tx_json_output_send_err"]
+async fn handle_tx_json_output_send_err_print() {
+    let (bc, payer, _rbh) = ProgramTest::default().start().await;
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(bc).await;
+    let url = format!("http://127.0.0.1:{port}");
+    let client = RpcClient::new(url.clone());
+
+    let rbh = client.get_confirmed_blockhash().unwrap();
+    let failing_tx = |lamports| {
+        Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &Pubkey::new_unique(),
+                lamports,
+            )],
+            Some(&payer.pubkey()),
+            &[&payer],
+            rbh.hash,
+        )
+    };
+    let json = |args: HandleTxArgs| args.with_output_format(TxOutputFormat::Json);
+
+    let err = client
+        .handle_tx(
+            &failing_tx(u64::MAX),
+            TxSendMode::SendActual,
+            json(HandleTxArgs {
+                skip_preflight: false,
+                ..HandleTxArgs::cli_default()
+            }),
+        )
+        .unwrap_err();
+    assert!(err.get_transaction_error().is_some());
+
+    // lands and fails
+    let err = solana_client::nonblocking::rpc_client::RpcClient::new(url)
+        .handle_tx(
+            &failing_tx(u64::MAX - 1),
+            TxSendMode::SendActual,
+            json(HandleTxArgs::cli_default()),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.get_transaction_error().is_some());
+}
+
+#[test]
+fn handle_tx_json_output_send_err() {
+    let (_crate, module) = module_path!().split_once("::").unwrap();
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([
+            &format!("{module}::handle_tx_json_output_send_err_print"),
+            "--exact",
+            "--ignored",
+            "--nocapture",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // libtest may print the test name on the same line
+    let outputs: Vec<HandleTxOutput> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .filter_map(|l| {
+            l.find("{\"signature\"")
+                .map(|i| serde_json::from_str(&l[i..]).unwrap())
+        })
+        .collect();
+    let [preflight_failed, landed_failed] = outputs.try_into().unwrap();
+    for output in [&preflight_failed, &landed_failed] {
+        assert_eq!(
+            output.err,
+            Some(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(SystemError::ResultWithNegativeLamports as u32)
+            ))
+        );
+        assert!(output
+            .logs
+            .as_ref()
+            .unwrap()
+            .iter()
+            .any(|log| log.contains("insufficient lamports")));
+    }
+    assert_eq!(preflight_failed.slot, None);
+    assert!(landed_failed.slot.is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handle_tx_durable_nonce() {
+    let nonce_authority = Keypair::new();
+    let nonce_args = NonceArgs {
+        nonce_account: Pubkey::new_unique(),
+        nonce_authority: nonce_authority.pubkey(),
+    };
+    let (client, payer, _rbh) = setup(ProgramTest::default().add_nonce_account(
+        nonce_args.nonce_account,
+        nonce_args.nonce_authority,
+        Hash::new_unique(),
+    ))
+    .await;
+
+    assert!(client
+        .get_durable_nonce(&NonceArgs {
+            nonce_authority: Pubkey::new_unique(),
+            ..nonce_args
+        })
+        .is_err());
+    let nonce = client.get_durable_nonce(&nonce_args).unwrap();
+    assert_eq!(nonce.authority, nonce_authority.pubkey());
+
+    let dst = Pubkey::new_unique();
+    let nonce_tx = |lamports| {
+        Transaction::new_signed_with_payer(
+            &nonce_args.prepend_advance_nonce_ix([system_instruction::transfer(
+                &payer.pubkey(),
+                &dst,
+                lamports,
+            )]),
+            Some(&payer.pubkey()),
+            &[&payer, &nonce_authority],
+            nonce.durable_nonce,
+        )
+    };
+    let tx = nonce_tx(LAMPORTS_PER_SOL);
+    assert_eq!(
+        durable_nonce_account_of(&tx),
+        Some(nonce_args.nonce_account)
+    );
+
+    let output = client
+        .handle_tx(&tx, TxSendMode::SendActual, HandleTxArgs::cli_default())
+        .unwrap();
+    assert_eq!(output.signature, Some(tx.signatures[0].to_string()));
+    assert_eq!(client.get_account(&dst).unwrap().lamports, LAMPORTS_PER_SOL);
+    assert_ne!(
+        client.get_durable_nonce(&nonce_args).unwrap().durable_nonce,
+        nonce.durable_nonce
+    );
+
+    // stale nonce, skip preflight so that the tx is dropped instead of rejected
+    let err = client
+        .handle_tx(
+            &nonce_tx(1),
+            TxSendMode::SendActual,
+            HandleTxArgs::optimal_tx_send(CommitmentLevel::Confirmed),
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Durable nonce advanced"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn build_and_handle_tx_compute_budget() {
+    let from = Keypair::new();
+    let dst = Pubkey::new_unique();
+    let (bc, payer, _rbh) = ProgramTest::default()
+        .add_system_account(from.pubkey(), 2 * LAMPORTS_PER_SOL)
+        .start()
+        .await;
+    let (port, _jh) = BanksRpcServer::new(bc)
+        .with_prioritization_fees(PrioritizationFeeSchedule::default().with_default([(1, 1_000)]))
+        .spawn_on_random_unused_port()
+        .await;
+    let url = format!("http://127.0.0.1:{port}");
+    let client = RpcClient::new(url.clone());
+
+    let ixs = [system_instruction::transfer(
+        &from.pubkey(),
+        &dst,
+        LAMPORTS_PER_SOL,
+    )];
+    // duplicate and unsorted signers
+    let signers = [&from, &payer, &from];
+    let build_args = BuildTxArgs {
+        payer: payer.pubkey(),
+        ixs: &ixs,
+        luts: &[],
+        signers: &signers,
+        nonce: None,
+    };
+    let args = HandleTxArgs::cli_default().with_output_format(TxOutputFormat::Json);
+
+    let sim = solana_client::nonblocking::rpc_client::RpcClient::new(url)
+        .build_and_handle_tx(
+            build_args,
+            ComputeBudgetArgs::default(),
+            TxSendMode::SimOnly,
+            args,
+        )
+        .await
+        .unwrap();
+    assert_eq!(sim.err, None);
+
+    let sent = client
+        .build_and_handle_tx(
+            build_args,
+            ComputeBudgetArgs::default(),
+            TxSendMode::SendActual,
+            args,
+        )
+        .unwrap();
+    assert_eq!(sent.err, None);
+    assert!(sent
+        .logs
+        .unwrap()
+        .iter()
+        .any(|log| log.contains(&compute_budget::ID.to_string())));
+    assert!(sent.units_consumed.is_some());
+    assert_eq!(client.get_account(&dst).unwrap().lamports, LAMPORTS_PER_SOL);
+}
+
+const TEST_RESEND_INTERVAL: Duration = Duration::from_millis(10);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn build_and_rebroadcast_tx_confirmed() {
+    let nonce_args = NonceArgs {
+        nonce_account: Pubkey::new_unique(),
+        nonce_authority: Pubkey::new_unique(),
+    };
+    let dst = Pubkey::new_unique();
+    let (bc, payer, _rbh) = ProgramTest::default().start().await;
+    let (port, _jh) = BanksRpcServer::new(bc)
+        .with_prioritization_fees(PrioritizationFeeSchedule::default().with_default([(1, 1_000)]))
+        .spawn_on_random_unused_port()
+        .await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    let ixs = [system_instruction::transfer(
+        &payer.pubkey(),
+        &dst,
+        LAMPORTS_PER_SOL,
+    )];
+    let build_args = BuildTxArgs {
+        payer: payer.pubkey(),
+        ixs: &ixs,
+        luts: &[],
+        signers: &[&payer],
+        nonce: None,
+    };
+    let args = RebroadcastArgs {
+        resend_interval: TEST_RESEND_INTERVAL,
+        ..Default::default()
+    }
+    .with_max_attempts(3);
+
+    let (nonce_output, _err) = client
+        .build_and_rebroadcast_tx(
+            BuildTxArgs {
+                nonce: Some(nonce_args),
+                ..build_args
+            },
+            ComputeBudgetArgs::default(),
+            args,
+        )
+        .unwrap_err();
+    assert!(nonce_output.attempts.is_empty());
+
+    let output = client
+        .build_and_rebroadcast_tx(build_args, ComputeBudgetArgs::default(), args)
+        .unwrap();
+    assert_eq!(output.attempts.len(), 1);
+    let attempt = &output.attempts[0];
+    assert_eq!(attempt.sends, 1);
+    assert!(matches!(
+        attempt.outcome,
+        RebroadcastOutcome::Confirmed { .. }
+    ));
+    assert_eq!(output.confirmed_signature(), Some(attempt.signature));
+    assert_eq!(client.get_account(&dst).unwrap().lamports, LAMPORTS_PER_SOL);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rebroadcast_tx_failed_and_expired() {
+    let (client, payer, _rbh) = setup(ProgramTest::default()).await;
+    let args = RebroadcastArgs {
+        resend_interval: TEST_RESEND_INTERVAL,
+        skip_preflight: true,
+        ..Default::default()
+    };
+    let rbh = client.get_confirmed_blockhash().unwrap();
+    let transfer_tx = |lamports| {
+        Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &Pubkey::new_unique(),
+                lamports,
+            )],
+            Some(&payer.pubkey()),
+            &[&payer],
+            rbh.hash,
+        )
+    };
+
+    let payer_balance = client.get_account(&payer.pubkey()).unwrap().lamports;
+    let attempt = client
+        .rebroadcast_tx(
+            &transfer_tx(payer_balance + 1),
+            rbh.last_valid_blockheight,
+            args,
+        )
+        .unwrap();
+    match attempt.outcome {
+        RebroadcastOutcome::Failed { err, .. } => assert_eq!(
+            err,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(SystemError::ResultWithNegativeLamports as u32)
+            )
+        ),
+        outcome => panic!("Unexpected outcome {outcome:?}"),
+    }
+
+    // invalid signature txs are dropped without landing
+    let mut dropped_tx = transfer_tx(1);
+    dropped_tx.signatures[0] = Signature::default();
+    let block_height = client.get_epoch_info().unwrap().block_height;
+    let attempt = client
+        .rebroadcast_tx(&dropped_tx, block_height - 1, args)
+        .unwrap();
+    assert_eq!(attempt.outcome, RebroadcastOutcome::Expired);
+    assert_eq!(attempt.sends, 1);
+    assert_eq!(attempt.blockhash.hash, rbh.hash);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn build_and_rebroadcast_tx_resigns_on_expiry() {
+    let dst = Pubkey::new_unique();
+    let mut ctx = ProgramTest::default().start_with_context().await;
+    let server = BanksRpcServer::new(ctx.banks_client.clone())
+        .with_prioritization_fees(PrioritizationFeeSchedule::default().with_default([(1, 1_000)]))
+        .with_fault_rules(vec![
+            // used up by the first attempt's first status poll
+            FaultRule {
+                method: Some("getEpochInfo".to_owned()),
+                fault: Fault::Delay(Duration::ZERO),
+                times: Some(1),
+            },
+            FaultRule {
+                method: Some("sendTransaction".to_owned()),
+                fault: Fault::DropTransaction,
+                times: None,
+            },
+        ]);
+    let faults = server.fault_injector();
+    let (port, _jh) = server.spawn_on_random_unused_port().await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    let payer = ctx.payer.insecure_clone();
+    let rebroadcast = tokio::task::spawn_blocking(move || {
+        let ixs = [system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )];
+        client
+            .build_and_rebroadcast_tx(
+                BuildTxArgs {
+                    payer: payer.pubkey(),
+                    ixs: &ixs,
+                    luts: &[],
+                    signers: &[&payer],
+                    nonce: None,
+                },
+                ComputeBudgetArgs::default(),
+                RebroadcastArgs {
+                    resend_interval: TEST_RESEND_INTERVAL,
+                    skip_preflight: true,
+                    ..Default::default()
+                }
+                .with_max_attempts(2),
+            )
+            .unwrap()
+    });
+
+    while faults.rules().len() == 2 && !rebroadcast.is_finished() {
+        tokio::time::sleep(TEST_RESEND_INTERVAL).await;
+    }
+    // each warp increments block height by 1
+    let slot = ctx.banks_client.get_root_slot().await.unwrap();
+    for i in 1..=MAX_PROCESSING_AGE as u64 + 1 {
+        ctx.warp_to_slot(slot + i).unwrap();
+    }
+    // let the first attempt expire before letting the second attempt land
+    tokio::time::sleep(20 * TEST_RESEND_INTERVAL).await;
+    faults.clear();
+
+    let output = rebroadcast.await.unwrap();
+    let [expired, confirmed] = output.attempts.as_slice() else {
+        panic!("Unexpected attempts {:?}", output.attempts);
+    };
+    assert_eq!(expired.outcome, RebroadcastOutcome::Expired);
+    assert!(expired.sends > 1);
+    assert!(matches!(
+        confirmed.outcome,
+        RebroadcastOutcome::Confirmed { .. }
+    ));
+    assert_ne!(confirmed.blockhash.hash, expired.blockhash.hash);
+    assert!(confirmed.blockhash.last_valid_blockheight > expired.blockhash.last_valid_blockheight);
+    assert_ne!(confirmed.signature, expired.signature);
+    assert_eq!(output.confirmed_signature(), Some(confirmed.signature));
+    assert_eq!(
+        ctx.banks_client.get_balance(dst).await.unwrap(),
+        LAMPORTS_PER_SOL
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn build_and_rebroadcast_tx_returns_attempts_on_err() {
+    let mut ctx = ProgramTest::default().start_with_context().await;
+    let server = BanksRpcServer::new(ctx.banks_client.clone())
+        .with_prioritization_fees(PrioritizationFeeSchedule::default().with_default([(1, 1_000)]))
+        .with_fault_rules(vec![
+            FaultRule {
+                method: Some("sendTransaction".to_owned()),
+                fault: Fault::DropTransaction,
+                times: None,
+            },
+            // failed polls should not end the first attempt
+            FaultRule {
+                method: Some("getSignatureStatuses".to_owned()),
+                fault: Fault::HttpStatus(503),
+                times: Some(2),
+            },
+        ]);
+    let faults = server.fault_injector();
+    let (port, _jh) = server.spawn_on_random_unused_port().await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    let payer = ctx.payer.insecure_clone();
+    let rebroadcast = tokio::task::spawn_blocking(move || {
+        let ixs = [system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL,
+        )];
+        client.build_and_rebroadcast_tx(
+            BuildTxArgs {
+                payer: payer.pubkey(),
+                ixs: &ixs,
+                luts: &[],
+                signers: &[&payer],
+                nonce: None,
+            },
+            ComputeBudgetArgs::default(),
+            RebroadcastArgs {
+                resend_interval: TEST_RESEND_INTERVAL,
+                skip_preflight: true,
+                ..Default::default()
+            }
+            .with_max_attempts(2),
+        )
+    });
+
+    while faults.rules().len() == 2 && !rebroadcast.is_finished() {
+        tokio::time::sleep(TEST_RESEND_INTERVAL).await;
+    }
+    // fail the second attempt's blockhash fetch
+    faults.push_rule(FaultRule {
+        method: Some("getLatestBlockhash".to_owned()),
+        fault: Fault::HttpStatus(503),
+        times: None,
+    });
+    let slot = ctx.banks_client.get_root_slot().await.unwrap();
+    for i in 1..=MAX_PROCESSING_AGE as u64 + 1 {
+        ctx.warp_to_slot(slot + i).unwrap();
+    }
+
+    let (output, _err) = rebroadcast.await.unwrap().unwrap_err();
+    let [expired] = output.attempts.as_slice() else {
+        panic!("Unexpected attempts {:?}", output.attempts);
+    };
+    assert_eq!(expired.outcome, RebroadcastOutcome::Expired);
+}
@@ -0,0 +1,147 @@
+use solana_account_decoder::UiAccountEncoding;
+use solana_program::{
+    hash::Hash, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction, system_program,
+};
+use solana_program_test::ProgramTest;
+use solana_rpc_client_api::{
+    client_error::ErrorKind,
+    config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig},
+    custom_error::JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_VERIFICATION_FAILURE,
+    request::RpcError,
+};
+use solana_sdk::{
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::tests::banks_rpc_server::common::setup;
+
+fn transfer_tx(payer: &Keypair, dst: &Pubkey, lamports: u64, rbh: Hash) -> Transaction {
+    Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(&payer.pubkey(), dst, lamports)],
+        Some(&payer.pubkey()),
+        &[payer],
+        rbh,
+    )
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn simulate_transaction_basic() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+
+    let dst = Pubkey::new_unique();
+    let tx = transfer_tx(&payer, &dst, LAMPORTS_PER_SOL, rbh);
+    let res = client.simulate_transaction(&tx).unwrap().value;
+
+    assert!(res.err.is_none());
+    assert!(res.units_consumed.unwrap() > 0);
+    assert!(!res.logs.unwrap().is_empty());
+    assert!(res.accounts.is_none());
+    // simulation should not modify state
+    assert!(client.get_account(&dst).is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn simulate_transaction_err() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+
+    let payer_balance = client.get_account(&payer.pubkey()).unwrap().lamports;
+    let tx = transfer_tx(&payer, &Pubkey::new_unique(), payer_balance + 1, rbh);
+    let res = client.simulate_transaction(&tx).unwrap().value;
+
+    assert!(matches!(
+        res.err.unwrap(),
+        TransactionError::InstructionError(0, _)
+    ));
+    assert!(res.accounts.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn simulate_transaction_sig_verify() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+
+    let mut tx = transfer_tx(&payer, &Pubkey::new_unique(), LAMPORTS_PER_SOL, rbh);
+    let cfg = RpcSimulateTransactionConfig {
+        sig_verify: true,
+        ..Default::default()
+    };
+    assert!(client
+        .simulate_transaction_with_config(&tx, cfg.clone())
+        .unwrap()
+        .value
+        .err
+        .is_none());
+
+    tx.signatures[0] = Signature::default();
+    let err = client
+        .simulate_transaction_with_config(&tx, cfg)
+        .unwrap_err();
+    match err.kind() {
+        ErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            assert_eq!(
+                *code,
+                JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_VERIFICATION_FAILURE
+            )
+        }
+        _ => panic!("Unexpected err {err}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn simulate_transaction_replace_recent_blockhash() {
+    let (client, payer, _rbh) = setup(ProgramTest::default()).await;
+
+    let tx = transfer_tx(
+        &payer,
+        &Pubkey::new_unique(),
+        LAMPORTS_PER_SOL,
+        Hash::new_unique(),
+    );
+    let res = client
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                replace_recent_blockhash: true,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .value;
+
+    assert!(res.err.is_none());
+    assert_eq!(
+        res.replacement_blockhash.unwrap().blockhash,
+        client.get_latest_blockhash().unwrap().to_string()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn simulate_transaction_accounts_unsupported() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+
+    let dst = Pubkey::new_unique();
+    let tx = transfer_tx(&payer, &dst, LAMPORTS_PER_SOL, rbh);
+    // post-simulation account states are not available from BanksClient
+    for address in [system_program::ID, dst] {
+        let err = client
+            .simulate_transaction_with_config(
+                &tx,
+                RpcSimulateTransactionConfig {
+                    accounts: Some(RpcSimulateTransactionAccountsConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        addresses: vec![address.to_string()],
+                    }),
+                    ..Default::default()
+                },
+            )
+            .unwrap_err();
+        match err.kind() {
+            ErrorKind::RpcError(RpcError::RpcResponseError { code, message, .. }) => {
+                assert_eq!(*code, jsonrpc_core::ErrorCode::InvalidParams.code());
+                assert!(message.contains("accounts config is not supported"));
+            }
+            _ => panic!("Unexpected err {err}"),
+        }
+    }
+}
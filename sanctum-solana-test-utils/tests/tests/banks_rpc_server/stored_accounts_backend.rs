@@ -0,0 +1,84 @@
+use sanctum_solana_test_utils::banks_rpc_server::{BanksRpcServer, StoredAccountsBackend};
+use solana_client::rpc_client::RpcClient;
+use solana_program::{
+    hash::Hash, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction, system_program,
+};
+use solana_sdk::{account::Account, signature::Keypair, signer::Signer, transaction::Transaction};
+
+use crate::tests::banks_rpc_server::common::{mint_account, token_account, TOKEN_PROGRAMS};
+
+async fn setup(backend: StoredAccountsBackend) -> RpcClient {
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(backend).await;
+    RpcClient::new(format!("http://127.0.0.1:{port}"))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stored_accounts_backend_serves_fixtures() {
+    let [mint, owner, token_acc, wallet] = [(); 4].map(|_| Pubkey::new_unique());
+    let token_program = TOKEN_PROGRAMS[0];
+    let backend = StoredAccountsBackend::new([
+        (mint, mint_account(token_program, 1_000, 6)),
+        (
+            token_acc,
+            token_account(token_program, mint, owner, 1_000, None),
+        ),
+        (
+            wallet,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                owner: system_program::ID,
+                ..Default::default()
+            },
+        ),
+    ]);
+    let client = setup(backend.clone()).await;
+
+    assert_eq!(client.get_balance(&wallet).unwrap(), LAMPORTS_PER_SOL);
+    assert_eq!(
+        client.get_account(&mint).unwrap(),
+        mint_account(token_program, 1_000, 6)
+    );
+    assert_eq!(
+        client.get_token_account_balance(&token_acc).unwrap().amount,
+        "1000"
+    );
+
+    // accounts can be enumerated without registering them
+    let program_accounts = client.get_program_accounts(&token_program).unwrap();
+    let mut keys: Vec<Pubkey> = program_accounts.into_iter().map(|(k, _)| k).collect();
+    keys.sort();
+    let mut expected = vec![mint, token_acc];
+    expected.sort();
+    assert_eq!(keys, expected);
+
+    // state can be changed after spawning
+    backend.remove_account(&wallet);
+    assert_eq!(client.get_balance(&wallet).unwrap(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stored_accounts_backend_slot_and_blockhash() {
+    let backend = StoredAccountsBackend::new::<Account>([]);
+    let blockhash = Hash::new_unique();
+    backend.set_blockhash(blockhash);
+    backend.set_slot(1_000);
+    let client = setup(backend).await;
+
+    assert_eq!(client.get_slot().unwrap(), 1_000);
+    assert_eq!(client.get_epoch_info().unwrap().absolute_slot, 1_000);
+    assert_eq!(client.get_latest_blockhash().unwrap(), blockhash);
+
+    let payer = Keypair::new();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            1,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        blockhash,
+    );
+    assert_eq!(client.get_fee_for_message(&tx.message).unwrap(), 5_000);
+    assert!(client.send_transaction(&tx).is_err());
+}
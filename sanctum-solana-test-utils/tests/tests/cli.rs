@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use assert_cmd::Command;
+use data_encoding::BASE64;
+use sanctum_solana_test_utils::{
+    cli::{assert_all_txs_success_nonempty, ExtendedCommand},
+    cosign_versioned_tx, ExtendedProgramTest,
+};
+use solana_program::{
+    message::{v0, VersionedMessage},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::VersionedTransaction,
+};
+
+fn sh(script: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(script);
+    cmd
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn exec_partially_signed_b64_txs() {
+    let from = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    let (mut bc, payer, rbh) = ProgramTest::default()
+        .add_system_account(from.pubkey(), 2 * LAMPORTS_PER_SOL)
+        .start()
+        .await;
+
+    let message = VersionedMessage::V0(
+        v0::Message::try_compile(
+            &payer.pubkey(),
+            &[system_instruction::transfer(
+                &from.pubkey(),
+                &recipient,
+                LAMPORTS_PER_SOL,
+            )],
+            &[],
+            rbh,
+        )
+        .unwrap(),
+    );
+    let mut tx = VersionedTransaction {
+        signatures: vec![],
+        message,
+    };
+    // CLI only has the fee payer's keypair
+    cosign_versioned_tx(&mut tx, &[&payer]);
+    assert_eq!(tx.signatures[1], Signature::default());
+    let b64 = BASE64.encode(&bincode::serialize(&tx).unwrap());
+
+    let res = sh(&format!("echo {b64}"))
+        .exec_b64_txs_cosigned(&mut bc, &[&from])
+        .await;
+    assert_all_txs_success_nonempty(&res);
+    assert_eq!(bc.get_balance(recipient).await.unwrap(), LAMPORTS_PER_SOL);
+}
+
+#[test]
+fn parse_json_stdout() {
+    let res: HashMap<String, u64> = sh(r#"echo '{"slot": 1, "epoch": 2}'"#).output_json();
+    assert_eq!(res["slot"], 1);
+    assert_eq!(res["epoch"], 2);
+}
+
+#[test]
+fn stderr_assertions() {
+    sh("echo 'Warning: deprecated' >&2").assert_stderr_contains("deprecated");
+    let output = sh("echo 'Error: insufficient funds' >&2; exit 1")
+        .assert_failure_stderr_contains("insufficient funds");
+    assert_eq!(output.status.code(), Some(1));
+}
@@ -0,0 +1,59 @@
+use sanctum_solana_test_utils::token::{
+    tokenkeg::TokenkegProgramTest, MockMintArgs, MockTokenAccountArgs,
+};
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::ProgramTest;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+/// A tokenkeg mint and 2 token accounts of it owned by `owner`
+pub struct TokenkegTransferFixture {
+    pub owner: Keypair,
+    pub mint: Pubkey,
+    pub src: Pubkey,
+    pub dst: Pubkey,
+}
+
+impl TokenkegTransferFixture {
+    pub fn new_unique() -> Self {
+        let [mint, src, dst] = [(); 3].map(|_| Pubkey::new_unique());
+        Self {
+            owner: Keypair::new(),
+            mint,
+            src,
+            dst,
+        }
+    }
+
+    /// Adds the mint with supply `amount`, `src` holding all of it and an empty `dst`
+    pub fn add_to(&self, pt: ProgramTest, amount: u64) -> ProgramTest {
+        let account_args = |amount| MockTokenAccountArgs {
+            mint: self.mint,
+            authority: self.owner.pubkey(),
+            amount,
+        };
+        pt.add_tokenkeg_mint_from_args(
+            self.mint,
+            MockMintArgs {
+                mint_authority: None,
+                freeze_authority: None,
+                supply: amount,
+                decimals: 9,
+            },
+        )
+        .add_tokenkeg_account_from_args(self.src, account_args(amount))
+        .add_tokenkeg_account_from_args(self.dst, account_args(0))
+    }
+
+    /// Transfer of `amount` from `src` to `dst`, signed by `owner`
+    pub fn transfer_ix(&self, amount: u64) -> Instruction {
+        spl_token::instruction::transfer(
+            &spl_token::ID,
+            &self.src,
+            &self.dst,
+            &self.owner.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap()
+    }
+}
@@ -0,0 +1,125 @@
+use sanctum_solana_test_utils::{CuBaseline, ExtendedBanksClient};
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::{signer::Signer, transaction::Transaction};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn report_failed_builtin_ix() {
+    let (mut bc, payer, rbh) = ProgramTest::default().start().await;
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            u64::MAX,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    let report = bc.exec_with_report(tx).await;
+
+    assert!(report.result.is_err());
+    assert_eq!(report.invocations.len(), 1);
+    let ix = &report.invocations[0];
+    assert_eq!(ix.program_id, solana_program::system_program::ID);
+    assert_eq!(ix.success, Some(false));
+    assert_eq!(report.ix_compute_units(), [None]);
+    report.assert_log_contains("insufficient lamports");
+    assert_eq!(report.return_data_padded::<8>(), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[should_panic(expected = "up from baseline 1")]
+async fn cu_baseline_exceeded_panics() {
+    let (mut bc, payer, rbh) = ProgramTest::default().start().await;
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    let report = bc.exec_with_report(tx).await;
+    report.assert_success();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cu-baseline.json");
+    CuBaseline {
+        path: path.clone(),
+        entries: [("system_transfer".to_owned(), 1)].into(),
+    }
+    .save();
+    report.assert_cu_baseline(&path, "system_transfer");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[should_panic(expected = "no baseline entry `system_transfer`")]
+async fn cu_baseline_missing_entry_panics() {
+    let (mut bc, payer, rbh) = ProgramTest::default().start().await;
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    let report = bc.exec_with_report(tx).await;
+    report.assert_success();
+
+    let dir = tempfile::tempdir().unwrap();
+    report.assert_cu_baseline(dir.path().join("cu-baseline.json"), "system_transfer");
+}
+
+#[cfg(feature = "token")]
+#[tokio::test(flavor = "multi_thread")]
+async fn report_tokenkeg_transfer_cus() {
+    use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+    use super::common::TokenkegTransferFixture;
+
+    let fixture = TokenkegTransferFixture::new_unique();
+    let (mut bc, payer, rbh) = fixture
+        .add_to(ProgramTest::default(), LAMPORTS_PER_SOL)
+        .start()
+        .await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(50_000),
+            fixture.transfer_ix(LAMPORTS_PER_SOL),
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &fixture.owner],
+        rbh,
+    );
+    let report = bc.exec_with_report(tx).await;
+
+    report.assert_success();
+    report.assert_log_contains("Instruction: Transfer");
+    let [budget_cus, transfer_cus] = report.ix_compute_units().try_into().unwrap();
+    assert_eq!(budget_cus, None);
+    assert!(transfer_cus.unwrap() > 0);
+    assert_eq!(report.invocations[1].program_id, spl_token::ID);
+    assert_eq!(report.invocations[1].logs, ["Instruction: Transfer"]);
+    report.assert_ix_cu_below(1, 10_000);
+    report.assert_cu_below(10_000);
+
+    let dir = tempfile::tempdir().unwrap();
+    let baseline = dir.path().join("cu-baseline.json");
+    CuBaseline {
+        path: baseline.clone(),
+        entries: [(
+            "tokenkeg_transfer".to_owned(),
+            report.compute_units_consumed,
+        )]
+        .into(),
+    }
+    .save();
+    report.assert_cu_baseline(&baseline, "tokenkeg_transfer");
+}
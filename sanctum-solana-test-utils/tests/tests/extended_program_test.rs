@@ -0,0 +1,97 @@
+use sanctum_solana_client_utils::to_est_cu_sim_tx;
+use sanctum_solana_test_utils::{
+    tx_ser_size, ExtendedBanksClient, ExtendedProgramTest, NONCE_ACCOUNT_LAMPORTS_PER_SIGNATURE,
+};
+use sanctum_system_program_lib::ReadonlyNonceAccount;
+use solana_program::{
+    hash::Hash,
+    message::{v0, VersionedMessage},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn v0_tx_with_address_lookup_table() {
+    let lut_addr = Pubkey::new_unique();
+    let recipients: Vec<Pubkey> = (0..20).map(|_| Pubkey::new_unique()).collect();
+    let (pt, lut) =
+        ProgramTest::default().add_address_lookup_table(lut_addr, None, recipients.clone(), None);
+    let (mut bc, payer, rbh) = pt.start().await;
+
+    let ixs: Vec<_> = recipients
+        .iter()
+        .map(|r| system_instruction::transfer(&payer.pubkey(), r, LAMPORTS_PER_SOL))
+        .collect();
+    let luts = [lut];
+    assert!(tx_ser_size(&payer.pubkey(), &ixs, &luts) < tx_ser_size(&payer.pubkey(), &ixs, &[]));
+
+    let sim_tx = to_est_cu_sim_tx(&payer.pubkey(), &ixs, &luts).unwrap();
+    assert_eq!(sim_tx.message.address_table_lookups().unwrap().len(), 1);
+
+    let tx = VersionedTransaction::try_new(
+        VersionedMessage::V0(v0::Message::try_compile(&payer.pubkey(), &ixs, &luts, rbh).unwrap()),
+        &[&payer],
+    )
+    .unwrap();
+    bc.process_transaction(tx).await.unwrap();
+    for r in recipients {
+        assert_eq!(bc.get_balance(r).await.unwrap(), LAMPORTS_PER_SOL);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn tx_with_durable_nonce() {
+    let nonce_addr = Pubkey::new_unique();
+    let nonce_authority = Keypair::new();
+    let durable_nonce = Hash::new_unique();
+    let pt = ProgramTest::default().add_nonce_account(
+        nonce_addr,
+        nonce_authority.pubkey(),
+        durable_nonce,
+    );
+    let (mut bc, payer, _rbh) = pt.start().await;
+
+    let nonce_acc = ReadonlyNonceAccount(bc.get_account_unwrapped(nonce_addr).await)
+        .try_into_valid()
+        .unwrap()
+        .try_into_initialized()
+        .unwrap();
+    assert_eq!(nonce_acc.nonce_data_authority(), nonce_authority.pubkey());
+    assert_eq!(nonce_acc.nonce_data_durable_nonce(), durable_nonce);
+    assert_eq!(
+        nonce_acc.nonce_data_fee_calculator_lamports_per_signature(),
+        NONCE_ACCOUNT_LAMPORTS_PER_SIGNATURE
+    );
+
+    let recipient = Pubkey::new_unique();
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            system_instruction::advance_nonce_account(&nonce_addr, &nonce_authority.pubkey()),
+            system_instruction::transfer(&payer.pubkey(), &recipient, LAMPORTS_PER_SOL),
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &nonce_authority],
+        durable_nonce,
+    );
+    // BanksClient::process_transaction() panics on blockhashes not in the blockhash queue
+    bc.process_transaction_with_metadata(tx)
+        .await
+        .unwrap()
+        .result
+        .unwrap();
+    assert_eq!(bc.get_balance(recipient).await.unwrap(), LAMPORTS_PER_SOL);
+
+    let advanced = ReadonlyNonceAccount(bc.get_account_unwrapped(nonce_addr).await)
+        .try_into_valid()
+        .unwrap()
+        .try_into_initialized()
+        .unwrap();
+    assert_ne!(advanced.nonce_data_durable_nonce(), durable_nonce);
+}
@@ -0,0 +1,90 @@
+use std::str::FromStr;
+
+use sanctum_solana_test_utils::{
+    ExtendedBanksClient, ExtendedProgramTest, ExtendedProgramTestContext, FixturesDirOpts,
+    KeyedUiAccount,
+};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    bpf_loader_upgradeable,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+/// program-test's bundled spl-token program, which is owned by the non-upgradeable BPF loader
+const TOKENKEG_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dump_and_load_fixtures_dir() {
+    let tokenkeg = Pubkey::from_str(TOKENKEG_ID).unwrap();
+    let [sys, missing, json_prog, so_prog] = [(); 4].map(|_| Pubkey::new_unique());
+    let dir = tempfile::tempdir().unwrap();
+    let accounts_dir = dir.path().join("nested").join("accounts");
+
+    let mut ctx = ProgramTest::default()
+        .add_system_account(sys, LAMPORTS_PER_SOL)
+        .start_with_context()
+        .await;
+    ctx.dump_accounts_to_dir(&[sys, missing, tokenkeg], &accounts_dir)
+        .await;
+    assert!(!accounts_dir.join(format!("{missing}.json")).exists());
+
+    // relocate the dumped spl-token program to json_prog
+    let tokenkeg_json = accounts_dir.join(format!("{tokenkeg}.json"));
+    let mut dumped = KeyedUiAccount::from_file(&tokenkeg_json);
+    dumped.pubkey = json_prog.to_string();
+    dumped.to_file(accounts_dir.join(format!("{json_prog}.json")));
+    std::fs::remove_file(tokenkeg_json).unwrap();
+
+    let elf = ctx.banks_client.get_account_data(tokenkeg).await;
+    std::fs::write(dir.path().join(format!("{so_prog}.so")), elf).unwrap();
+
+    let upgrade_authority = Pubkey::new_unique();
+    let mut ctx = ProgramTest::default()
+        .add_fixtures_dir(
+            dir.path(),
+            FixturesDirOpts {
+                upgrade_authority: Some(upgrade_authority),
+                ..Default::default()
+            },
+        )
+        .start_with_context()
+        .await;
+
+    assert_eq!(
+        ctx.banks_client.get_balance(sys).await.unwrap(),
+        LAMPORTS_PER_SOL
+    );
+    for program_id in [json_prog, so_prog] {
+        let prog_acc = ctx.banks_client.get_account_unwrapped(program_id).await;
+        assert_eq!(prog_acc.owner, bpf_loader_upgradeable::ID);
+        assert!(prog_acc.executable);
+        let (prog_data_addr, _bump) =
+            Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID);
+        let prog_data = ctx.banks_client.get_account_data(prog_data_addr).await;
+        assert_eq!(prog_data[13..45], upgrade_authority.to_bytes());
+
+        // spl-token's TokenError::InvalidInstruction proves the program was loaded and executed
+        let rbh = ctx.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[Instruction::new_with_bytes(program_id, &[255], vec![])],
+            Some(&ctx.payer.pubkey()),
+            &[&ctx.payer],
+            rbh,
+        );
+        let report = ctx.banks_client.exec_with_report(tx).await;
+        assert_eq!(
+            report.result,
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(12)
+            ))
+        );
+        assert!(report.invocations[0].compute_units_consumed.is_some());
+    }
+}
@@ -1,2 +1,25 @@
+mod accounts_snapshot;
+
+#[cfg(feature = "token")]
+mod common;
+
 #[cfg(feature = "banks-rpc-server")]
 mod banks_rpc_server;
+
+#[cfg(feature = "cli")]
+mod cli;
+
+mod execution_report;
+
+mod extended_program_test;
+
+mod fixtures_dir;
+
+#[cfg(feature = "stake")]
+mod stake;
+
+#[cfg(feature = "stake-pool")]
+mod stake_pool;
+
+#[cfg(feature = "token-2022")]
+mod token_2022;
@@ -0,0 +1,216 @@
+use sanctum_solana_test_utils::{
+    stake::{
+        cumulative_epoch_credits, mock_active_epoch_rewards, mock_stake_history,
+        ActiveOrActivatingUnlockedStakeAccount, MockVoteAccountArgs, SingleAuthorityAuthorized,
+        StakeProgramTest, StakeProgramTestContext,
+    },
+    ExtendedBanksClient,
+};
+use solana_program::{
+    clock::Clock,
+    epoch_rewards::EpochRewards,
+    instruction::InstructionError,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    stake::{
+        self,
+        instruction::StakeError,
+        state::{Delegation, StakeStateV2},
+    },
+    stake_history::StakeHistory,
+    vote::state::VoteState,
+};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+fn vote_args(epoch_credits: Vec<(u64, u64, u64)>) -> MockVoteAccountArgs {
+    MockVoteAccountArgs {
+        node_pubkey: Pubkey::new_unique(),
+        authorized_voter: Pubkey::new_unique(),
+        authorized_withdrawer: Pubkey::new_unique(),
+        commission: 5,
+        epoch_credits,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vote_account_roundtrip() {
+    let vote = Pubkey::new_unique();
+    let args = vote_args(cumulative_epoch_credits([(1, 100), (2, 200)]));
+    let voter = args.authorized_voter;
+    let pt = ProgramTest::default().add_vote_account(vote, args);
+    let (mut bc, _payer, _rbh) = pt.start().await;
+
+    let vote_acc = bc.get_account_unwrapped(vote).await;
+    assert_eq!(vote_acc.owner, solana_program::vote::program::ID);
+    let mut vote_state = VoteState::deserialize(&vote_acc.data).unwrap();
+    assert_eq!(vote_state.commission, 5);
+    assert_eq!(vote_state.epoch_credits, [(1, 100, 0), (2, 300, 100)]);
+    assert_eq!(
+        vote_state.get_and_update_authorized_voter(2).unwrap(),
+        voter
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deactivate_delinquent_with_mock_vote_accounts() {
+    const CURRENT_EPOCH: u64 = 10;
+
+    let [stake_acc, delinquent_vote, reference_vote] = [(); 3].map(|_| Pubkey::new_unique());
+    let pt = ProgramTest::default()
+        .add_vote_account(
+            delinquent_vote,
+            vote_args(cumulative_epoch_credits((1..=4).map(|e| (e, 100)))),
+        )
+        .add_vote_account(
+            reference_vote,
+            vote_args(cumulative_epoch_credits(
+                (CURRENT_EPOCH - 4..=CURRENT_EPOCH).map(|e| (e, 100)),
+            )),
+        )
+        .add_active_unlocked_stake_account(
+            stake_acc,
+            ActiveOrActivatingUnlockedStakeAccount {
+                staked_lamports: LAMPORTS_PER_SOL,
+                voter: delinquent_vote,
+                authorized: SingleAuthorityAuthorized(Pubkey::new_unique()).into(),
+                activation_epoch: 0,
+                credits_observed: 0,
+            },
+        );
+    let mut ctx = pt.start_with_context().await;
+    ctx.warp_to_epoch(CURRENT_EPOCH).unwrap();
+    ctx.warp_forward_force_reward_interval_end().unwrap();
+
+    let rbh = ctx.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[stake::instruction::deactivate_delinquent_stake(
+            &stake_acc,
+            &delinquent_vote,
+            &reference_vote,
+        )],
+        Some(&ctx.payer.pubkey()),
+        &[&ctx.payer],
+        rbh,
+    );
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let stake_acc = ctx.banks_client.get_account_unwrapped(stake_acc).await;
+    let stake = bincode::deserialize::<StakeStateV2>(&stake_acc.data)
+        .unwrap()
+        .stake()
+        .unwrap();
+    assert_eq!(stake.delegation.deactivation_epoch, CURRENT_EPOCH);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stake_history_partially_activated() {
+    const CURRENT_EPOCH: u64 = 3;
+
+    let cluster = Delegation {
+        stake: 1_000 * LAMPORTS_PER_SOL,
+        activation_epoch: u64::MAX,
+        ..Delegation::default()
+    };
+    let activating = Delegation {
+        voter_pubkey: Pubkey::new_unique(),
+        stake: 1_000 * LAMPORTS_PER_SOL,
+        activation_epoch: 1,
+        ..Delegation::default()
+    };
+    let deactivating = Delegation {
+        voter_pubkey: Pubkey::new_unique(),
+        stake: 500 * LAMPORTS_PER_SOL,
+        activation_epoch: u64::MAX,
+        deactivation_epoch: 2,
+        ..Delegation::default()
+    };
+    let delegations = [cluster, activating, deactivating];
+    let history = mock_stake_history(&delegations, CURRENT_EPOCH, Some(0));
+    assert_eq!(history.len(), 3);
+
+    let status = activating.stake_activating_and_deactivating(CURRENT_EPOCH, &history, Some(0));
+    assert!(status.effective > 0);
+    assert!(status.activating > 0);
+    let status = deactivating.stake_activating_and_deactivating(CURRENT_EPOCH, &history, Some(0));
+    assert!(status.effective > 0);
+    assert!(status.deactivating > 0);
+
+    let mut ctx = ProgramTest::default().start_with_context().await;
+    let installed = ctx.warp_to_epoch_with_stake_history(CURRENT_EPOCH, &delegations, Some(0));
+    assert_eq!(installed, history);
+    let on_chain: StakeHistory = ctx.banks_client.get_sysvar().await.unwrap();
+    assert_eq!(on_chain, history);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn set_clock_to_epoch() {
+    const EPOCH: u64 = 7;
+
+    let mut ctx = ProgramTest::default().start_with_context().await;
+    let clock = ctx.set_clock_to_epoch(EPOCH);
+    assert_eq!(clock.epoch, EPOCH);
+    assert_eq!(
+        clock.slot,
+        ctx.genesis_config()
+            .epoch_schedule
+            .get_first_slot_in_epoch(EPOCH)
+    );
+    let on_chain: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    assert_eq!(on_chain, clock);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn active_epoch_rewards_blocks_stake_ixs() {
+    let stake_acc = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let pt = ProgramTest::default().add_active_unlocked_stake_account(
+        stake_acc,
+        ActiveOrActivatingUnlockedStakeAccount {
+            staked_lamports: LAMPORTS_PER_SOL,
+            voter: Pubkey::new_unique(),
+            authorized: SingleAuthorityAuthorized(authority.pubkey()).into(),
+            activation_epoch: 0,
+            credits_observed: 0,
+        },
+    );
+    let mut ctx = pt.start_with_context().await;
+    let payer = ctx.payer.insecure_clone();
+    let deactivate_tx = |rbh| {
+        Transaction::new_signed_with_payer(
+            &[stake::instruction::deactivate_stake(
+                &stake_acc,
+                &authority.pubkey(),
+            )],
+            Some(&payer.pubkey()),
+            &[&payer, &authority],
+            rbh,
+        )
+    };
+
+    ctx.set_sysvar(&mock_active_epoch_rewards(0, LAMPORTS_PER_SOL));
+    let err = ctx
+        .banks_client
+        .process_transaction(deactivate_tx(ctx.last_blockhash))
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(StakeError::EpochRewardsActive as u32)
+        )
+    );
+
+    ctx.set_sysvar(&EpochRewards::default());
+    let rbh = ctx.get_new_latest_blockhash().await.unwrap();
+    ctx.banks_client
+        .process_transaction(deactivate_tx(rbh))
+        .await
+        .unwrap();
+}
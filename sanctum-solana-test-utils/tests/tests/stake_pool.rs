@@ -0,0 +1,212 @@
+use sanctum_solana_test_utils::{
+    est_rent_exempt_lamports,
+    stake_pool::{MockSplStakePoolArgs, MockValidatorArgs, StakePoolProgramTest},
+    ExtendedBanksClient,
+};
+use sanctum_spl_stake_pool_lib::{
+    account_resolvers::UpdateStakePoolBalance, deserialize_stake_pool_checked,
+    deserialize_validator_list_checked,
+};
+use solana_program::{
+    native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey, stake::state::StakeStateV2,
+};
+use solana_program_test::ProgramTest;
+use solana_readonly_account::keyed::Keyed;
+use spl_stake_pool_interface::StakeStatus;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn spl_stake_pool_accounts_consistent() {
+    let program_id = Pubkey::new_unique();
+    let [active_vote, transient_vote, deactivating_vote] = [(); 3].map(|_| Pubkey::new_unique());
+    let mut args = MockSplStakePoolArgs::new_unique(
+        program_id,
+        vec![
+            MockValidatorArgs::active(active_vote, 10 * LAMPORTS_PER_SOL),
+            MockValidatorArgs {
+                transient_stake_lamports: 2 * LAMPORTS_PER_SOL,
+                transient_seed_suffix: 1,
+                ..MockValidatorArgs::active(transient_vote, 5 * LAMPORTS_PER_SOL)
+            },
+            MockValidatorArgs {
+                transient_stake_lamports: LAMPORTS_PER_SOL,
+                status: StakeStatus::DeactivatingAll,
+                ..MockValidatorArgs::active(deactivating_vote, 3 * LAMPORTS_PER_SOL)
+            },
+        ],
+    );
+    args.reserve_lamports = LAMPORTS_PER_SOL;
+    args.pool_token_supply = 20 * LAMPORTS_PER_SOL;
+    args.max_validators = 10;
+
+    let (pt, handle) = ProgramTest::default().add_spl_stake_pool(args);
+    let (mut bc, _payer, _rbh) = pt.start().await;
+
+    let pool_acc = bc.get_account_unwrapped(handle.stake_pool).await;
+    assert_eq!(pool_acc.owner, program_id);
+    let pool = deserialize_stake_pool_checked(&pool_acc.data).unwrap();
+    assert_eq!(pool, handle.stake_pool_state);
+    assert_eq!(pool.total_lamports, 22 * LAMPORTS_PER_SOL);
+
+    let list_acc = bc.get_account_unwrapped(handle.validator_list).await;
+    let list = deserialize_validator_list_checked(&list_acc.data).unwrap();
+    assert_eq!(list, handle.validator_list_state);
+    assert_eq!(list.header.max_validators, 10);
+    assert_eq!(list.validators.len(), 3);
+
+    let reserve = bc.get_account_unwrapped(handle.reserve_stake).await;
+    assert_eq!(
+        reserve.lamports,
+        est_rent_exempt_lamports(StakeStateV2::size_of()) + LAMPORTS_PER_SOL
+    );
+
+    for (keys, info) in handle.validators.iter().zip(list.validators.iter()) {
+        let vsa = bc.get_account_unwrapped(keys.validator_stake_account).await;
+        assert_eq!(vsa.lamports, info.active_stake_lamports);
+        let stake = bincode::deserialize::<StakeStateV2>(&vsa.data)
+            .unwrap()
+            .stake()
+            .unwrap();
+        assert_eq!(stake.delegation.voter_pubkey, keys.vote);
+        if info.transient_stake_lamports > 0 {
+            let tsa = bc.get_account_unwrapped(keys.transient_stake_account).await;
+            assert_eq!(tsa.lamports, info.transient_stake_lamports);
+        } else {
+            bc.assert_account_not_exist(keys.transient_stake_account)
+                .await;
+        }
+    }
+
+    let mint = bc.get_account_unwrapped(handle.pool_mint).await;
+    let mint = spl_token::state::Mint::unpack(&mint.data).unwrap();
+    assert_eq!(mint.supply, 20 * LAMPORTS_PER_SOL);
+    assert_eq!(mint.mint_authority, Some(handle.withdraw_authority).into());
+
+    let keys = UpdateStakePoolBalance {
+        stake_pool: Keyed {
+            pubkey: handle.stake_pool,
+            account: pool_acc,
+        },
+    }
+    .resolve_for_prog(&program_id)
+    .unwrap();
+    assert_eq!(keys.withdraw_authority, handle.withdraw_authority);
+    assert_eq!(keys.reserve_stake, handle.reserve_stake);
+    assert_eq!(keys.manager_fee_account, handle.manager_fee_account);
+}
+
+#[test]
+#[should_panic(expected = "transient_stake_lamports 1 < minimum")]
+fn spl_stake_pool_transient_below_rent_exempt_panics() {
+    let args = MockSplStakePoolArgs::new_unique(
+        Pubkey::new_unique(),
+        vec![MockValidatorArgs {
+            transient_stake_lamports: 1,
+            ..MockValidatorArgs::active(Pubkey::new_unique(), LAMPORTS_PER_SOL)
+        }],
+    );
+    ProgramTest::default().add_spl_stake_pool(args);
+}
+
+#[test]
+#[should_panic(expected = "active_stake_lamports 1 < minimum")]
+fn spl_stake_pool_active_below_rent_exempt_panics() {
+    let args = MockSplStakePoolArgs::new_unique(
+        Pubkey::new_unique(),
+        vec![MockValidatorArgs::active(Pubkey::new_unique(), 1)],
+    );
+    ProgramTest::default().add_spl_stake_pool(args);
+}
+
+#[cfg(feature = "proptest")]
+mod proptests {
+    use borsh::BorshSerialize;
+    use proptest::prelude::*;
+    use sanctum_solana_test_utils::stake_pool::proptest_utils::{
+        stake_pool, stake_pool_with_validator_list, validator_list_total_lamports,
+    };
+    use sanctum_spl_stake_pool_lib::{
+        deserialize_stake_pool_checked, CmpFee, QuoteDepositSol, QuoteDepositStake,
+        StakeAccountDataForQuoting,
+    };
+    use spl_stake_pool_interface::{FutureEpochFee, StakePool};
+
+    /// mint ratio of the spl stake pool program, with 1:1 for empty pools
+    fn expected_pool_tokens(pool: &StakePool, lamports: u64) -> u64 {
+        if pool.total_lamports == 0 || pool.pool_token_supply == 0 {
+            return lamports;
+        }
+        (u128::from(lamports) * u128::from(pool.pool_token_supply)
+            / u128::from(pool.total_lamports))
+        .try_into()
+        .unwrap()
+    }
+
+    proptest! {
+        #[test]
+        fn stake_pool_with_validator_list_consistent(
+            (pool, list) in stake_pool_with_validator_list(8)
+        ) {
+            prop_assert!(list.validators.len() <= 8);
+            prop_assert!(pool.total_lamports >= validator_list_total_lamports(&list));
+            prop_assert!(pool.pool_token_supply <= pool.total_lamports);
+            prop_assert_eq!(pool.pool_token_supply == 0, pool.total_lamports == 0);
+            for v in list.validators.iter() {
+                prop_assert_eq!(v.last_update_epoch, pool.last_update_epoch);
+            }
+            for fee in [
+                &pool.epoch_fee,
+                &pool.stake_deposit_fee,
+                &pool.stake_withdrawal_fee,
+                &pool.sol_deposit_fee,
+                &pool.sol_withdrawal_fee,
+            ] {
+                prop_assert!(CmpFee(fee).is_zero() || fee.numerator <= fee.denominator);
+            }
+            for future_fee in [
+                &pool.next_epoch_fee,
+                &pool.next_stake_withdrawal_fee,
+                &pool.next_sol_withdrawal_fee,
+            ] {
+                if let FutureEpochFee::One { fee } | FutureEpochFee::Two { fee } = future_fee {
+                    prop_assert!(fee.numerator <= fee.denominator);
+                }
+            }
+
+            let mut data = Vec::new();
+            pool.serialize(&mut data).unwrap();
+            prop_assert_eq!(deserialize_stake_pool_checked(&data).unwrap(), pool);
+        }
+
+        #[test]
+        fn quote_deposit_sol_splits_minted_pool_tokens(
+            pool in stake_pool(),
+            lamports_in in 1..=u64::MAX / 2,
+        ) {
+            if let Ok(q) = pool.quote_deposit_sol(lamports_in) {
+                prop_assert!(q.user > 0);
+                prop_assert_eq!(
+                    u128::from(q.user) + u128::from(q.referrer) + u128::from(q.manager),
+                    u128::from(expected_pool_tokens(&pool, lamports_in))
+                );
+            }
+        }
+
+        #[test]
+        fn quote_deposit_stake_splits_minted_pool_tokens(
+            pool in stake_pool(),
+            staked_lamports in 0..=u64::MAX / 4,
+            unstaked_lamports in 0..=u64::MAX / 4,
+        ) {
+            let stake_account = StakeAccountDataForQuoting {
+                staked_lamports,
+                unstaked_lamports,
+            };
+            if let Ok(q) = pool.quote_deposit_stake(&stake_account) {
+                prop_assert_eq!(
+                    u128::from(q.user) + u128::from(q.referrer) + u128::from(q.manager),
+                    u128::from(expected_pool_tokens(&pool, staked_lamports + unstaked_lamports))
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use sanctum_solana_test_utils::{
+    est_rent_exempt_lamports,
+    token::{
+        token_2022::{
+            MockInterestBearingConfigArgs, MockMetadataPointerArgs, MockToken22AccountArgs,
+            MockToken22AccountExtensions, MockToken22MintArgs, MockToken22MintExtensions,
+            MockTokenMetadataArgs, MockTransferFeeConfigArgs, Token2022ProgramTest,
+        },
+        MockMintArgs, MockTokenAccountArgs,
+    },
+    ExtendedBanksClient,
+};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTest;
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use spl_token_2022::{
+    extension::{
+        default_account_state::DefaultAccountState,
+        immutable_owner::ImmutableOwner,
+        interest_bearing_mint::InterestBearingConfig,
+        metadata_pointer::MetadataPointer,
+        mint_close_authority::MintCloseAuthority,
+        non_transferable::{NonTransferable, NonTransferableAccount},
+        permanent_delegate::PermanentDelegate,
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensions, StateWithExtensionsOwned,
+    },
+    state::{Account, AccountState, Mint},
+};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn mint_and_account_with_extensions_roundtrip() {
+    let mint = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let token_acc = Pubkey::new_unique();
+    let pt = ProgramTest::default()
+        .add_token22_mint_from_args(
+            mint,
+            MockToken22MintArgs {
+                mint: MockMintArgs {
+                    mint_authority: Some(authority),
+                    freeze_authority: Some(authority),
+                    supply: 1_000,
+                    decimals: 9,
+                },
+                extensions: MockToken22MintExtensions {
+                    interest_bearing_config: Some(MockInterestBearingConfigArgs {
+                        rate_authority: Some(authority),
+                        initialization_timestamp: 1,
+                        current_rate: 500,
+                    }),
+                    close_authority: Some(authority),
+                    permanent_delegate: Some(authority),
+                    metadata_pointer: Some(MockMetadataPointerArgs {
+                        authority: Some(authority),
+                        metadata_address: Some(mint),
+                    }),
+                    token_metadata: Some(MockTokenMetadataArgs {
+                        update_authority: Some(authority),
+                        name: "Test Token".to_owned(),
+                        symbol: "TEST".to_owned(),
+                        uri: "https://example.com".to_owned(),
+                        additional_metadata: vec![("k".to_owned(), "v".to_owned())],
+                    }),
+                    non_transferable: true,
+                    default_account_state: Some(AccountState::Frozen),
+                    ..Default::default()
+                },
+            },
+        )
+        .add_token22_account_from_args(
+            token_acc,
+            MockToken22AccountArgs {
+                account: MockTokenAccountArgs {
+                    mint,
+                    authority,
+                    amount: 1_000,
+                },
+                state: AccountState::Frozen,
+                extensions: MockToken22AccountExtensions {
+                    immutable_owner: true,
+                    non_transferable: true,
+                    ..Default::default()
+                },
+            },
+        );
+    let (mut bc, _payer, _rbh) = pt.start().await;
+
+    let mint_acc = bc.get_account_unwrapped(mint).await;
+    assert_eq!(mint_acc.owner, spl_token_2022::ID);
+    assert_eq!(
+        mint_acc.lamports,
+        est_rent_exempt_lamports(mint_acc.data.len())
+    );
+    let s = StateWithExtensionsOwned::<Mint>::unpack(mint_acc.data).unwrap();
+    assert_eq!(s.base.supply, 1_000);
+    assert_eq!(s.base.decimals, 9);
+    assert_eq!(
+        i16::from(
+            s.get_extension::<InterestBearingConfig>()
+                .unwrap()
+                .current_rate
+        ),
+        500
+    );
+    assert_eq!(
+        Option::<Pubkey>::from(
+            s.get_extension::<MintCloseAuthority>()
+                .unwrap()
+                .close_authority
+        ),
+        Some(authority)
+    );
+    assert_eq!(
+        Option::<Pubkey>::from(s.get_extension::<PermanentDelegate>().unwrap().delegate),
+        Some(authority)
+    );
+    assert_eq!(
+        Option::<Pubkey>::from(
+            s.get_extension::<MetadataPointer>()
+                .unwrap()
+                .metadata_address
+        ),
+        Some(mint)
+    );
+    s.get_extension::<NonTransferable>().unwrap();
+    assert_eq!(
+        s.get_extension::<DefaultAccountState>().unwrap().state,
+        u8::from(AccountState::Frozen)
+    );
+    let metadata = s.get_variable_len_extension::<TokenMetadata>().unwrap();
+    assert_eq!(metadata.mint, mint);
+    assert_eq!(metadata.name, "Test Token");
+    assert_eq!(metadata.additional_metadata, [("k".into(), "v".into())]);
+
+    let token_acc = bc.get_account_unwrapped(token_acc).await;
+    assert_eq!(
+        token_acc.lamports,
+        est_rent_exempt_lamports(token_acc.data.len())
+    );
+    let s = StateWithExtensionsOwned::<Account>::unpack(token_acc.data).unwrap();
+    assert_eq!(s.base.amount, 1_000);
+    assert_eq!(s.base.state, AccountState::Frozen);
+    s.get_extension::<ImmutableOwner>().unwrap();
+    s.get_extension::<NonTransferableAccount>().unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn transfer_with_transfer_fee() {
+    const AMOUNT: u64 = 10_000;
+    const DECIMALS: u8 = 6;
+
+    let mint = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let [src, dst] = [(); 2].map(|_| Pubkey::new_unique());
+    let account_args = |amount| MockToken22AccountArgs {
+        account: MockTokenAccountArgs {
+            mint,
+            authority: owner.pubkey(),
+            amount,
+        },
+        state: AccountState::Initialized,
+        extensions: MockToken22AccountExtensions {
+            transfer_fee_withheld_amount: Some(0),
+            ..Default::default()
+        },
+    };
+    let pt = ProgramTest::default()
+        .add_token22_mint_from_args(
+            mint,
+            MockToken22MintArgs {
+                mint: MockMintArgs {
+                    mint_authority: None,
+                    freeze_authority: None,
+                    supply: AMOUNT,
+                    decimals: DECIMALS,
+                },
+                extensions: MockToken22MintExtensions {
+                    transfer_fee_config: Some(MockTransferFeeConfigArgs {
+                        transfer_fee_basis_points: 100,
+                        maximum_fee: u64::MAX,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            },
+        )
+        .add_token22_account_from_args(src, account_args(AMOUNT))
+        .add_token22_account_from_args(dst, account_args(0));
+    let (mut bc, payer, rbh) = pt.start().await;
+
+    let ix = spl_token_2022::instruction::transfer_checked(
+        &spl_token_2022::ID,
+        &src,
+        &mint,
+        &dst,
+        &owner.pubkey(),
+        &[],
+        AMOUNT,
+        DECIMALS,
+    )
+    .unwrap();
+    let tx =
+        Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[&payer, &owner], rbh);
+    bc.process_transaction(tx).await.unwrap();
+
+    let mint_acc = bc.get_account_unwrapped(mint).await;
+    let mint_state = StateWithExtensionsOwned::<Mint>::unpack(mint_acc.data).unwrap();
+    assert_eq!(
+        u16::from(
+            mint_state
+                .get_extension::<TransferFeeConfig>()
+                .unwrap()
+                .newer_transfer_fee
+                .transfer_fee_basis_points
+        ),
+        100
+    );
+
+    let dst_acc = bc.get_account_unwrapped(dst).await;
+    let dst_state = StateWithExtensionsOwned::<Account>::unpack(dst_acc.data).unwrap();
+    let expected_fee = AMOUNT / 100;
+    assert_eq!(dst_state.base.amount, AMOUNT - expected_fee);
+    assert_eq!(
+        u64::from(
+            dst_state
+                .get_extension::<TransferFeeAmount>()
+                .unwrap()
+                .withheld_amount
+        ),
+        expected_fee
+    );
+}